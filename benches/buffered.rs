@@ -0,0 +1,107 @@
+use std::io::Cursor;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use linurgy::{factory, Editor, NewlineType};
+
+/// Total input sizes, in bytes.
+const SIZES: [usize; 2] = [64 * 1024, 1024 * 1024];
+
+/// How the input is split into lines.
+#[derive(Clone, Copy)]
+enum Lines {
+    /// Lines of a few words, so newlines are dense.
+    Short,
+    /// A handful of lines, each a quarter of the input.
+    Huge,
+}
+
+impl Lines {
+    fn name(self) -> &'static str {
+        match self {
+            Lines::Short => "many short lines",
+            Lines::Huge => "few huge lines",
+        }
+    }
+
+    fn len(self, size: usize) -> usize {
+        match self {
+            Lines::Short => 16,
+            Lines::Huge => size / 4,
+        }
+    }
+}
+
+/// About `size` bytes of lines, with a blank line after every other line.
+fn input(lines: Lines, size: usize, newline: &str) -> String {
+    let line: String = "lorem ipsum "
+        .chars()
+        .cycle()
+        .take(lines.len(size))
+        .collect();
+    let mut input = String::with_capacity(size + newline.len() * 2);
+
+    for i in 0.. {
+        if input.len() >= size {
+            break;
+        }
+        input.push_str(&line);
+        input.push_str(newline);
+        if i % 2 == 1 {
+            input.push_str(newline);
+        }
+    }
+
+    input
+}
+
+fn editors(line_ending: NewlineType) -> Vec<(&'static str, Editor)> {
+    vec![
+        ("noop", factory::replacer_with("", 0, line_ending)),
+        ("replacer", factory::replacer_with(" ", 2, line_ending)),
+        ("appender", factory::appender_with("---", 2, line_ending)),
+    ]
+}
+
+/// Compare [`Editor::edit`] against [`Editor::edit_buffered`] over a
+/// [`Cursor`], for each editor, line ending, and shape of input.
+fn edit_vs_buffered(c: &mut Criterion) {
+    for &(ending, newline, line_ending) in &[
+        ("lf", "\n", NewlineType::Lf),
+        ("crlf", "\r\n", NewlineType::Crlf),
+    ] {
+        for (name, editor) in editors(line_ending) {
+            let mut group = c.benchmark_group(format!("{}/{}", name, ending));
+
+            for &lines in &[Lines::Short, Lines::Huge] {
+                for &size in &SIZES {
+                    let input = input(lines, size, newline);
+                    let param = format!("{}/{}KiB", lines.name(), size / 1024);
+                    group.throughput(Throughput::Bytes(input.len() as u64));
+
+                    group.bench_with_input(BenchmarkId::new("edit", &param), &input, |b, input| {
+                        b.iter(|| editor.edit(input))
+                    });
+
+                    group.bench_with_input(
+                        BenchmarkId::new("edit_buffered", &param),
+                        &input,
+                        |b, input| {
+                            b.iter(|| {
+                                let mut output = Vec::with_capacity(input.len());
+                                editor
+                                    .edit_buffered(&mut Cursor::new(input.as_bytes()), &mut output)
+                                    .unwrap();
+                                output
+                            })
+                        },
+                    );
+                }
+            }
+
+            group.finish();
+        }
+    }
+}
+
+criterion_group!(benches, edit_vs_buffered);
+criterion_main!(benches);
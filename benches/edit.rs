@@ -0,0 +1,34 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use linurgy::{factory, NewlineType};
+
+/// Mostly ASCII prose with paragraphs separated by blank lines.
+fn document(newline: &str) -> String {
+    let paragraph = "Incididunt sit aute laboris veniam anim non tempor. Cillum laborum id\n\
+                     minim tempor quis magna consequat labore. Quis veniam amet cupidatat\n\
+                     incididunt labore lorem eu mollit laborum elit.\n\n";
+
+    paragraph.replace('\n', newline).repeat(4096)
+}
+
+fn edit(c: &mut Criterion) {
+    let lf = document("\n");
+    let crlf = document("\r\n");
+    let ls = document("\u{2028}");
+
+    let mut group = c.benchmark_group("edit");
+    group.throughput(Throughput::Bytes(lf.len() as u64));
+
+    let editor = factory::replacer("\n", 2);
+    group.bench_function("lf", |b| b.iter(|| editor.edit(&lf)));
+
+    let editor = factory::replacer_crlf("\r\n", 2);
+    group.bench_function("crlf", |b| b.iter(|| editor.edit(&crlf)));
+
+    let editor = factory::replacer_with("\n", 2, NewlineType::LineSeparator);
+    group.bench_function("line separator", |b| b.iter(|| editor.edit(&ls)));
+
+    group.finish();
+}
+
+criterion_group!(benches, edit);
+criterion_main!(benches);
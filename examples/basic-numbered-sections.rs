@@ -0,0 +1,18 @@
+use linurgy::{Editor, NewlineType};
+
+/// Number each section break with a closure
+fn main() {
+    // the replacement string is unused, the closure provides it instead
+    let editor = Editor::new(String::new(), 2, NewlineType::Lf);
+
+    let input = "intro\n\nfirst section\n\nsecond section";
+
+    // the closure receives the index of each match, starting at 0
+    let output = editor.edit_with(input, |index| format!("\n---{}---\n", index + 1));
+
+    let expected = "intro\n---1---\nfirst section\n---2---\nsecond section";
+
+    assert_eq!(expected, output);
+
+    println!("input:\n{}\n\noutput:\n{}\n", input, output);
+}
@@ -1,4 +1,4 @@
-use std::io::{stdin, Read, Result};
+use std::io::Result;
 
 /// Edit stdin and output to stdout.
 ///
@@ -9,14 +9,11 @@ fn main() -> Result<()> {
     // appeneds "---\n" after every line
     let editor = linurgy::factory::appender("---\n", 1);
 
-    // input buffer
-    let mut input = String::new();
-
-    // read the input
-    stdin().read_to_string(&mut input)?;
+    // read and edit the input
+    let output = editor.edit_stdin_to_string()?;
 
     // write the output
-    print!("{}", editor.edit(&input));
+    print!("{}", output);
 
     Ok(())
 }
@@ -14,7 +14,7 @@ fn main() -> Result<()> {
     // create a buffer to hold the output
     let mut output_buf: Vec<u8> = Vec::new();
 
-    // `edit_buffered` returns an io::Result
+    // `edit_buffered` returns an EditError, which converts into io::Error
     editor.edit_buffered(&mut input_buf, &mut output_buf)?;
 
     // convert the output buffer to a string
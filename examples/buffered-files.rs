@@ -29,7 +29,7 @@ fn main() -> Result<()> {
     // wrap the input in a buffered reader
     let mut input_buf = BufReader::new(input_file);
 
-    // `edit_buffered` returns an io::Result
+    // `edit_buffered` returns an EditError, which converts into io::Error
     editor.edit_buffered(&mut input_buf, &mut output_file)?;
 
     // check the output
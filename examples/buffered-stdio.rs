@@ -11,9 +11,10 @@ fn main() -> Result<()> {
     // create a buffered reader over stdin
     let mut input = BufReader::new(stdin());
 
-    // `edit_buffered` returns an io::Result
+    // `edit_buffered_flushing` writes each line as soon as it is edited.
+    // It returns an EditError, which converts into io::Error
     // in a simple program like this, we can pass stdout directly
-    editor.edit_buffered(&mut input, &mut stdout())?;
+    editor.edit_buffered_flushing(&mut input, &mut stdout())?;
 
     Ok(())
 }
@@ -0,0 +1,109 @@
+use std::io;
+use std::mem;
+
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::editor::{BufState, BUFSIZE};
+use crate::{EditError, Editor, NewlineType};
+
+impl Editor {
+    /// Edit the async input buffer's newlines into the async output writer
+    ///
+    /// Input types must implement [`AsyncBufRead`].
+    /// Output types must implement [`AsyncWrite`].
+    ///
+    /// The async version of [`Editor::edit_buffered`], producing the same
+    /// output. Lines are read and written without blocking the executor.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::error::Error;
+    /// # use std::str::from_utf8;
+    /// # use tokio::io::BufReader;
+    /// # use linurgy::factory;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), Box<dyn Error>> {
+    /// let editor = factory::appender("---\n", 2);
+    /// let mut input = BufReader::new("foo\n\nbar".as_bytes());
+    /// let mut output = Vec::new();
+    /// editor.edit_buffered_async(&mut input, &mut output).await?;
+    /// assert_eq!("foo\n\n---\nbar", from_utf8(&output)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub async fn edit_buffered_async<I, O>(
+        &self,
+        input: &mut I,
+        output: &mut O,
+    ) -> Result<(), EditError>
+    where
+        I: AsyncBufRead + Unpin,
+        O: AsyncWrite + Unpin,
+    {
+        let mut state = BufState::new(self);
+        let mut buf = String::with_capacity(BUFSIZE);
+        let mut edited = String::with_capacity(BUFSIZE);
+
+        let mut lines = 0;
+
+        loop {
+            buf.clear();
+
+            let len = match self.line_ending {
+                NewlineType::Lf | NewlineType::Crlf | NewlineType::Auto => {
+                    input.read_line(&mut buf).await
+                }
+                line_ending => read_separated(input, &mut buf, line_ending.as_bytes()).await,
+            }
+            .map_err(|error| EditError::read(error, lines + 1))?;
+
+            // EOF
+            if len == 0 {
+                break;
+            }
+            lines += 1;
+
+            // edited lines are collected to make fewer, larger writes
+            state.push_line(&buf, &mut edited);
+            if edited.len() >= BUFSIZE {
+                output
+                    .write_all(edited.as_bytes())
+                    .await
+                    .map_err(EditError::WriteFailed)?;
+                edited.clear();
+            }
+        }
+
+        state.finish(&mut edited);
+        output
+            .write_all(edited.as_bytes())
+            .await
+            .map_err(EditError::WriteFailed)?;
+
+        Ok(())
+    }
+}
+
+/// Async version of the buffered reader splitting on a multi-byte separator.
+async fn read_separated<I>(input: &mut I, buf: &mut String, separator: &[u8]) -> io::Result<usize>
+where
+    I: AsyncBufRead + Unpin,
+{
+    let last = separator[separator.len() - 1];
+    let mut bytes = mem::replace(buf, String::new()).into_bytes();
+
+    loop {
+        // EOF
+        if input.read_until(last, &mut bytes).await? == 0 || bytes.ends_with(separator) {
+            break;
+        }
+    }
+
+    let len = bytes.len();
+    *buf = String::from_utf8(bytes)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+
+    Ok(len)
+}
@@ -0,0 +1,112 @@
+use std::io::{BufRead, Write};
+
+use crate::{BufEditor, EditError, Editor, NewlineType};
+
+impl<'a, I, O> BufEditor<'a, I, O>
+where
+    I: BufRead,
+    O: Write,
+{
+    /// Create a new buffered editor
+    ///
+    /// - `replace`: string to replace newlines with.
+    /// - `newlines`: number of newlines to trigger the replacement.
+    /// - `line_ending`: type of newline to use.
+    /// - `input`: buffered reader to edit.
+    /// - `output`: writer for the edited text.
+    ///
+    /// See [`Editor::new`] for details on the editing parameters.
+    #[inline]
+    pub fn new(
+        replace: String,
+        newlines: usize,
+        line_ending: NewlineType,
+        input: &'a mut I,
+        output: &'a mut O,
+    ) -> Self {
+        Self::with_editor(Editor::new(replace, newlines, line_ending), input, output)
+    }
+
+    /// Create a new buffered editor from an existing [`Editor`]
+    ///
+    /// Useful with the [`factory`](crate::factory) functions.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::error::Error;
+    /// # use std::io::Cursor;
+    /// # use std::str::from_utf8;
+    /// # use linurgy::{factory, BufEditor};
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut input = Cursor::new("foo\n\nbar");
+    /// let mut output = Vec::new();
+    ///
+    /// let editor = factory::appender("---\n", 2);
+    /// BufEditor::with_editor(editor, &mut input, &mut output).edit()?;
+    ///
+    /// assert_eq!("foo\n\n---\nbar", from_utf8(&output)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn with_editor(editor: Editor, input: &'a mut I, output: &'a mut O) -> Self {
+        BufEditor {
+            editor,
+            input,
+            output,
+        }
+    }
+
+    /// Edit the input's newlines into the output
+    ///
+    /// Reads the input until EOF. Calling this again only edits input that
+    /// has become available since the last call.
+    #[inline]
+    pub fn edit(&mut self) -> Result<(), EditError> {
+        self.editor.edit_buffered(self.input, self.output)
+    }
+
+    /// The [`Editor`] used to edit the input
+    #[inline]
+    pub fn editor(&self) -> &Editor {
+        &self.editor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::factory;
+    use std::io::Cursor;
+
+    #[test]
+    fn same_output_as_editor() {
+        let input = "foo\r\n\r\nbar\r\nbaz\r\n\r\n";
+        let editor = factory::inserter_crlf("*", 2);
+        let expected = editor.edit(input);
+
+        let mut buf_input = Cursor::new(input);
+        let mut output = Vec::new();
+        BufEditor::with_editor(editor, &mut buf_input, &mut output)
+            .edit()
+            .unwrap();
+
+        assert_eq!(expected, String::from_utf8_lossy(&output));
+    }
+
+    #[test]
+    fn edit_again_after_eof_writes_nothing() {
+        let mut input = Cursor::new("foo\nbar\n");
+        let mut output = Vec::new();
+
+        {
+            let mut editor =
+                BufEditor::new("-".to_string(), 1, NewlineType::Lf, &mut input, &mut output);
+            editor.edit().unwrap();
+            editor.edit().unwrap();
+        }
+
+        assert_eq!("foo-bar-", String::from_utf8_lossy(&output));
+    }
+}
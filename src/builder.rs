@@ -0,0 +1,678 @@
+use core::mem;
+use core::ops::RangeInclusive;
+
+use crate::factory::Factory;
+use crate::prelude::*;
+use crate::{BuildError, EditType, Editor, EditorBuilder, LineOptions, NewlineType, TriggerMode};
+
+impl EditorBuilder {
+    /// Create a builder with default options
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the text to append, insert, or replace with
+    #[inline]
+    pub fn text(&mut self, text: &str) -> &mut Self {
+        self.text.clear();
+        self.text.push_str(text);
+        self.dirty = true;
+        self
+    }
+
+    /// Set the type of edit to make when the trigger is met
+    #[inline]
+    pub fn edit_type(&mut self, edit_type: EditType) -> &mut Self {
+        self.edit_type = edit_type;
+        self.dirty = true;
+        self
+    }
+
+    /// Set the number of newlines which trigger an edit
+    #[inline]
+    pub fn newline_trigger(&mut self, newlines: usize) -> &mut Self {
+        self.newlines = newlines;
+        self.dirty = true;
+        self
+    }
+
+    /// Set the range of newlines in a run which trigger an edit
+    ///
+    /// Runs with a length in `range` are replaced as a whole, and shorter or
+    /// longer runs are kept, as with [`TriggerMode::Between`]. This sets
+    /// both the trigger and the [`TriggerMode`]: a range starting at `1`
+    /// builds an [`AtMost`](TriggerMode::AtMost) editor, and one ending at
+    /// `usize::MAX` an [`AtLeast`](TriggerMode::AtLeast) editor.
+    ///
+    /// A range which starts after it ends never triggers an edit, and is
+    /// rejected by [`EditorBuilder::try_build`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::EditorBuilder;
+    /// let editor = EditorBuilder::new()
+    ///     .text("<hr>")
+    ///     .newline_trigger_range(3..=5)
+    ///     .build();
+    ///
+    /// let output = editor.edit("a\n\nb\n\n\n\nc\n\n\n\n\n\nd");
+    /// assert_eq!("a\n\nb<hr>c\n\n\n\n\n\nd", output);
+    /// ```
+    pub fn newline_trigger_range(&mut self, range: RangeInclusive<usize>) -> &mut Self {
+        let (start, end) = range.into_inner();
+        let (newlines, mode) = if start <= 1 {
+            (end, TriggerMode::AtMost)
+        } else if end == usize::max_value() {
+            (start, TriggerMode::AtLeast)
+        } else {
+            (end, TriggerMode::Between(start))
+        };
+
+        self.newlines = newlines;
+        self.trigger_mode = mode;
+        self.dirty = true;
+        self
+    }
+
+    /// Set the type of newline to edit
+    #[inline]
+    pub fn newline_type(&mut self, line_ending: NewlineType) -> &mut Self {
+        self.line_ending = line_ending;
+        self.dirty = true;
+        self
+    }
+
+    /// Edit [`NewlineType::Crlf`] newlines
+    #[inline]
+    pub fn newline_crlf(&mut self) -> &mut Self {
+        self.newline_type(NewlineType::Crlf)
+    }
+
+    /// See [`Editor::ensure_trailing_newline`]
+    #[inline]
+    pub fn ensure_trailing_newline(&mut self, ensure: bool) -> &mut Self {
+        self.trailing_newline = ensure;
+        self
+    }
+
+    /// See [`Editor::max_edits`]
+    #[inline]
+    pub fn max_edits(&mut self, max: Option<usize>) -> &mut Self {
+        self.max_edits = max;
+        self
+    }
+
+    /// See [`Editor::skip_edits`]
+    #[inline]
+    pub fn skip_edits(&mut self, skip: usize) -> &mut Self {
+        self.skip_edits = skip;
+        self
+    }
+
+    /// See [`Editor::strip_cr`]
+    #[inline]
+    pub fn strip_cr(&mut self, strip: bool) -> &mut Self {
+        self.strip_cr = strip;
+        self
+    }
+
+    /// See [`Editor::treat_whitespace_lines_as_blank`]
+    #[inline]
+    pub fn treat_whitespace_lines_as_blank(&mut self, blank: bool) -> &mut Self {
+        self.blank_whitespace = blank;
+        self
+    }
+
+    /// See [`Editor::trigger_mode`]
+    #[inline]
+    pub fn trigger_mode(&mut self, mode: TriggerMode) -> &mut Self {
+        self.trigger_mode = mode;
+        self
+    }
+
+    /// See [`Editor::trim_trailing_whitespace`]
+    #[inline]
+    pub fn trim_trailing_whitespace(&mut self, trim: bool) -> &mut Self {
+        self.lines.trim_trailing = trim;
+        self
+    }
+
+    /// See [`Editor::strip_leading_whitespace`]
+    #[inline]
+    pub fn strip_leading_whitespace(&mut self, strip: bool) -> &mut Self {
+        self.lines.trim_leading = strip;
+        self
+    }
+
+    /// See [`Editor::trim_leading_newlines`]
+    #[inline]
+    pub fn trim_leading_newlines(&mut self, trim: bool) -> &mut Self {
+        self.lines.trim_leading_newlines = trim;
+        self
+    }
+
+    /// See [`Editor::trim_trailing_newlines`]
+    #[inline]
+    pub fn trim_trailing_newlines(&mut self, trim: bool) -> &mut Self {
+        self.lines.trim_trailing_newlines = trim;
+        self
+    }
+
+    /// See [`Editor::append_at_eof`]
+    #[inline]
+    pub fn append_at_eof(&mut self, append: bool) -> &mut Self {
+        self.lines.append_eof = append;
+        self
+    }
+
+    /// Restore every option to its default, as in [`EditorBuilder::new`]
+    ///
+    /// The builder's text and `replace` buffers are cleared rather than
+    /// dropped, so one builder can be reused for many editors without
+    /// allocating them again.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::{factory, EditorBuilder};
+    /// let mut builder = EditorBuilder::new();
+    /// builder.text("-").newline_trigger(2).max_edits(Some(1));
+    /// assert_eq!(factory::replacer("-", 2).max_edits(Some(1)), builder.build());
+    ///
+    /// builder.reset().text("+").newline_trigger(1);
+    /// assert_eq!(factory::replacer("+", 1), builder.build());
+    /// ```
+    pub fn reset(&mut self) -> &mut Self {
+        let mut text = mem::replace(&mut self.text, String::new());
+        let mut replace = mem::replace(&mut self.replace, String::new());
+        text.clear();
+        replace.clear();
+
+        *self = Self {
+            text,
+            replace,
+            dirty: true,
+            ..Self::default()
+        };
+        self
+    }
+
+    /// Build the `replace` string if any option has changed since it was last
+    /// built
+    pub fn prepare(&mut self) -> &mut Self {
+        if self.dirty {
+            self.replace.clear();
+            Factory::new(&self.text, self.newlines, self.edit_type, self.line_ending)
+                .push_replace(&mut self.replace);
+            self.dirty = false;
+        }
+
+        self
+    }
+
+    /// Create an [`Editor`] from the prepared options
+    ///
+    /// # Panics
+    ///
+    /// If an option has changed since [`EditorBuilder::prepare`] was called.
+    pub fn build_prepared(&self) -> Editor {
+        assert!(
+            !self.dirty,
+            "EditorBuilder options changed since it was prepared"
+        );
+
+        let mut editor = Editor::with_edit_type(
+            self.replace.clone(),
+            self.text.clone(),
+            self.edit_type,
+            self.newlines,
+            self.line_ending,
+        )
+        .ensure_trailing_newline(self.trailing_newline)
+        .max_edits(self.max_edits)
+        .skip_edits(self.skip_edits)
+        .strip_cr(self.strip_cr)
+        .treat_whitespace_lines_as_blank(self.blank_whitespace)
+        .trigger_mode(self.trigger_mode);
+        editor.lines = self.lines;
+        editor
+    }
+
+    /// Create an [`Editor`] from the current options
+    #[inline]
+    pub fn build(&mut self) -> Editor {
+        self.prepare().build_prepared()
+    }
+
+    /// Create an [`Editor`] from the current options, if they are valid
+    ///
+    /// Unlike [`EditorBuilder::build`], options which would make surprising
+    /// edits are rejected. Useful when the options come from configuration
+    /// rather than code.
+    ///
+    /// # Errors
+    ///
+    /// - [`BuildError::ZeroTrigger`] if the newline trigger is `0`
+    /// - [`BuildError::EmptyRange`] if the
+    ///   [range](EditorBuilder::newline_trigger_range) of newlines which
+    ///   trigger an edit starts after it ends
+    /// - [`BuildError::LoneCarriageReturn`] if the text contains a `\r`
+    ///   which is not followed by `\n`
+    /// - [`BuildError::MixedNewlines`] if the text contains a bare `\n` for
+    ///   a [`NewlineType::Crlf`] editor, or a `\r\n` for a
+    ///   [`NewlineType::Lf`] editor
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::{BuildError, EditorBuilder};
+    /// let res = EditorBuilder::new()
+    ///     .text("\n")
+    ///     .newline_trigger(2)
+    ///     .newline_crlf()
+    ///     .try_build();
+    ///
+    /// assert_eq!(Err(BuildError::MixedNewlines), res);
+    /// ```
+    pub fn try_build(&mut self) -> Result<Editor, BuildError> {
+        self.validate()?;
+        Ok(self.build())
+    }
+
+    /// Check the options for [`EditorBuilder::try_build`].
+    fn validate(&self) -> Result<(), BuildError> {
+        if self.newlines == 0 {
+            return Err(BuildError::ZeroTrigger);
+        }
+
+        if let TriggerMode::Between(start) = self.trigger_mode {
+            if start > self.newlines {
+                return Err(BuildError::EmptyRange);
+            }
+        }
+
+        let text = self.text.as_str();
+        if text
+            .split('\r')
+            .skip(1)
+            .any(|after| !after.starts_with('\n'))
+        {
+            return Err(BuildError::LoneCarriageReturn);
+        }
+
+        let mixed = match self.line_ending {
+            NewlineType::Lf => text.contains('\r'),
+            NewlineType::Crlf => text
+                .match_indices('\n')
+                .any(|(index, _)| !text[..index].ends_with('\r')),
+            _ => false,
+        };
+        if mixed {
+            return Err(BuildError::MixedNewlines);
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for EditorBuilder {
+    /// Builds [`Editor::default`]
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            edit_type: EditType::Replace,
+            newlines: 0,
+            line_ending: NewlineType::Lf,
+            trailing_newline: false,
+            max_edits: None,
+            skip_edits: 0,
+            strip_cr: false,
+            blank_whitespace: false,
+            trigger_mode: TriggerMode::Exactly,
+            lines: LineOptions::default(),
+            replace: String::new(),
+            dirty: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::factory;
+
+    #[test]
+    fn new_builds_default_editor() {
+        assert_eq!(Editor::default(), EditorBuilder::new().build());
+    }
+
+    #[test]
+    fn matches_factory_lf() {
+        let mut builder = EditorBuilder::new();
+        builder.text("--").newline_trigger(2);
+
+        builder.edit_type(EditType::Append);
+        assert_eq!(factory::appender("--", 2), builder.build());
+
+        builder.edit_type(EditType::Insert);
+        assert_eq!(factory::inserter("--", 2), builder.build());
+
+        builder.edit_type(EditType::Replace);
+        assert_eq!(factory::replacer("--", 2), builder.build());
+    }
+
+    #[test]
+    fn matches_factory_crlf() {
+        let mut builder = EditorBuilder::new();
+        builder.text("\t").newline_trigger(1).newline_crlf();
+
+        builder.edit_type(EditType::Append);
+        assert_eq!(factory::appender_crlf("\t", 1), builder.build());
+
+        builder.edit_type(EditType::Insert);
+        assert_eq!(factory::inserter_crlf("\t", 1), builder.build());
+
+        builder.edit_type(EditType::Replace);
+        assert_eq!(factory::replacer_crlf("\t", 1), builder.build());
+    }
+
+    #[test]
+    fn reset_builds_default_editor() {
+        let mut builder = EditorBuilder::new();
+        builder
+            .text("---")
+            .edit_type(EditType::Append)
+            .newline_trigger(3)
+            .newline_crlf()
+            .ensure_trailing_newline(true)
+            .max_edits(Some(2))
+            .skip_edits(1)
+            .strip_cr(true)
+            .treat_whitespace_lines_as_blank(true)
+            .trigger_mode(TriggerMode::AtLeast)
+            .build();
+
+        let capacity = builder.replace.capacity();
+        builder.reset();
+
+        assert!(builder.dirty);
+        assert_eq!(capacity, builder.replace.capacity());
+        assert_eq!(EditorBuilder::new().build(), builder.build());
+    }
+
+    #[test]
+    fn matches_factory_trigger_mode() {
+        let mut builder = EditorBuilder::new();
+        builder.text("-").newline_trigger(2);
+
+        builder.trigger_mode(TriggerMode::AtLeast);
+        assert_eq!(factory::replacer_at_least("-", 2), builder.build());
+
+        builder.trigger_mode(TriggerMode::AtMost);
+        assert_eq!(factory::replacer_at_most("-", 2), builder.build());
+    }
+
+    #[test]
+    fn options_set_in_any_order() {
+        let editor = EditorBuilder::new()
+            .newline_crlf()
+            .newline_trigger(3)
+            .edit_type(EditType::Append)
+            .text("x")
+            .build();
+
+        assert_eq!(factory::appender_crlf("x", 3), editor);
+    }
+
+    #[test]
+    fn ensure_trailing_newline() {
+        let editor = EditorBuilder::new()
+            .text("-")
+            .newline_trigger(1)
+            .ensure_trailing_newline(true)
+            .build();
+
+        assert_eq!(
+            factory::replacer("-", 1).ensure_trailing_newline(true),
+            editor
+        );
+    }
+
+    #[test]
+    fn max_edits() {
+        let editor = EditorBuilder::new()
+            .text("-")
+            .newline_trigger(1)
+            .max_edits(Some(2))
+            .build();
+
+        assert_eq!(factory::replacer("-", 1).max_edits(Some(2)), editor);
+    }
+
+    #[test]
+    fn skip_edits() {
+        let editor = EditorBuilder::new()
+            .text("-")
+            .newline_trigger(1)
+            .skip_edits(3)
+            .build();
+
+        assert_eq!(factory::replacer("-", 1).skip_edits(3), editor);
+    }
+
+    #[test]
+    fn strip_cr() {
+        let editor = EditorBuilder::new()
+            .text("-")
+            .newline_trigger(1)
+            .strip_cr(true)
+            .build();
+
+        assert_eq!(factory::replacer("-", 1).strip_cr(true), editor);
+    }
+
+    #[test]
+    fn treat_whitespace_lines_as_blank() {
+        let editor = EditorBuilder::new()
+            .text("-")
+            .newline_trigger(2)
+            .treat_whitespace_lines_as_blank(true)
+            .build();
+
+        assert_eq!(
+            factory::replacer("-", 2).treat_whitespace_lines_as_blank(true),
+            editor
+        );
+    }
+
+    #[test]
+    fn trim_trailing_whitespace() {
+        let editor = EditorBuilder::new()
+            .text("-")
+            .newline_trigger(2)
+            .trim_trailing_whitespace(true)
+            .build();
+
+        assert_eq!(
+            factory::replacer("-", 2).trim_trailing_whitespace(true),
+            editor
+        );
+    }
+
+    #[test]
+    fn strip_leading_whitespace() {
+        let editor = EditorBuilder::new()
+            .text("-")
+            .newline_trigger(2)
+            .strip_leading_whitespace(true)
+            .build();
+
+        assert_eq!(
+            factory::replacer("-", 2).strip_leading_whitespace(true),
+            editor
+        );
+    }
+
+    #[test]
+    fn trim_newlines() {
+        let editor = EditorBuilder::new()
+            .text("-")
+            .newline_trigger(2)
+            .trim_leading_newlines(true)
+            .trim_trailing_newlines(true)
+            .build();
+
+        let expected = factory::replacer("-", 2)
+            .trim_leading_newlines(true)
+            .trim_trailing_newlines(true);
+        assert_eq!(expected, editor);
+        assert_eq!("foo-bar", editor.edit("\n\nfoo\n\nbar\n"));
+    }
+
+    #[test]
+    fn append_at_eof() {
+        let editor = EditorBuilder::new()
+            .text(";")
+            .edit_type(EditType::Append)
+            .newline_trigger(1)
+            .append_at_eof(true)
+            .build();
+
+        assert_eq!(factory::appender(";", 1).append_at_eof(true), editor);
+        assert_eq!("foo\n;bar;", editor.edit("foo\nbar"));
+    }
+
+    #[test]
+    fn build_prepared_reuses_replace() {
+        let mut builder = EditorBuilder::new();
+        builder.text("-").newline_trigger(2).prepare();
+
+        let first = builder.build_prepared();
+        let second = builder.build_prepared();
+
+        assert_eq!(first, second);
+        assert_eq!(factory::replacer("-", 2), first);
+    }
+
+    #[test]
+    fn try_build_valid() {
+        let editor = EditorBuilder::new()
+            .text("\r\n-")
+            .newline_trigger(2)
+            .newline_crlf()
+            .try_build();
+
+        assert_eq!(Ok(factory::replacer_crlf("\r\n-", 2)), editor);
+    }
+
+    #[test]
+    fn try_build_zero_trigger() {
+        let res = EditorBuilder::new().text("-").try_build();
+        assert_eq!(Err(BuildError::ZeroTrigger), res);
+    }
+
+    #[test]
+    fn try_build_lone_cr() {
+        let mut builder = EditorBuilder::new();
+        builder.text("a\rb").newline_trigger(1);
+        assert_eq!(Err(BuildError::LoneCarriageReturn), builder.try_build());
+
+        builder.text("-\r").newline_crlf();
+        assert_eq!(Err(BuildError::LoneCarriageReturn), builder.try_build());
+    }
+
+    #[test]
+    fn try_build_crlf_in_lf_text() {
+        let res = EditorBuilder::new()
+            .text("\r\n")
+            .newline_trigger(1)
+            .try_build();
+
+        assert_eq!(Err(BuildError::MixedNewlines), res);
+    }
+
+    #[test]
+    fn try_build_lf_in_crlf_text() {
+        let res = EditorBuilder::new()
+            .text("\r\n\n")
+            .newline_trigger(1)
+            .newline_crlf()
+            .try_build();
+
+        assert_eq!(Err(BuildError::MixedNewlines), res);
+    }
+
+    #[test]
+    fn try_build_separator_allows_lf() {
+        let res = EditorBuilder::new()
+            .text("\n")
+            .newline_trigger(1)
+            .newline_type(NewlineType::LineSeparator)
+            .try_build();
+
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn newline_trigger_range() {
+        let mut builder = EditorBuilder::new();
+        builder.text("-").newline_trigger_range(2..=3);
+        assert_eq!(
+            factory::replacer("-", 3).trigger_mode(TriggerMode::Between(2)),
+            builder.build()
+        );
+
+        builder.newline_trigger_range(1..=3);
+        assert_eq!(factory::replacer_at_most("-", 3), builder.build());
+
+        builder.newline_trigger_range(2..=usize::max_value());
+        assert_eq!(factory::replacer_at_least("-", 2), builder.build());
+    }
+
+    #[test]
+    fn newline_trigger_range_edits_whole_runs() {
+        let editor = EditorBuilder::new()
+            .text("-")
+            .newline_trigger_range(2..=3)
+            .try_build()
+            .unwrap();
+
+        assert_eq!(
+            "a\nb-c-d\n\n\n\ne",
+            editor.edit("a\nb\n\nc\n\n\nd\n\n\n\ne")
+        );
+    }
+
+    #[test]
+    fn newline_trigger_range_single_length() {
+        let editor = EditorBuilder::new()
+            .text("-")
+            .newline_trigger_range(2..=2)
+            .build();
+
+        assert_eq!("a-b\n\n\n\nc", editor.edit("a\n\nb\n\n\n\nc"));
+    }
+
+    #[test]
+    fn try_build_empty_range() {
+        let (start, end) = (4, 3);
+        let mut builder = EditorBuilder::new();
+        builder.text("-").newline_trigger_range(start..=end);
+        assert_eq!(Err(BuildError::EmptyRange), builder.try_build());
+
+        // never met when built anyway
+        let editor = builder.build();
+        assert!(editor.is_noop());
+        assert_eq!("a\n\n\nb", editor.edit("a\n\n\nb"));
+    }
+
+    #[test]
+    #[should_panic(expected = "changed since it was prepared")]
+    fn build_prepared_after_change_panics() {
+        let mut builder = EditorBuilder::new();
+        builder.prepare().text("-");
+        builder.build_prepared();
+    }
+}
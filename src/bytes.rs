@@ -0,0 +1,526 @@
+#[cfg(feature = "bytes")]
+use ::bytes::BytesMut;
+use core::str;
+#[cfg(all(unix, feature = "std"))]
+use std::ffi::{OsStr, OsString};
+#[cfg(feature = "std")]
+use std::io::{BufRead, Write};
+#[cfg(all(unix, feature = "std"))]
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+
+use crate::editor::BufState;
+#[cfg(feature = "std")]
+use crate::editor::{copy_buffered, read_line_bytes, BUFSIZE};
+use crate::prelude::*;
+#[cfg(feature = "std")]
+use crate::EditError;
+use crate::Editor;
+
+impl Editor {
+    /// Edit the newlines of input which may not be valid UTF-8
+    ///
+    /// Produces the same output as [`Editor::edit`] for valid UTF-8. Any
+    /// invalid bytes are treated as text which is not a newline: they end a
+    /// run of newlines and are copied to the output unchanged. Like any
+    /// other text, they are part of the line they are in, and are never
+    /// trimmed as whitespace. Nothing is lost by converting the input to a
+    /// [`String`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::replacer(" ", 1);
+    /// assert_eq!(b"f\xffo o", &editor.edit_bytes(b"f\xffo\no")[..]);
+    /// ```
+    pub fn edit_bytes(&self, input: &[u8]) -> Vec<u8> {
+        if let Ok(input) = str::from_utf8(input) {
+            return self.edit(input).into_bytes();
+        }
+
+        if self.is_noop() {
+            return input.to_vec();
+        }
+
+        let editor = self.resolve(&String::from_utf8_lossy(input));
+
+        let mut state = BufState::new(&editor);
+        let mut edited = String::new();
+        let mut output = Vec::with_capacity(input.len());
+
+        let mut newline = [0; 4];
+        let newline = state.line_end().encode_utf8(&mut newline).as_bytes();
+        let mut rest = input;
+
+        while !rest.is_empty() {
+            let end = rest
+                .windows(newline.len())
+                .position(|window| window == newline)
+                .map_or(rest.len(), |index| index + newline.len());
+            push_bytes(&mut state, &rest[..end], &mut edited, &mut output);
+            rest = &rest[end..];
+        }
+
+        state.finish(&mut edited);
+        output.extend_from_slice(edited.as_bytes());
+
+        output
+    }
+
+    /// Edit the input buffer's newlines into the output writer, allowing
+    /// invalid UTF-8
+    ///
+    /// Writes the same output as [`Editor::edit_buffered`] for valid UTF-8.
+    /// Invalid bytes are edited as [`Editor::edit_bytes`] edits them: they
+    /// end a run of newlines and are copied to the output unchanged, rather
+    /// than stopping the edit with [`EditError::InvalidUtf8`]. Useful for
+    /// logs and other files with the odd corrupt line.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EditError`] if reading the input or writing the output
+    /// fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::{factory, EditError};
+    /// # fn main() -> Result<(), EditError> {
+    /// let editor = factory::replacer("\n", 2);
+    /// let mut output = Vec::new();
+    /// editor.edit_buffered_bytes(&mut &b"foo\n\nb\xffr\n\nbaz"[..], &mut output)?;
+    /// assert_eq!(&b"foo\nb\xffr\nbaz"[..], &output[..]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn edit_buffered_bytes<I, O>(&self, input: &mut I, output: &mut O) -> Result<(), EditError>
+    where
+        I: BufRead,
+        O: Write,
+    {
+        if self.is_noop() {
+            copy_buffered(input, output, false)?;
+            return Ok(());
+        }
+
+        let mut state = BufState::new(self);
+        let mut buf = Vec::with_capacity(BUFSIZE);
+        let mut edited = String::with_capacity(BUFSIZE);
+        let mut bytes = Vec::with_capacity(BUFSIZE);
+
+        // edited lines are collected to make fewer, larger writes
+        while read_line_bytes(input, &mut buf, self.line_ending())? {
+            push_bytes(&mut state, &buf, &mut edited, &mut bytes);
+            if bytes.len() >= BUFSIZE {
+                output.write_all(&bytes).map_err(EditError::WriteFailed)?;
+                bytes.clear();
+            }
+        }
+
+        state.finish(&mut edited);
+        bytes.extend_from_slice(edited.as_bytes());
+        output.write_all(&bytes).map_err(EditError::WriteFailed)?;
+
+        Ok(())
+    }
+
+    /// Edit the newlines of an [`OsStr`], such as a list of paths
+    ///
+    /// The raw bytes of the input are edited with [`Editor::edit_bytes`],
+    /// so paths which are not valid UTF-8 are kept exactly as they are.
+    /// Only available on Unix, where an [`OsStr`] is made of bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::ffi::OsStr;
+    /// # use std::os::unix::ffi::OsStrExt;
+    /// # use linurgy::factory;
+    /// let paths = OsStr::from_bytes(b"./foo\n./b\xe4r\n");
+    /// let output = factory::replacer("\0", 1).edit_os(paths);
+    /// assert_eq!(b"./foo\0./b\xe4r\0", output.as_bytes());
+    /// ```
+    #[cfg(all(unix, feature = "std"))]
+    #[cfg_attr(docsrs, doc(cfg(all(unix, feature = "std"))))]
+    pub fn edit_os(&self, input: &OsStr) -> OsString {
+        OsString::from_vec(self.edit_bytes(input.as_bytes()))
+    }
+
+    /// Edit the newlines of a [`Bytes`](::bytes::Bytes) buffer into a
+    /// [`BytesMut`]
+    ///
+    /// Produces the same bytes as [`Editor::edit_bytes`], so input which is
+    /// not valid UTF-8 is kept as it is. Valid UTF-8 is edited straight into
+    /// the new buffer, without an intermediate [`String`]. Any buffer which
+    /// derefs to `[u8]` can be edited, including a [`BytesMut`]. Useful
+    /// inside a codec.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bytes::Bytes;
+    /// # use linurgy::factory;
+    /// let input = Bytes::from_static(b"foo\n\nbar\n");
+    /// let output = factory::replacer("\n", 2).edit_bytes_buf(&input);
+    /// assert_eq!(&b"foo\nbar\n"[..], &output[..]);
+    /// ```
+    #[cfg(feature = "bytes")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bytes")))]
+    pub fn edit_bytes_buf(&self, input: &[u8]) -> BytesMut {
+        let text = match str::from_utf8(input) {
+            Ok(text) => text,
+            Err(_) => return BytesMut::from(&self.edit_bytes(input)[..]),
+        };
+
+        let mut output = BytesMut::with_capacity(input.len() + self.replacement().len());
+        self.edit_fmt(text, &mut output)
+            .expect("a BytesMut grows to fit anything written to it");
+
+        output
+    }
+}
+
+/// Edit a line which may not be valid UTF-8, pushing the result onto the end
+/// of `output`.
+///
+/// Invalid sequences are text inside the line, so they are never trimmed as
+/// whitespace and never end it. Each is edited as a character which is found
+/// nowhere else in the line or the editor's text, and swapped back for the
+/// invalid bytes in the output. `edited` is used to collect the edited text,
+/// and is left empty.
+fn push_bytes(state: &mut BufState, line: &[u8], edited: &mut String, output: &mut Vec<u8>) {
+    let mut parts = Vec::new();
+    let mut rest = line;
+    loop {
+        let (valid, invalid) = split_invalid(rest);
+        parts.push((valid, invalid));
+        if invalid.is_empty() {
+            break;
+        }
+        rest = &rest[valid.len() + invalid.len()..];
+    }
+
+    if let [(valid, _)] = parts[..] {
+        state.push_line(valid, edited);
+        output.extend_from_slice(edited.as_bytes());
+        edited.clear();
+        return;
+    }
+
+    let placeholder = placeholder(state.editor(), &parts);
+    let mut text = String::with_capacity(line.len());
+    for &(valid, invalid) in &parts {
+        text.push_str(valid);
+        if !invalid.is_empty() {
+            text.push(placeholder);
+        }
+    }
+
+    state.push_line(&text, edited);
+
+    // the placeholders are only ever written as part of the line's text, so
+    // they come out in the order they went in
+    let mut invalid = parts.iter().map(|&(_, invalid)| invalid);
+    for (index, text) in edited.split(placeholder).enumerate() {
+        if index > 0 {
+            let bytes = invalid
+                .next()
+                .expect("each placeholder is an invalid sequence");
+            output.extend_from_slice(bytes);
+        }
+        output.extend_from_slice(text.as_bytes());
+    }
+    edited.clear();
+}
+
+/// A private use character which is in none of the valid `parts` of a line
+/// or the text `editor` writes, to edit in place of invalid UTF-8.
+fn placeholder(editor: &Editor, parts: &[(&str, &[u8])]) -> char {
+    let fallback = editor.fallback.as_ref().map_or("", String::as_str);
+    let texts = [
+        editor.replace.as_str(),
+        editor.text.as_str(),
+        editor.open.as_str(),
+        editor.close.as_str(),
+        fallback,
+    ];
+
+    (0xe000..=0xf8ff)
+        .chain(0xf_0000..=0xf_fffd)
+        .filter_map(core::char::from_u32)
+        .find(|&c| {
+            !texts.iter().any(|text| text.contains(c))
+                && !parts.iter().any(|&(valid, _)| valid.contains(c))
+        })
+        .expect("no line uses every private use character")
+}
+
+/// Split the input into its leading valid UTF-8, and the invalid sequence
+/// after it.
+fn split_invalid(input: &[u8]) -> (&str, &[u8]) {
+    match str::from_utf8(input) {
+        Ok(valid) => (valid, &[]),
+        Err(error) => {
+            let (valid, rest) = input.split_at(error.valid_up_to());
+            let len = error.error_len().unwrap_or(rest.len());
+            let valid = str::from_utf8(valid).expect("bytes before the error are valid");
+            (valid, &rest[..len])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{factory, NewlineType};
+    #[cfg(feature = "bytes")]
+    use ::bytes::Bytes;
+
+    #[test]
+    fn valid_utf8_same_as_edit() {
+        let editor = factory::appender("-", 2);
+        let input = "foo\n\nbar\n\n\nbaz";
+        assert_eq!(
+            editor.edit(input).as_bytes(),
+            &editor.edit_bytes(input.as_bytes())[..]
+        );
+    }
+
+    #[test]
+    fn invalid_bytes_kept() {
+        let editor = factory::replacer("-", 2);
+        let output = editor.edit_bytes(b"\xff\n\n\xfe\xfd\nb\xc3");
+        assert_eq!(&b"\xff-\xfe\xfd\nb\xc3"[..], &output[..]);
+    }
+
+    #[test]
+    fn invalid_bytes_end_runs() {
+        let editor = factory::replacer("-", 2);
+        let output = editor.edit_bytes(b"a\n\xff\nb\n\n");
+        assert_eq!(&b"a\n\xff\nb-"[..], &output[..]);
+    }
+
+    #[test]
+    fn options_across_invalid_bytes() {
+        let editor = factory::wrap_crlf("<", ">", 1)
+            .max_edits(Some(2))
+            .ensure_trailing_newline(true);
+        let output = editor.edit_bytes(b"\xff\r\n\xff\r\n\xff\r\n\r\n");
+        assert_eq!(&b"<\xff>\r\n<\xff>\r\n<\xff\r\n\r\n>\r\n"[..], &output[..]);
+    }
+
+    /// The text with every `x` replaced by an invalid byte.
+    fn invalid(text: &str) -> Vec<u8> {
+        text.bytes()
+            .map(|byte| if byte == b'x' { 0xff } else { byte })
+            .collect()
+    }
+
+    /// Assert every way of editing bytes edits `input` into `expected`.
+    fn assert_bytes(expected: &[u8], editor: &Editor, input: &[u8]) {
+        let message = format!("\neditor: {}\ninput: {:?}\n", editor, input);
+        assert_eq!(expected, &editor.edit_bytes(input)[..], "bytes{}", message);
+
+        #[cfg(all(unix, feature = "std"))]
+        {
+            let output = editor.edit_os(OsStr::from_bytes(input));
+            assert_eq!(expected, output.as_bytes(), "os{}", message);
+        }
+
+        #[cfg(feature = "std")]
+        for &capacity in &[1, 3, 1024] {
+            let output = edit_buffered_bytes(editor, input, capacity);
+            assert_eq!(expected, &output[..], "buffered {}{}", capacity, message);
+        }
+
+        #[cfg(feature = "bytes")]
+        {
+            let output = editor.edit_bytes_buf(&Bytes::copy_from_slice(input));
+            assert_eq!(expected, &output[..], "bytes buf{}", message);
+        }
+    }
+
+    #[test]
+    fn invalid_bytes_edited_as_text() {
+        let editors = [
+            factory::appender("-", 2).ensure_trailing_newline(true),
+            factory::line_prefixer("> "),
+            factory::replacer_crlf(" ", 1).strip_cr(true),
+            factory::replacer("", 2).treat_whitespace_lines_as_blank(true),
+        ];
+        let input = "\n\nx\n\nx\r\n \nx\r\n\n\nfoo x\n\n";
+
+        // the editors don't add any `x`, so they mark the invalid bytes
+        for editor in &editors {
+            let expected = invalid(&editor.edit(input));
+            let actual = editor.edit_bytes(&invalid(input));
+            assert_eq!(expected, actual, "\neditor: {}\n", editor);
+        }
+    }
+
+    #[test]
+    fn line_options_around_invalid_bytes() {
+        let editors = [
+            factory::inserter("", 1).treat_whitespace_lines_as_blank(true),
+            factory::replacer("-", 1).trim_trailing_whitespace(true),
+            factory::replacer("-", 1).strip_leading_whitespace(true),
+            factory::replacer("-", 2).trim_leading_newlines(true),
+            factory::replacer("-", 2).trim_trailing_newlines(true),
+            factory::appender(";", 1).append_at_eof(true),
+        ];
+        let input = "\n\n x \n\t\n\nax \t\nx  b\n x\n \t x\t\n\n\n";
+
+        for editor in &editors {
+            let expected = invalid(&editor.edit(input));
+            assert_bytes(&expected, editor, &invalid(input));
+        }
+    }
+
+    #[test]
+    fn whitespace_around_invalid_bytes_not_blank() {
+        let editor = factory::inserter("", 1).treat_whitespace_lines_as_blank(true);
+        assert_bytes(b" \xff \n\n", &editor, b" \xff \n\n");
+    }
+
+    #[test]
+    fn trim_trailing_whitespace_around_invalid_bytes() {
+        let editor = factory::inserter("", 1).trim_trailing_whitespace(true);
+        assert_bytes(b"a \xffb\n", &editor, b"a \xffb\n");
+        assert_bytes(b"a\xff\n\xfe", &editor, b"a\xff \t\n\xfe  ");
+        assert_bytes(b"\t\xff\n\n", &editor, b"\t\xff\t\n \n");
+
+        let editor = factory::inserter_crlf("", 1).trim_trailing_whitespace(true);
+        assert_bytes(b"\xff \xfe\r\n", &editor, b"\xff \xfe \r\n");
+    }
+
+    #[test]
+    fn strip_leading_whitespace_around_invalid_bytes() {
+        let editor = factory::inserter("", 1).strip_leading_whitespace(true);
+        assert_bytes(b"a\xff  b\n", &editor, b"a\xff  b\n");
+        assert_bytes(b"\xff\n\xfe \n", &editor, b" \t\xff\n  \xfe \n");
+        assert_bytes(b"\n\xff\t", &editor, b"\t\n \xff\t");
+
+        let editor = factory::inserter_crlf("", 1).strip_leading_whitespace(true);
+        assert_bytes(b"\xff \xfe\r\n", &editor, b" \xff \xfe\r\n");
+    }
+
+    #[test]
+    fn crlf_around_invalid_bytes() {
+        let editor = factory::replacer_crlf(" ", 1);
+        let output = editor.edit_bytes(b"a\r\xff\nb\r\n\xff");
+        assert_eq!(&b"a\r\xff b \xff"[..], &output[..]);
+    }
+
+    #[test]
+    fn auto_detects_around_invalid_bytes() {
+        let editor = factory::replacer_with(" ", 2, NewlineType::Auto);
+        let output = editor.edit_bytes(b"\xff\r\n\r\n\xff\r\n\r\n");
+        assert_eq!(&b"\xff \xff "[..], &output[..]);
+    }
+
+    #[test]
+    fn noop_copies_input() {
+        let input = b"\xff\n\n\xfe";
+        assert_eq!(&input[..], &Editor::default().edit_bytes(input)[..]);
+    }
+
+    #[cfg(feature = "std")]
+    fn edit_buffered_bytes(editor: &Editor, input: &[u8], capacity: usize) -> Vec<u8> {
+        let mut input = std::io::BufReader::with_capacity(capacity, input);
+        let mut output = Vec::new();
+        editor.edit_buffered_bytes(&mut input, &mut output).unwrap();
+        output
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn buffered_invalid_line_edits_newlines_around_it() {
+        let editor = factory::replacer("-", 2);
+        let input = b"foo\n\nb\xffr\n\n\xfe\n\nbaz";
+
+        for capacity in 1..8 {
+            let output = edit_buffered_bytes(&editor, input, capacity);
+            assert_eq!(&b"foo-b\xffr-\xfe-baz"[..], &output[..]);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn buffered_same_as_edit_bytes() {
+        let editors = [
+            factory::appender("-", 2).ensure_trailing_newline(true),
+            factory::wrap_crlf("<", ">", 1).max_edits(Some(2)),
+            factory::replacer_crlf(" ", 1).strip_cr(true),
+            factory::replacer("", 2).treat_whitespace_lines_as_blank(true),
+            factory::replacer_with(" ", 1, NewlineType::LineSeparator),
+            factory::replacer_at_least("-", 2),
+            Editor::default(),
+        ];
+        let input = b"\xff\n\n\xe2\x80\n\r\n\xff\r\n \n\xc3\n\xe2\x80\xa8\xff\n\n\nb\xc3";
+
+        for editor in &editors {
+            let expected = editor.edit_bytes(input);
+            for &capacity in &[1, 3, 1024] {
+                let actual = edit_buffered_bytes(editor, input, capacity);
+                assert_eq!(
+                    expected, actual,
+                    "\neditor: {}\ncapacity: {}\n",
+                    editor, capacity
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn buffered_valid_utf8_same_as_edit_buffered() {
+        let editor = factory::inserter_crlf(";", 1).ensure_trailing_newline(true);
+        let input = "foo\r\nbar\n\r\n\r\nbaz\r".repeat(200);
+
+        let mut expected = Vec::new();
+        editor
+            .edit_buffered(&mut input.as_bytes(), &mut expected)
+            .unwrap();
+
+        assert_eq!(expected, edit_buffered_bytes(&editor, input.as_bytes(), 16));
+    }
+
+    #[cfg(all(unix, feature = "std"))]
+    #[test]
+    fn os_paths() {
+        let paths = OsStr::from_bytes(b"./caf\xe9\r\n./foo\r\n");
+        let output = factory::normalizer(NewlineType::Crlf, NewlineType::Lf).edit_os(paths);
+        assert_eq!(&b"./caf\xe9\n./foo\n"[..], output.as_bytes());
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn bytes_buf() {
+        let editor = factory::appender_crlf("---", 2);
+        let input = Bytes::from("foo\r\n\r\nbar\nbaz\r\n\r\n");
+
+        let output = editor.edit_bytes_buf(&input);
+
+        assert_eq!(&b"foo\r\n\r\n---bar\r\nbaz\r\n\r\n---"[..], &output[..]);
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn bytes_buf_same_as_edit_bytes() {
+        let editors = [
+            factory::replacer(" ", 1).ensure_trailing_newline(true),
+            factory::wrap_with("<", ">", 1, NewlineType::Auto),
+            Editor::default(),
+        ];
+        let inputs: [&[u8]; 4] = [b"", b"a\nb\n\n", b"a\r\n\r\nb\n", b"a\n\xff\nb"];
+
+        for editor in &editors {
+            for &input in &inputs {
+                let output = editor.edit_bytes_buf(&Bytes::copy_from_slice(input));
+                assert_eq!(&editor.edit_bytes(input)[..], &output[..], "{}", editor);
+            }
+        }
+    }
+}
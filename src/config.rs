@@ -0,0 +1,138 @@
+/*!
+Read a list of [`Editor`]s from a config file, so editing can be changed without recompiling.
+
+Each line of a config is one editor in the form `edit_type:trigger:text`, parsed exactly as
+[`Editor::from_str`](std::str::FromStr::from_str). Lines starting with `#`, after any spaces or
+tabs, are comments. Blank lines are ignored. The editors are returned in the order they appear,
+ready to run one after another in a [`Pipeline`](crate::Pipeline).
+
+# Examples
+
+```rust
+# use std::error::Error;
+# use linurgy::{config, Pipeline};
+# fn main() -> Result<(), Box<dyn Error>> {
+let text = "\
+## join paragraphs, then end lines with a semicolon
+replace:2: |
+
+insert:1:;
+";
+
+let editors = config::parse(text.as_bytes())?;
+let pipeline = Pipeline::new(editors);
+assert_eq!("foo |bar;\nbaz", pipeline.edit("foo\n\nbar\nbaz"));
+# Ok(())
+# }
+```
+*/
+use std::io::BufRead;
+
+use crate::{ConfigError, Editor};
+
+/// Parse every editor in the config read from `reader`
+///
+/// Stops at the first line which cannot be read or is not a valid editor,
+/// returning an error with its line number.
+///
+/// # Examples
+///
+/// ```rust
+/// # use linurgy::config;
+/// let err = config::parse("append:2:-\nprepend:1:-\n".as_bytes()).unwrap_err();
+/// assert_eq!(Some(2), err.line());
+/// ```
+pub fn parse<R>(reader: R) -> Result<Vec<Editor>, ConfigError>
+where
+    R: BufRead,
+{
+    let mut editors = Vec::new();
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.map_err(ConfigError::Io)?;
+        let spec = line.trim_end_matches('\r');
+
+        let start = spec.trim_start_matches(|c| c == ' ' || c == '\t');
+        if start.is_empty() || start.starts_with('#') {
+            continue;
+        }
+
+        let editor = spec.parse().map_err(|error| ConfigError::InvalidEditor {
+            line: index + 1,
+            error,
+        })?;
+        editors.push(editor);
+    }
+
+    Ok(editors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{factory, ParseEditorError};
+
+    #[test]
+    fn comments_and_blank_lines() {
+        let text = "# header\n\nappend:2:---\n  # indented comment\n\t\nreplace:1: :crlf\n";
+
+        let editors = parse(text.as_bytes()).unwrap();
+
+        assert_eq!(
+            vec![factory::appender("---", 2), factory::replacer_crlf(" ", 1)],
+            editors
+        );
+    }
+
+    #[test]
+    fn crlf_config() {
+        let text = "# header\r\ninsert:1:;\r\n\r\nreplace:3:\r\n";
+
+        let editors = parse(text.as_bytes()).unwrap();
+
+        assert_eq!(
+            vec![factory::inserter(";", 1), factory::replacer("", 3)],
+            editors
+        );
+    }
+
+    #[test]
+    fn text_keeps_spaces_and_hashes() {
+        let editors = parse("append:1: # \n".as_bytes()).unwrap();
+        assert_eq!(vec![factory::appender(" # ", 1)], editors);
+    }
+
+    #[test]
+    fn empty_config() {
+        assert!(parse("".as_bytes()).unwrap().is_empty());
+        assert!(parse("# nothing\n\n".as_bytes()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn invalid_editor_reports_line() {
+        let text = "# header\nappend:2:-\n\nappend:x:-\n";
+
+        match parse(text.as_bytes()) {
+            Err(ConfigError::InvalidEditor {
+                line: 4,
+                error: ParseEditorError::InvalidTrigger(_),
+            }) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn invalid_utf8_is_io_error() {
+        let err = parse(&b"append:1:\xff\n"[..]).unwrap_err();
+
+        assert!(matches_io(&err));
+        assert_eq!(None, err.line());
+    }
+
+    fn matches_io(err: &ConfigError) -> bool {
+        match err {
+            ConfigError::Io(_) => true,
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use crate::prelude::*;
+use crate::{ConstEditor, EditType, Editor, NewlineType};
+
+impl ConstEditor {
+    /// Create a new editor at compile time
+    ///
+    /// Replaces runs of exactly `newlines` newlines with `replace`, the same
+    /// as [`Editor::new`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::{ConstEditor, NewlineType};
+    /// const SPACES: ConstEditor = ConstEditor::new(" ", 1, NewlineType::Crlf);
+    /// assert_eq!("foo bar", SPACES.edit("foo\r\nbar"));
+    /// ```
+    #[inline]
+    pub const fn new(replace: &'static str, newlines: usize, line_ending: NewlineType) -> Self {
+        ConstEditor {
+            replace,
+            newlines,
+            line_ending,
+        }
+    }
+
+    /// The string newlines are replaced with
+    #[inline]
+    pub const fn replacement(&self) -> &'static str {
+        self.replace
+    }
+
+    /// The number of newlines which trigger an edit
+    #[inline]
+    pub const fn trigger(&self) -> usize {
+        self.newlines
+    }
+
+    /// The type of newline this editor matches
+    #[inline]
+    pub const fn line_ending(&self) -> NewlineType {
+        self.line_ending
+    }
+
+    /// Edit the input's newlines
+    ///
+    /// Returns the same text as [`Editor::edit`] for an editor made with
+    /// [`Editor::new`]. The replacement is pushed straight from its
+    /// `'static` string, so only the output is allocated.
+    #[inline]
+    pub fn edit<S>(&self, input: S) -> String
+    where
+        S: AsRef<str>,
+    {
+        let input = input.as_ref();
+        let mut replace = self.replace;
+        let mut output = String::with_capacity(input.len() + self.replace.len());
+
+        // empty strings do not allocate, so this editor is free to build
+        let editor = Editor::with_edit_type(
+            String::new(),
+            String::new(),
+            EditType::Replace,
+            self.newlines,
+            self.line_ending,
+        );
+        editor.edit_into_with(input, &mut output, &mut replace);
+
+        output
+    }
+
+    /// Copy into an owned [`Editor`], which allocates its replacement
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::{factory, ConstEditor, NewlineType};
+    /// const DASHES: ConstEditor = ConstEditor::new("-", 2, NewlineType::Lf);
+    /// assert_eq!(factory::replacer("-", 2), DASHES.to_editor());
+    /// ```
+    #[inline]
+    pub fn to_editor(&self) -> Editor {
+        Editor::new(self.replace.to_string(), self.newlines, self.line_ending)
+    }
+}
+
+impl From<ConstEditor> for Editor {
+    #[inline]
+    fn from(editor: ConstEditor) -> Self {
+        editor.to_editor()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::factory;
+
+    static PARAGRAPHS: ConstEditor = ConstEditor::new("\n---\n", 2, NewlineType::Lf);
+
+    const INPUT: &str = "foo\n\nbar\nbaz\n\n\nqux\r\n\r\nquux\u{2028}\u{2028}";
+
+    fn assert_same_as_editor(editor: ConstEditor) {
+        assert_eq!(editor.to_editor().edit(INPUT), editor.edit(INPUT));
+    }
+
+    #[test]
+    fn edit_static() {
+        assert_eq!("foo\n---\nbar", PARAGRAPHS.edit("foo\n\nbar"));
+        assert_eq!("foo\nbar", PARAGRAPHS.edit(String::from("foo\nbar")));
+    }
+
+    #[test]
+    fn same_as_editor() {
+        assert_same_as_editor(PARAGRAPHS);
+        assert_same_as_editor(ConstEditor::new("", 1, NewlineType::Lf));
+        assert_same_as_editor(ConstEditor::new("-", 2, NewlineType::Crlf));
+        assert_same_as_editor(ConstEditor::new("\r\n", 1, NewlineType::Auto));
+        assert_same_as_editor(ConstEditor::new(" ", 2, NewlineType::LineSeparator));
+        assert_same_as_editor(ConstEditor::new("-", 0, NewlineType::Lf));
+    }
+
+    #[test]
+    fn into_editor() {
+        let editor: Editor = PARAGRAPHS.into();
+
+        assert_eq!(factory::replacer("\n---\n", 2), editor);
+        assert_eq!("\n---\n", PARAGRAPHS.replacement());
+        assert_eq!(2, PARAGRAPHS.trigger());
+        assert_eq!(NewlineType::Lf, PARAGRAPHS.line_ending());
+    }
+}
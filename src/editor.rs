@@ -1,9 +1,26 @@
-use std::fmt;
-use std::io::{self, BufRead, Write};
+use alloc::borrow::Cow;
+use core::cmp::{self, Ordering};
+use core::fmt;
+use core::mem;
+use core::ops::Range;
+use core::str::{self, FromStr};
+#[cfg(feature = "std")]
+use std::io::{self, BufRead, BufReader, Read, Write};
 
-use crate::{Editor, NewlineType};
+use crate::factory::{self, Factory};
+use crate::prelude::*;
+use crate::preview::Spans;
+#[cfg(feature = "std")]
+use crate::EditError;
+use crate::{
+    EditSpan, EditType, Editor, LineOptions, NewlineType, ParseEditorError, Pipeline, TriggerMode,
+};
 
-const BUFSIZE: usize = 1024;
+pub(crate) const BUFSIZE: usize = 1024;
+
+/// Bytes of input read between calls to a progress callback.
+#[cfg(feature = "std")]
+const PROGRESS_INTERVAL: u64 = 64 * 1024;
 
 impl Editor {
     /// Create a new editor
@@ -30,256 +47,7824 @@ impl Editor {
     ///
     /// # Factory
     ///
-    /// Users of this library are encouraged to use the [`factory`](crate::factory)
+    /// Users of this library are encouraged to use the [`factory`]
     /// functions. These provide convient ways to create instances of this type.
+    ///
+    /// Editors created with this function have an [`EditType::Replace`] edit
+    /// type, as the newlines are replaced with exactly the given string.
+    #[inline]
+    pub fn new(replace: String, newlines: usize, line_ending: NewlineType) -> Self {
+        let text = replace.clone();
+        Self::with_edit_type(replace, text, EditType::Replace, newlines, line_ending)
+    }
+
+    /// Create a new editor which reads one type of newline and writes
+    /// another
+    ///
+    /// Runs of `input_ending` newlines are counted and replaced as with
+    /// [`Editor::new`], and every newline which is not replaced is written as
+    /// an `output_ending` newline. The `replace` string is written exactly
+    /// as given, so its newlines should also be `output_ending`.
+    ///
+    /// `input_ending` can be [`NewlineType::Auto`] to read either LF or CRLF
+    /// input. An `output_ending` of [`NewlineType::Auto`] writes the same
+    /// type of newline as the input.
+    ///
+    /// # Examples
+    ///
+    /// Read any newlines, removing blank lines, and write LF.
+    ///
+    /// ```rust
+    /// # use linurgy::{Editor, NewlineType};
+    /// let editor = Editor::new_converting("\n".to_string(), 2, NewlineType::Auto, NewlineType::Lf);
+    /// assert_eq!("foo\nbar\nbaz\n", editor.edit("foo\r\n\r\nbar\r\nbaz\r\n"));
+    /// assert_eq!("foo\nbar\nbaz\n", editor.edit("foo\n\nbar\nbaz\n"));
+    /// ```
+    #[inline]
+    pub fn new_converting(
+        replace: String,
+        newlines: usize,
+        input_ending: NewlineType,
+        output_ending: NewlineType,
+    ) -> Self {
+        let mut editor = Self::new(replace, newlines, input_ending);
+        if output_ending != input_ending && output_ending != NewlineType::Auto {
+            editor.output_ending = Some(output_ending);
+        }
+        editor
+    }
+
+    /// Create a new editor, recording the text and type of edit used to
+    /// build the `replace` string.
     #[inline]
-    pub fn new(replace: String, newlines: u8, line_ending: NewlineType) -> Self {
+    pub(crate) fn with_edit_type(
+        replace: String,
+        text: String,
+        edit_type: EditType,
+        newlines: usize,
+        line_ending: NewlineType,
+    ) -> Self {
         Editor {
             replace,
             newlines,
             line_ending,
+            edit_type,
+            text,
+            trailing_newline: false,
+            open: String::new(),
+            close: String::new(),
+            max_edits: None,
+            skip_edits: 0,
+            strip_cr: false,
+            blank_whitespace: false,
+            output_ending: None,
+            skip_empty_end: false,
+            trigger_mode: TriggerMode::Exactly,
+            template: false,
+            fallback: None,
+            reversed_crlf: false,
+            lines: LineOptions::default(),
         }
     }
 
-    /// Edit the input's newlines
+    /// The type of edit this editor makes
     ///
-    /// Produces a [`String`] containing the edited text according to how this
-    /// editor was constructed. Can be used multiple times. The `replace`
-    /// string is used to replace newlines when the `newlines` trigger is met.
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::{factory, EditType};
+    /// assert_eq!(EditType::Append, factory::appender("-", 2).edit_type());
+    /// assert_eq!(EditType::Insert, factory::inserter_crlf("-", 1).edit_type());
+    /// assert_eq!(EditType::Replace, factory::replacer(" ", 1).edit_type());
+    /// ```
+    #[inline]
+    pub fn edit_type(&self) -> EditType {
+        self.edit_type
+    }
+
+    /// The string which replaces each matched run of newlines
     ///
     /// # Examples
     ///
     /// ```rust
-    /// # use linurgy::{Editor, NewlineType};
-    /// let editor = Editor::new("-".to_string(), 1, NewlineType::Lf);
-    /// let output = editor.edit("foo\nbar");
-    /// assert_eq!("foo-bar", output);
+    /// # use linurgy::{factory, NewlineType};
+    /// let editor = factory::appender("-", 2);
+    /// assert_eq!("\n\n-", editor.replacement());
+    /// assert_eq!(2, editor.trigger());
+    /// assert_eq!(NewlineType::Lf, editor.line_ending());
     /// ```
     #[inline]
-    pub fn edit(&self, input: &str) -> String {
-        match self.line_ending {
-            NewlineType::Lf => self.edit_lf(input),
-            NewlineType::Crlf => self.edit_crlf(input),
+    pub fn replacement(&self) -> &str {
+        &self.replace
+    }
+
+    /// The number of newlines in a row which trigger an edit
+    #[inline]
+    pub fn trigger(&self) -> usize {
+        self.newlines
+    }
+
+    /// The type of newline this editor matches
+    #[inline]
+    pub fn line_ending(&self) -> NewlineType {
+        self.line_ending
+    }
+
+    /// The type of newline this editor writes
+    ///
+    /// The same as [`Editor::line_ending`], unless the editor was created
+    /// with [`Editor::new_converting`].
+    #[inline]
+    pub fn output_ending(&self) -> NewlineType {
+        self.output_ending.unwrap_or(self.line_ending)
+    }
+
+    /// Copy this editor for a different [`NewlineType`]
+    ///
+    /// Newlines added by an append or insert edit, or by
+    /// [`factory::wrap`], are changed to `line_ending`. The `replace` string
+    /// of other replace edits is kept as it is. All other options are
+    /// copied.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::{factory, NewlineType};
+    /// let editor = factory::appender("-", 1).with_line_ending(NewlineType::Crlf);
+    /// assert_eq!(factory::appender_crlf("-", 1), editor);
+    /// ```
+    pub fn with_line_ending(&self, line_ending: NewlineType) -> Editor {
+        let replace = match self.edit_type {
+            EditType::Replace if self.is_wrap_replace(self.line_ending) => {
+                Factory::wrap_replace(&self.open, &self.close, self.newlines, line_ending)
+            }
+            EditType::Replace => self.replace.clone(),
+            edit_type => {
+                let mut replace = String::new();
+                Factory::new(&self.text, self.newlines, edit_type, line_ending)
+                    .push_replace(&mut replace);
+                replace
+            }
+        };
+
+        let text = match self.edit_type {
+            EditType::Replace => replace.clone(),
+            _ => self.text.clone(),
+        };
+
+        Editor {
+            replace,
+            text,
+            line_ending,
+            output_ending: self.output_ending.filter(|&output| output != line_ending),
+            ..self.clone()
+        }
+    }
+
+    /// Copy this editor with new text to append, insert, or replace with
+    ///
+    /// The `replace` string is rebuilt from `text` for this editor's
+    /// [`EditType`] and line ending, so a copy of an appender still appends
+    /// after the same newlines. A [`EditType::Replace`] editor replaces
+    /// newlines with `text` as it is, including wrapping editors, which keep
+    /// their `open` and `close` text. All other options are copied.
+    ///
+    /// # Examples
+    ///
+    /// Number each section break.
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::appender("", 2).max_edits(Some(1));
+    /// let editors: Vec<_> = (1..=3)
+    ///     .map(|n| editor.clone_with_text(&format!("{}. ", n)))
+    ///     .collect();
+    ///
+    /// assert_eq!("foo\n\n2. bar", editors[1].edit("foo\n\nbar"));
+    /// ```
+    pub fn clone_with_text(&self, text: &str) -> Editor {
+        let mut replace = String::new();
+        Factory::new(text, self.newlines, self.edit_type, self.line_ending)
+            .push_replace(&mut replace);
+
+        Editor {
+            replace,
+            text: text.to_string(),
+            ..self.clone()
+        }
+    }
+
+    /// Was the `replace` string built by [`factory::wrap`] for `line_ending`.
+    #[inline]
+    fn is_wrap_replace(&self, line_ending: NewlineType) -> bool {
+        self.is_wrapped()
+            && self.replace
+                == Factory::wrap_replace(&self.open, &self.close, self.newlines, line_ending)
+    }
+
+    /// Whether this editor makes exactly the same edits as `other`
+    ///
+    /// Editors are compared by their effect: the `replace` string, the
+    /// `newlines` trigger, the [`NewlineType`], and the options which change
+    /// output, such as [`Editor::max_edits`]. How each editor was created,
+    /// its [`EditType`] and the text given to a [`factory`] function, is
+    /// ignored. Editors which are `==` always behave the same.
+    ///
+    /// # Examples
+    ///
+    /// An appender of nothing and an inserter of nothing both replace each
+    /// newline with itself.
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let append = factory::appender("", 1);
+    /// let insert = factory::inserter("", 1);
+    ///
+    /// assert_ne!(append, insert);
+    /// assert!(append.behavior_eq(&insert));
+    /// ```
+    pub fn behavior_eq(&self, other: &Editor) -> bool {
+        self.replace == other.replace
+            && self.newlines == other.newlines
+            && self.line_ending == other.line_ending
+            && self.trailing_newline == other.trailing_newline
+            && self.open == other.open
+            && self.close == other.close
+            && self.max_edits == other.max_edits
+            && self.skip_edits == other.skip_edits
+            && self.strip_cr == other.strip_cr
+            && self.blank_whitespace == other.blank_whitespace
+            && self.output_ending == other.output_ending
+            && self.skip_empty_end == other.skip_empty_end
+            && self.trigger_mode == other.trigger_mode
+            && self.template == other.template
+            && self.fallback == other.fallback
+            && self.reversed_crlf == other.reversed_crlf
+            && self.lines == other.lines
+    }
+
+    /// Whether this editor leaves every input unchanged
+    ///
+    /// An editor is a no-op when it is not [`NewlineType::Crlf`], does not
+    /// [ensure a trailing newline](Editor::ensure_trailing_newline), and
+    /// either:
+    ///
+    /// - Its `newlines` trigger is `0`, so it can never be met
+    /// - Its [`max_edits`](Editor::max_edits) is `Some(0)`, or its
+    ///   [`TriggerMode::Between`] starts after `newlines`, without a
+    ///   [`fallback`](Editor::fallback)
+    /// - Its `replace` string is exactly the newlines it replaces, such as
+    ///   `factory::appender("", 2)` or `normalizer(Lf, Lf)`, and its
+    ///   [`TriggerMode`] is `Exactly`
+    ///
+    /// [`NewlineType::Crlf`] editors are never no-ops, as they write any bare
+    /// `\n` in the input as `\r\n`. Nor are editors which
+    /// [`strip_cr`](Editor::strip_cr), as they remove `\r` from `\r\n`,
+    /// which read [`reversed_crlf`](Editor::reversed_crlf), as they remove
+    /// `\r` from `\n\r`, or which [treat whitespace lines as
+    /// blank](Editor::treat_whitespace_lines_as_blank) or trim
+    /// [trailing](Editor::trim_trailing_whitespace) or
+    /// [leading](Editor::strip_leading_whitespace) whitespace, as they remove
+    /// the whitespace, or which trim [leading](Editor::trim_leading_newlines)
+    /// or [trailing](Editor::trim_trailing_newlines) newlines. Appenders
+    /// which [append at EOF](Editor::append_at_eof) are never no-ops either.
+    ///
+    /// Useful for skipping an expensive edit over a large input. The buffered
+    /// methods already copy input straight to the output for no-op editors.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::{factory, Editor};
+    /// assert!(Editor::default().is_noop());
+    /// assert!(factory::inserter("", 1).is_noop());
+    /// assert!(!factory::replacer(" ", 1).is_noop());
+    /// ```
+    pub fn is_noop(&self) -> bool {
+        if self.trailing_newline
+            || self.is_wrapped()
+            || self.skips_cr()
+            || self.joins_reversed_crlf()
+            || self.blank_whitespace
+            || self.lines_affect_scan()
+            || self.output_ending.is_some()
+        {
+            return false;
+        }
+
+        self.newlines == 0
+            || ((self.max_edits == Some(0) || self.is_empty_range()) && self.fallback.is_none())
+            || (self.trigger_mode == TriggerMode::Exactly
+                && !self.template
+                && self.fallback.is_none()
+                && self.replace_newlines() == Some(self.newlines))
+    }
+
+    /// Whether editing the output again leaves it unchanged
+    ///
+    /// When `true`, `edit(edit(x)) == edit(x)` for every input, so the
+    /// editor can be used to normalize text which may already have been
+    /// normalized. Only `true` when this is certain, which is the case for
+    /// [no-op](Editor::is_noop) editors, and for editors whose `replace`
+    /// string contains no newlines, such as `factory::replacer(" ", 1)`.
+    /// Every run of newlines is then shorter than the trigger once edited, so
+    /// it is never met again.
+    ///
+    /// Editors are not idempotent when:
+    ///
+    /// - Their `replace` string contains newlines, as appenders and
+    ///   inserters do, which can meet the trigger again.
+    /// - They [wrap](Editor::wrap) the text, or limit edits with
+    ///   [`max_edits`](Editor::max_edits) or
+    ///   [`skip_edits`](Editor::skip_edits).
+    /// - They [ensure a trailing
+    ///   newline](Editor::ensure_trailing_newline) which meets the trigger,
+    ///   with a trigger of `1`, [`TriggerMode::AtMost`], or a
+    ///   [`TriggerMode::Between`] starting at `1`, or which a
+    ///   [`fallback`](Editor::fallback) replaces.
+    /// - Their [`fallback`](Editor::fallback) contains newlines.
+    /// - Their line ending is [`NewlineType::Auto`], as editing can change
+    ///   which line ending is detected.
+    /// - They [`strip_cr`](Editor::strip_cr), which removes one `\r` from
+    ///   `\r\r\n` each time, or [treat whitespace lines as
+    ///   blank](Editor::treat_whitespace_lines_as_blank).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::replacer_at_least("", 3).ensure_trailing_newline(true);
+    /// assert!(editor.is_idempotent());
+    ///
+    /// let editor = factory::appender("\n", 1);
+    /// assert!(!editor.is_idempotent());
+    /// assert_eq!("foo\n\n\n\nbar", editor.edit(editor.edit("foo\nbar")));
+    /// ```
+    pub fn is_idempotent(&self) -> bool {
+        if self.is_noop() {
+            return true;
+        }
+
+        if self.is_wrapped()
+            || self.max_edits.is_some()
+            || self.skip_edits > 0
+            || self.strip_cr
+            || self.reversed_crlf
+            || self.blank_whitespace
+            || self.lines_affect_scan()
+            || self.line_ending == NewlineType::Auto
+        {
+            return false;
+        }
+
+        // the trailing newline is a run of one, which may meet the trigger
+        let single_edited = match self.whole_runs() {
+            Some((shortest, _)) => shortest == 1,
+            None => self.newlines == 1,
+        };
+        if self.trailing_newline && single_edited {
+            return false;
+        }
+
+        if let Some(ref fallback) = self.fallback {
+            if self.trailing_newline || contains_newline(fallback) {
+                return false;
+            }
+        }
+
+        self.newlines == 0 || !contains_newline(&self.replace)
+    }
+
+    /// Make sure edited output ends with exactly one newline
+    ///
+    /// When `ensure` is `true`, any run of newlines at the end of the edited
+    /// output is replaced by a single newline of this editor's
+    /// [`NewlineType`]. Output without a trailing newline gets one added.
+    /// Empty output is left empty.
+    ///
+    /// This happens after the main edit, so newlines at the end of the
+    /// `replace` string are also trimmed. Defaults to `false`, which
+    /// preserves trailing newlines as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::replacer("\n", 2).ensure_trailing_newline(true);
+    /// assert_eq!("foo\nbar\n", editor.edit("foo\n\nbar"));
+    /// assert_eq!("foo\nbar\n", editor.edit("foo\n\nbar\n\n\n"));
+    /// ```
+    #[inline]
+    pub fn ensure_trailing_newline(mut self, ensure: bool) -> Self {
+        self.trailing_newline = ensure;
+        self
+    }
+
+    /// Surround edited output with `open` and `close` text
+    ///
+    /// `open` is written before the first character of output and `close`
+    /// after the last. Nothing is written for empty input. Combined with a
+    /// `replace` string which closes and reopens, this wraps every segment of
+    /// the text, as [`factory::wrap`] does.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::replacer("\n", 2).wrap("---\n", "\n---");
+    /// assert_eq!("---\nfoo\nbar\n---", editor.edit("foo\n\nbar"));
+    /// assert_eq!("", editor.edit(""));
+    /// ```
+    #[inline]
+    pub fn wrap(mut self, open: &str, close: &str) -> Self {
+        self.open = String::from(open);
+        self.close = String::from(close);
+        self
+    }
+
+    /// Don't wrap an empty segment at the end of the input
+    ///
+    /// By default, input ending with a run of newlines which is edited has
+    /// one more, empty, segment after it. A [wrapping](Editor::wrap) editor
+    /// writes `open` and `close` around it. When `skip` is `true`, the input
+    /// ends with the run instead: the `open` at the end of its replacement
+    /// and the final `close` are left out. A replacement which doesn't end
+    /// with `open` opens no segment, so `close` is still written.
+    ///
+    /// This wraps every line with a trigger of `1`, as
+    /// [`factory::line_prefixer`] does. Only wrapping editors are affected.
+    /// Defaults to `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::wrap("<", ">", 1);
+    /// assert_eq!("<foo>\n<>", editor.edit("foo\n"));
+    ///
+    /// let editor = editor.skip_empty_last_segment(true);
+    /// assert_eq!("<foo>\n", editor.edit("foo\n"));
+    /// ```
+    #[inline]
+    pub fn skip_empty_last_segment(mut self, skip: bool) -> Self {
+        self.skip_empty_end = skip;
+        self
+    }
+
+    /// Set how the length of a run of newlines is compared to the trigger
+    ///
+    /// With [`TriggerMode::Exactly`], the default, every `newlines` in a run
+    /// are replaced. With [`TriggerMode::AtLeast`], a run of `newlines` or
+    /// more is replaced as a whole, with [`TriggerMode::AtMost`], a run of
+    /// between one and `newlines`, and with [`TriggerMode::Between`], a run
+    /// of between its minimum and `newlines`. Each replaced run counts as one
+    /// edit
+    /// for [`Editor::max_edits`] and [`Editor::skip_edits`], and its whole
+    /// length is passed to [`Editor::edit_with_run_len`].
+    ///
+    /// # Examples
+    ///
+    /// Collapse any blank lines into one.
+    ///
+    /// ```rust
+    /// # use linurgy::{factory, TriggerMode};
+    /// let editor = factory::replacer("\n\n", 2).trigger_mode(TriggerMode::AtLeast);
+    /// assert_eq!("foo\n\nbar\nbaz", editor.edit("foo\n\n\n\nbar\nbaz"));
+    /// ```
+    #[inline]
+    pub fn trigger_mode(mut self, mode: TriggerMode) -> Self {
+        self.trigger_mode = mode;
+        self
+    }
+
+    /// Replace runs of newlines too short to meet the trigger
+    ///
+    /// By default, runs shorter than the `newlines` trigger are kept as they
+    /// are. With `Some(replace)`, each one is replaced with `replace`
+    /// instead, as are any newlines left over at the end of a run after the
+    /// trigger is met. Runs which are [skipped](Editor::skip_edits), or come
+    /// after [`max_edits`](Editor::max_edits) is reached, are still kept.
+    ///
+    /// This has no effect with [`TriggerMode::AtMost`], which already
+    /// replaces every run shorter than the trigger.
+    ///
+    /// # Examples
+    ///
+    /// Double newlines become `<hr>`, and single newlines `<br>`.
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::replacer("<hr>", 2).fallback(Some("<br>"));
+    /// assert_eq!("foo<br>bar<hr>baz", editor.edit("foo\nbar\n\nbaz"));
+    /// assert_eq!("foo<hr><br>bar", editor.edit("foo\n\n\nbar"));
+    /// ```
+    #[inline]
+    pub fn fallback(mut self, replace: Option<&str>) -> Self {
+        self.fallback = replace.map(String::from);
+        self
+    }
+
+    /// Limit how many times the `newlines` trigger is edited
+    ///
+    /// Once `max` edits have been made, the rest of the input is passed
+    /// through unchanged. `None`, the default, places no limit on edits.
+    ///
+    /// # Examples
+    ///
+    /// Insert a marker after the first blank line only.
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::appender("---\n", 2).max_edits(Some(1));
+    /// let output = editor.edit("head\n\nfoo\n\nbar");
+    /// assert_eq!("head\n\n---\nfoo\n\nbar", output);
+    /// ```
+    #[inline]
+    pub fn max_edits(mut self, max: Option<usize>) -> Self {
+        self.max_edits = max;
+        self
+    }
+
+    /// Leave the first `skip` runs which meet the `newlines` trigger unedited
+    ///
+    /// Skipped runs are passed through as ordinary newlines, then the rest
+    /// of the input is edited as normal. Combined with [`Editor::max_edits`],
+    /// which counts edits made after skipping, this edits a window of runs.
+    ///
+    /// Skipped newlines are still part of the output, so
+    /// [`Editor::ensure_trailing_newline`] trims them if they end it.
+    ///
+    /// # Examples
+    ///
+    /// Leave a header untouched, then collapse every double newline.
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::replacer("\n", 2).skip_edits(1);
+    /// let output = editor.edit("head\n\nfoo\n\nbar\n\nbaz");
+    /// assert_eq!("head\n\nfoo\nbar\nbaz", output);
+    /// ```
+    #[inline]
+    pub fn skip_edits(mut self, skip: usize) -> Self {
+        self.skip_edits = skip;
+        self
+    }
+
+    /// Treat a `\r` before a `\n` as part of the newline in LF mode
+    ///
+    /// LF editors normally leave `\r` as ordinary text, so CRLF input keeps
+    /// every `\r` stranded before the edit. With `strip` set to `true`,
+    /// `\r\n` is matched as a single newline and written as `\n`, so CRLF
+    /// input is edited as if it were LF. A `\r` which is not followed by
+    /// `\n` is kept.
+    ///
+    /// Only LF editors are affected, as other [`NewlineType`]s already
+    /// handle `\r` themselves or never see it. Defaults to `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let input = "foo\r\nbar\r\n";
+    ///
+    /// let editor = factory::inserter(";", 1);
+    /// assert_eq!("foo\r;\nbar\r;\n", editor.edit(input));
+    ///
+    /// let editor = factory::inserter(";", 1).strip_cr(true);
+    /// assert_eq!("foo;\nbar;\n", editor.edit(input));
+    /// ```
+    #[inline]
+    pub fn strip_cr(mut self, strip: bool) -> Self {
+        self.strip_cr = strip;
+        self
+    }
+
+    /// Read `\n\r` as a single newline
+    ///
+    /// Some broken exports write their newlines backwards, as `\n\r`. With
+    /// `reversed` set to `true`, a `\r` straight after a `\n` is part of
+    /// that newline, and it is written as this editor's own newline type.
+    /// Pairs are matched from the start of the text, so the `\n` of a
+    /// `\r\n` never starts a reversed pair, and `\r\n\r\n` is still two
+    /// CRLF newlines.
+    ///
+    /// Only LF and CRLF editors are affected, including those with
+    /// [`NewlineType::Auto`]. Defaults to `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let input = "foo\n\r\n\rbar\n\rbaz";
+    ///
+    /// let editor = factory::replacer("\n", 2);
+    /// assert_eq!("foo\n\r\n\rbar\n\rbaz", editor.edit(input));
+    ///
+    /// let editor = factory::replacer("\n", 2).reversed_crlf(true);
+    /// assert_eq!("foo\nbar\nbaz", editor.edit(input));
+    ///
+    /// let editor = factory::replacer_crlf("", 2).reversed_crlf(true);
+    /// assert_eq!("foobar\r\nbaz", editor.edit(input));
+    /// ```
+    #[inline]
+    pub fn reversed_crlf(mut self, reversed: bool) -> Self {
+        self.reversed_crlf = reversed;
+        self
+    }
+
+    /// Count lines of only spaces and tabs as blank lines
+    ///
+    /// Blank lines in hand-edited text often hold stray spaces or tabs, so
+    /// `"foo\n  \nbar"` has no run of two newlines. With `blank` set to
+    /// `true`, a line of only spaces and tabs counts towards the run of
+    /// newlines around it. Its spaces and tabs are removed, whether or not
+    /// the run is edited.
+    ///
+    /// Text after the last newline is never a blank line. Defaults to
+    /// `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::replacer("\n", 2).treat_whitespace_lines_as_blank(true);
+    /// assert_eq!("foo\nbar", editor.edit("foo\n \t\nbar"));
+    /// ```
+    #[inline]
+    pub fn treat_whitespace_lines_as_blank(mut self, blank: bool) -> Self {
+        self.blank_whitespace = blank;
+        self
+    }
+
+    /// Remove trailing spaces and tabs from the end of each line
+    ///
+    /// Each line is trimmed before it is edited, including the last line
+    /// when the input doesn't end with a newline. A line of only spaces and
+    /// tabs is left empty, so it counts towards the run of newlines around
+    /// it, the same as with
+    /// [`treat_whitespace_lines_as_blank`](Editor::treat_whitespace_lines_as_blank).
+    ///
+    /// Only spaces and tabs directly before a newline are removed. For LF
+    /// editors a `\r` before `\n` is text, unless
+    /// [`strip_cr`](Editor::strip_cr) is set, so spaces before it are kept.
+    /// Defaults to `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::inserter("", 1).trim_trailing_whitespace(true);
+    /// assert_eq!("foo\nbar\n\nbaz", editor.edit("foo \t\nbar\n  \nbaz\t"));
+    ///
+    /// let editor = factory::replacer("\n", 2).trim_trailing_whitespace(true);
+    /// assert_eq!("foo\nbar", editor.edit("foo  \n \nbar"));
+    /// ```
+    #[inline]
+    pub fn trim_trailing_whitespace(mut self, trim: bool) -> Self {
+        self.lines.trim_trailing = trim;
+        self
+    }
+
+    /// Remove leading spaces and tabs from the start of each line
+    ///
+    /// Every line loses all of its indentation, the same as trimming the
+    /// start of each line on its own. A line of only spaces and tabs is left
+    /// empty, so it counts towards the run of newlines around it. Defaults
+    /// to `false`.
+    ///
+    /// To keep the indentation of lines relative to each other, use
+    /// [`Editor::edit_dedented`] instead, which removes only the whitespace
+    /// shared by every line.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::replacer("\n", 2).strip_leading_whitespace(true);
+    /// assert_eq!("fn main() {\nfoo();\n}", editor.edit("  fn main() {\n\t  foo();\n  \n  }"));
+    /// ```
+    #[inline]
+    pub fn strip_leading_whitespace(mut self, strip: bool) -> Self {
+        self.lines.trim_leading = strip;
+        self
+    }
+
+    /// Remove the newlines at the start of the input
+    ///
+    /// Any run of newlines before the first text of the input is removed,
+    /// which cleans up files beginning with blank lines. These newlines
+    /// don't count towards the trigger: the first run counted is the first
+    /// one after some text, so the input is edited as if it began at its
+    /// first text. Lines left blank by
+    /// [`treat_whitespace_lines_as_blank`](Editor::treat_whitespace_lines_as_blank)
+    /// or by trimming whitespace are removed along with them. An input of
+    /// only newlines edits into nothing, apart from any
+    /// [`wrap`](Editor::wrap). Defaults to `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::replacer("-", 2).trim_leading_newlines(true);
+    /// assert_eq!("foo-bar\n", editor.edit("\n\n\nfoo\n\nbar\n"));
+    /// ```
+    #[inline]
+    pub fn trim_leading_newlines(mut self, trim: bool) -> Self {
+        self.lines.trim_leading_newlines = trim;
+        self
+    }
+
+    /// Remove the newlines at the end of the input
+    ///
+    /// Any run of newlines after the last text of the input is removed,
+    /// which cleans up files ending with blank lines. As with
+    /// [`trim_leading_newlines`](Editor::trim_leading_newlines), these
+    /// newlines don't count towards the trigger, so the input is edited as
+    /// if it ended at its last text. Lines left blank by
+    /// [`treat_whitespace_lines_as_blank`](Editor::treat_whitespace_lines_as_blank)
+    /// or by trimming whitespace are removed along with them. Defaults to
+    /// `false`.
+    ///
+    /// Combine with
+    /// [`ensure_trailing_newline`](Editor::ensure_trailing_newline) to end
+    /// the output with exactly one newline, after any replacement.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::replacer("-", 2).trim_trailing_newlines(true);
+    /// assert_eq!("foo-bar", editor.edit("foo\n\nbar\n\n\n"));
+    ///
+    /// let editor = factory::appender("-", 1)
+    ///     .trim_trailing_newlines(true)
+    ///     .ensure_trailing_newline(true);
+    /// assert_eq!("foo\n-bar\n", editor.edit("foo\nbar\n\n"));
+    /// ```
+    #[inline]
+    pub fn trim_trailing_newlines(mut self, trim: bool) -> Self {
+        self.lines.trim_trailing_newlines = trim;
+        self
+    }
+
+    /// Append the text after the last line, even without a newline
+    ///
+    /// An [appender](crate::factory::appender) only adds its text after
+    /// newlines, so input which doesn't end with a newline gets no text
+    /// after its last line. With this option, the text is also added at the
+    /// end of the input whenever it ends with text rather than a newline.
+    /// Empty input gets nothing. The text added at the end is not counted as
+    /// an edit, and is not limited by [`max_edits`](Editor::max_edits).
+    ///
+    /// Only editors with an [`EditType::Append`] edit type are affected.
+    /// Defaults to `false`, where the last line is left as it is.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::appender(" ;", 1);
+    /// assert_eq!("foo\n ;bar", editor.edit("foo\nbar"));
+    ///
+    /// let editor = editor.append_at_eof(true);
+    /// assert_eq!("foo\n ;bar ;", editor.edit("foo\nbar"));
+    /// assert_eq!("foo\n ;bar\n ;", editor.edit("foo\nbar\n"));
+    /// ```
+    #[inline]
+    pub fn append_at_eof(mut self, append: bool) -> Self {
+        self.lines.append_eof = append;
+        self
+    }
+
+    /// Is any text appended at the end of input which ends with text.
+    #[inline(always)]
+    pub(crate) fn appends_at_eof(&self) -> bool {
+        self.lines.append_eof && self.appends_text()
+    }
+
+    /// Does this editor append non-empty text.
+    #[inline(always)]
+    fn appends_text(&self) -> bool {
+        self.edit_type == EditType::Append && !self.text.is_empty()
+    }
+
+    /// Can the line options change the text the newline scan writes.
+    #[inline(always)]
+    pub(crate) fn lines_affect_scan(&self) -> bool {
+        self.lines.affects_scan(self.appends_text())
+    }
+
+    /// Byte index just after the last text of `input`, where its run of
+    /// trailing newlines starts.
+    ///
+    /// Lines are read the same way as [`Editor::edit`], so text which is
+    /// trimmed or blank doesn't count.
+    pub(crate) fn content_end(&self, input: &str) -> usize {
+        let newline = self.line_ending.end_char();
+        let skip_cr = self.skips_cr();
+        let join_cr = self.joins_reversed_crlf();
+
+        let mut end = 0;
+        let mut offset = 0;
+
+        while let Some(index) = input[offset..].find(newline) {
+            let mut text = &input[offset..offset + index];
+            let bare = !text.ends_with('\r');
+
+            if skip_cr && !bare {
+                text = &text[..text.len() - 1];
+            }
+            text = self.trim_end(text);
+
+            let trimmed = self.trim_start(text);
+            if !trimmed.is_empty() && !self.is_blank(trimmed) {
+                end = offset + text.len();
+            }
+
+            offset += index + newline.len_utf8();
+            if join_cr && bare && input[offset..].starts_with('\r') {
+                offset += 1;
+            }
+        }
+
+        if !self.trim_start(self.trim_end(&input[offset..])).is_empty() {
+            end = input.len();
+        }
+
+        end
+    }
+
+    /// Length in bytes of the first line of `input`, including its newline.
+    ///
+    /// A `\r` which ends a reversed newline is kept on the line it ends.
+    #[inline]
+    pub(crate) fn line_len(&self, input: &str) -> usize {
+        let newline = self.line_ending.end_char();
+
+        match input.find(newline) {
+            Some(index) => {
+                let end = index + newline.len_utf8();
+                let bare = !input[..index].ends_with('\r');
+                if self.joins_reversed_crlf() && bare && input[end..].starts_with('\r') {
+                    end + 1
+                } else {
+                    end
+                }
+            }
+            None => input.len(),
+        }
+    }
+
+    /// Is the text of a line only spaces and tabs, to be removed.
+    #[inline(always)]
+    pub(crate) fn is_blank(&self, line: &str) -> bool {
+        self.blank_whitespace && line.bytes().all(|b| b == b' ' || b == b'\t')
+    }
+
+    /// The text of a line without any trailing whitespace to be removed.
+    #[inline(always)]
+    pub(crate) fn trim_end<'t>(&self, text: &'t str) -> &'t str {
+        if self.lines.trim_trailing {
+            text.trim_end_matches(is_space_or_tab)
+        } else {
+            text
+        }
+    }
+
+    /// The text of a line without any leading whitespace to be removed.
+    #[inline(always)]
+    pub(crate) fn trim_start<'t>(&self, text: &'t str) -> &'t str {
+        if self.lines.trim_leading {
+            text.trim_start_matches(is_space_or_tab)
+        } else {
+            text
+        }
+    }
+
+    /// Is a `\r` directly after a bare `\n` part of the newline.
+    #[inline(always)]
+    pub(crate) fn joins_reversed_crlf(&self) -> bool {
+        self.reversed_crlf && self.line_ending.end_char() == '\n'
+    }
+
+    /// Is a `\r` directly before a `\n` part of the newline.
+    #[inline(always)]
+    pub(crate) fn skips_cr(&self) -> bool {
+        match self.line_ending {
+            NewlineType::Crlf | NewlineType::Auto => true,
+            NewlineType::Lf => self.strip_cr,
+            NewlineType::LineSeparator | NewlineType::ParagraphSeparator => false,
+        }
+    }
+
+    /// Can the trigger be edited after it has been met `matches` times.
+    #[inline(always)]
+    pub(crate) fn can_edit(&self, matches: usize) -> bool {
+        match self.max_edits {
+            Some(max) => matches < self.skip_edits.saturating_add(max),
+            None => true,
+        }
+    }
+
+    /// Is the next trigger met after `matches` skipped.
+    #[inline(always)]
+    pub(crate) fn skips(&self, matches: usize) -> bool {
+        matches < self.skip_edits
+    }
+
+    /// Number of edits made once the trigger has been met `matches` times.
+    #[inline(always)]
+    pub(crate) fn edits_made(&self, matches: usize) -> usize {
+        matches.saturating_sub(self.skip_edits)
+    }
+
+    /// The shortest and longest runs of newlines which are replaced as a
+    /// whole once they end, or `None` if every `newlines` newlines in a run
+    /// are replaced as they are counted.
+    #[inline(always)]
+    pub(crate) fn whole_runs(&self) -> Option<(usize, usize)> {
+        match self.trigger_mode {
+            TriggerMode::Exactly => None,
+            TriggerMode::AtLeast => Some((self.newlines, usize::max_value())),
+            TriggerMode::AtMost => Some((1, self.newlines)),
+            TriggerMode::Between(shortest) => Some((cmp::max(shortest, 1), self.newlines)),
+        }
+    }
+
+    /// Is the trigger a range which no run can meet.
+    #[inline]
+    fn is_empty_range(&self) -> bool {
+        self.whole_runs()
+            .map_or(false, |(shortest, longest)| shortest > longest)
+    }
+
+    /// Is output surrounded by text from [`Editor::wrap`].
+    #[inline]
+    fn is_wrapped(&self) -> bool {
+        !self.open.is_empty() || !self.close.is_empty()
+    }
+
+    /// Edit the input's newlines
+    ///
+    /// Produces a [`String`] containing the edited text according to how this
+    /// editor was constructed. Can be used multiple times. The `replace`
+    /// string is used to replace newlines when the `newlines` trigger is met.
+    ///
+    /// The input can be anything which is viewed as a [`str`], such as a
+    /// [`String`] or [`Cow<str>`](std::borrow::Cow).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::{Editor, NewlineType};
+    /// let editor = Editor::new("-".to_string(), 1, NewlineType::Lf);
+    /// let output = editor.edit("foo\nbar");
+    /// assert_eq!("foo-bar", output);
+    ///
+    /// let input = String::from("foo\n\nbar");
+    /// assert_eq!("foo--bar", editor.edit(input));
+    /// ```
+    #[inline]
+    pub fn edit<S>(&self, input: S) -> String
+    where
+        S: AsRef<str>,
+    {
+        self.edit_str(input.as_ref())
+    }
+
+    /// Edit the input's newlines into raw bytes
+    ///
+    /// Produces the same text as [`Editor::edit`], as a [`Vec<u8>`] rather
+    /// than a [`String`]. Useful when the output is only written out as
+    /// bytes, such as to an [`io::Write`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::replacer("\n", 2);
+    /// assert_eq!(b"foo\nbar".to_vec(), editor.edit_to_vec("foo\n\nbar"));
+    /// ```
+    #[inline]
+    pub fn edit_to_vec(&self, input: &str) -> Vec<u8> {
+        self.edit_str(input).into_bytes()
+    }
+
+    /// Edit the input's newlines into a [`fmt::Write`]
+    ///
+    /// Writes the same text as [`Editor::edit`] returns, without building
+    /// the whole output first. Useful for editing straight into a
+    /// [`fmt::Formatter`], such as inside a [`Display`](fmt::Display) impl.
+    ///
+    /// # Errors
+    ///
+    /// Any error from writing to `output`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::fmt;
+    /// # use linurgy::factory;
+    /// struct Paragraphs<'a>(&'a str);
+    ///
+    /// impl fmt::Display for Paragraphs<'_> {
+    ///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    ///         factory::wrap("<p>", "</p>", 2).edit_fmt(self.0, f)
+    ///     }
+    /// }
+    ///
+    /// let html = Paragraphs("foo\n\nbar").to_string();
+    /// assert_eq!("<p>foo</p>\n\n<p>bar</p>", html);
+    /// ```
+    pub fn edit_fmt<W>(&self, input: &str, output: &mut W) -> fmt::Result
+    where
+        W: fmt::Write + ?Sized,
+    {
+        if self.is_noop() {
+            return output.write_str(input);
+        }
+
+        let mut state = BufState::new(self);
+        state.resolve(input);
+        let mut edited = String::with_capacity(cmp::min(input.len(), BUFSIZE));
+        let line_end = state.line_end();
+        let mut rest = input;
+
+        // edited lines are collected to make fewer, larger writes
+        while !rest.is_empty() {
+            let len = rest
+                .find(line_end)
+                .map_or(rest.len(), |index| index + line_end.len_utf8());
+            let (line, next) = rest.split_at(len);
+
+            state.push_line(line, &mut edited);
+            if edited.len() >= BUFSIZE {
+                output.write_str(&edited)?;
+                edited.clear();
+            }
+            rest = next;
+        }
+
+        state.finish(&mut edited);
+        output.write_str(&edited)
+    }
+
+    /// Edit each of the inputs, in order
+    ///
+    /// Produces the same text as calling [`Editor::edit`] on each input.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let records = vec!["foo\nbar", "baz", "qux\n"];
+    /// let edited = factory::replacer(" | ", 1).edit_all(&records);
+    /// assert_eq!(vec!["foo | bar", "baz", "qux | "], edited);
+    /// ```
+    pub fn edit_all<I, S>(&self, inputs: I) -> Vec<String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        inputs.into_iter().map(|input| self.edit(input)).collect()
+    }
+
+    /// Edit the input's newlines, borrowing the input if the text is
+    /// unchanged
+    ///
+    /// Produces the same text as [`Editor::edit`]. The output is checked
+    /// against the input as it is edited, and nothing is allocated until
+    /// the first change. Then the input up to that point is copied, and
+    /// editing carries on into the copy. When nothing is replaced, converted
+    /// or removed, such as when the `newlines` trigger is never met, the
+    /// input is borrowed without allocating. [No-op](Editor::is_noop)
+    /// editors borrow the input without editing it.
+    ///
+    /// A replacement which writes the same newlines as it replaces is not a
+    /// change.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::borrow::Cow;
+    /// # use linurgy::factory;
+    /// let editor = factory::replacer(" ", 2);
+    ///
+    /// // trigger not met, so the input is borrowed
+    /// match editor.edit_cow("foo\nbar") {
+    ///     Cow::Borrowed(output) => assert_eq!("foo\nbar", output),
+    ///     Cow::Owned(_) => unreachable!(),
+    /// }
+    ///
+    /// assert_eq!("foo bar", editor.edit_cow("foo\n\nbar"));
+    /// ```
+    pub fn edit_cow<'a>(&self, input: &'a str) -> Cow<'a, str> {
+        let mut output = CowOutput::new(input);
+        self.edit_into(input, &mut output);
+        output.into_cow()
+    }
+
+    /// Edit the input's newlines, or return `None` if the text is unchanged
+    ///
+    /// Useful for skipping needless writes, such as when rewriting files.
+    /// Built on [`Editor::edit_cow`], so an unchanged input is never copied.
+    /// [No-op](Editor::is_noop) editors return `None` without editing, and a
+    /// replacement which writes the same newlines as it replaces is not a
+    /// change.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::replacer(" ", 2);
+    /// assert_eq!(Some("foo bar".to_string()), editor.edit_if_changed("foo\n\nbar"));
+    /// assert_eq!(None, editor.edit_if_changed("foo\nbar"));
+    /// ```
+    #[inline]
+    pub fn edit_if_changed(&self, input: &str) -> Option<String> {
+        match self.edit_cow(input) {
+            Cow::Borrowed(_) => None,
+            Cow::Owned(output) => Some(output),
+        }
+    }
+
+    /// Non-generic body of [`Editor::edit`].
+    #[inline]
+    fn edit_str(&self, input: &str) -> String {
+        let mut output = String::with_capacity(input.len() + self.replace.len());
+        self.edit_into(input, &mut output);
+        output
+    }
+
+    /// Length in bytes of the output [`Editor::edit`] would return
+    ///
+    /// The input is edited one line at a time, and each line's output is
+    /// counted then discarded, so the whole output is never built. Memory
+    /// use depends on the longest line rather than the whole input. Useful
+    /// for sizing a buffer up front, or rejecting input which would edit
+    /// into too much text.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::appender("---", 2);
+    /// let input = "foo\n\nbar\n\nbaz";
+    /// assert_eq!(editor.edit(input).len(), editor.output_len(input));
+    /// assert_eq!(19, editor.output_len(input));
+    /// ```
+    pub fn output_len(&self, input: &str) -> usize {
+        if self.is_noop() {
+            return input.len();
+        }
+
+        let mut state = BufState::new(self);
+        state.resolve(input);
+        let end_char = state.line_end();
+
+        let mut edited = String::new();
+        let mut len = 0;
+        let mut rest = input;
+
+        while !rest.is_empty() {
+            let end = rest
+                .find(end_char)
+                .map_or(rest.len(), |index| index + end_char.len_utf8());
+            state.push_line(&rest[..end], &mut edited);
+            len += edited.len();
+            edited.clear();
+            rest = &rest[end..];
+        }
+
+        state.finish(&mut edited);
+        len + edited.len()
+    }
+
+    /// Edit the input buffer's newlines into the output writer
+    ///
+    /// Input types must implement [`BufRead`].
+    /// Output types must implement [`Write`].
+    ///
+    /// Text is edited according to how this editor was constructed. Can be
+    /// used multiple times. The `replace` string is used to replace newlines
+    /// when the `newlines` trigger is met.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EditError`] if reading the input or writing the output
+    /// fails, or the input is not valid UTF-8. Input to a
+    /// [no-op](Editor::is_noop) editor is copied as bytes, so is not checked
+    /// for valid UTF-8.
+    /// [`Editor::edit_buffered_bytes`] edits input which may not be valid
+    /// UTF-8.
+    ///
+    /// # Output
+    ///
+    /// Edited lines are collected and written in chunks of at least 1 KiB,
+    /// rather than one line at a time, so unbuffered writers such as a
+    /// [`File`](std::fs::File) don't need wrapping in a
+    /// [`BufWriter`](std::io::BufWriter). Everything has been written by the
+    /// time this returns.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::error::Error;
+    /// # use std::io::Cursor;
+    /// # use std::str::from_utf8;
+    /// # use linurgy::{Editor, NewlineType};
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let editor = Editor::new("-".to_string(), 1, NewlineType::Lf);
+    /// // Cursor implements BufRead over a string
+    /// let mut input = Cursor::new("foo\nbar");
+    /// let mut output = Vec::new();
+    /// editor.edit_buffered(&mut input, &mut output)?;
+    /// assert_eq!("foo-bar", from_utf8(&output)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    pub fn edit_buffered<I, O>(&self, input: &mut I, output: &mut O) -> Result<(), EditError>
+    where
+        I: BufRead,
+        O: Write,
+    {
+        self.edit_buffered_counted(input, output)?;
+        Ok(())
+    }
+
+    /// Edit the input buffer's newlines into an owned writer, returning it
+    ///
+    /// Works like [`Editor::edit_buffered`], but takes the reader and writer
+    /// by value. Useful for one-shot edits of an owned [`File`](std::fs::File)
+    /// or into a new [`Vec<u8>`], which is handed back once everything has
+    /// been written.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Editor::edit_buffered`]. An [`EditError`] converts into
+    /// an [`io::Error`], so `?` works in functions returning
+    /// [`io::Result`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::error::Error;
+    /// # use linurgy::factory;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let editor = factory::replacer("-", 1);
+    /// let output = editor.edit_buffered_owned("foo\nbar".as_bytes(), Vec::new())?;
+    /// assert_eq!(b"foo-bar", &output[..]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    pub fn edit_buffered_owned<I, O>(&self, mut input: I, mut output: O) -> Result<O, EditError>
+    where
+        I: BufRead,
+        O: Write,
+    {
+        self.edit_buffered(&mut input, &mut output)?;
+        Ok(output)
+    }
+
+    /// Edit the newlines of any reader into the output writer
+    ///
+    /// Wraps `input` in a [`BufReader`], then works like
+    /// [`Editor::edit_buffered`]. Useful for a bare [`File`](std::fs::File)
+    /// or socket, which only implement [`Read`]. Readers which are already
+    /// [`BufRead`] can skip the extra buffer with [`Editor::edit_buffered`].
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Editor::edit_buffered`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::error::Error;
+    /// # use std::fs::File;
+    /// # use linurgy::factory;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let path = std::env::temp_dir().join("linurgy-edit-read-doc.txt");
+    /// # std::fs::write(&path, "foo\n\nbar")?;
+    /// let editor = factory::replacer("-", 2);
+    /// let mut output = Vec::new();
+    /// editor.edit_read(File::open(&path)?, &mut output)?;
+    /// assert_eq!(b"foo-bar", &output[..]);
+    /// # std::fs::remove_file(&path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    pub fn edit_read<R, O>(&self, input: R, output: &mut O) -> Result<(), EditError>
+    where
+        R: Read,
+        O: Write,
+    {
+        self.edit_buffered(&mut BufReader::new(input), output)
+    }
+
+    /// Edit the input buffer's newlines into the output writer, calling
+    /// `on_line` with each line of output
+    ///
+    /// Writes the same output as [`Editor::edit_buffered`]. Each line is
+    /// passed to `on_line` once it is complete, including any edits, just
+    /// before it is written. Useful for counting or logging lines during a
+    /// long edit.
+    ///
+    /// Lines are lines of the output rather than the input, split after each
+    /// newline of this editor's output [`NewlineType`], which is included.
+    /// A replacement containing newlines makes more than one line, and one
+    /// without joins lines of the input into one. Text after the last
+    /// newline is passed last, if there is any, so the lines joined together
+    /// are exactly the output.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Editor::edit_buffered`], except that the input to a
+    /// [no-op](Editor::is_noop) editor must also be valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::error::Error;
+    /// # use linurgy::factory;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let editor = factory::appender("---\n", 2);
+    /// let mut output = Vec::new();
+    /// let mut lines = Vec::new();
+    ///
+    /// editor.edit_buffered_with_callback(&mut "a\n\nb".as_bytes(), &mut output, |line| {
+    ///     lines.push(line.to_string())
+    /// })?;
+    ///
+    /// assert_eq!(vec!["a\n", "\n", "---\n", "b"], lines);
+    /// assert_eq!(b"a\n\n---\nb", &output[..]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn edit_buffered_with_callback<I, O, F>(
+        &self,
+        input: &mut I,
+        output: &mut O,
+        mut on_line: F,
+    ) -> Result<(), EditError>
+    where
+        I: BufRead,
+        O: Write,
+        F: FnMut(&str),
+    {
+        let end_char = self.output_ending().end_char();
+        let mut state = BufState::new(self);
+        let mut buf = String::with_capacity(BUFSIZE);
+        let mut edited = String::with_capacity(BUFSIZE);
+
+        // the start of an output line, until its newline is edited
+        let mut line = String::new();
+        let mut scanned = 0;
+        let mut lines = 0;
+
+        while read_line(input, &mut buf, &mut lines, self.line_ending)? {
+            state.push_line(&buf, &mut edited);
+            call_lines(&edited[scanned..], &mut line, end_char, &mut on_line);
+            scanned = edited.len();
+
+            if edited.len() >= BUFSIZE {
+                write_str(output, &edited)?;
+                edited.clear();
+                scanned = 0;
+            }
+        }
+
+        state.finish(&mut edited);
+        call_lines(&edited[scanned..], &mut line, end_char, &mut on_line);
+        if !line.is_empty() {
+            on_line(&line);
+        }
+        write_str(output, &edited)?;
+
+        Ok(())
+    }
+
+    /// Edit the input buffer's newlines into the output writer, calling
+    /// `progress` with the number of input bytes read so far
+    ///
+    /// Writes the same output as [`Editor::edit_buffered`]. The callback is
+    /// passed the total bytes read from `input`, after about every 64 KiB,
+    /// and once more with the total when everything has been written, even
+    /// if the input is empty. Useful for driving a progress bar while
+    /// editing very large inputs.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Editor::edit_buffered`], except that the input to a
+    /// [no-op](Editor::is_noop) editor must also be valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::error::Error;
+    /// # use linurgy::factory;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let editor = factory::replacer("-", 1);
+    /// let input = "foo\n".repeat(50_000);
+    /// let mut output = Vec::new();
+    /// let mut reports = Vec::new();
+    ///
+    /// editor.edit_buffered_with_progress(&mut input.as_bytes(), &mut output, |read| {
+    ///     reports.push(read)
+    /// })?;
+    ///
+    /// assert_eq!(Some(&200_000), reports.last());
+    /// assert_eq!(4, reports.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn edit_buffered_with_progress<I, O, F>(
+        &self,
+        input: &mut I,
+        output: &mut O,
+        mut progress: F,
+    ) -> Result<(), EditError>
+    where
+        I: BufRead,
+        O: Write,
+        F: FnMut(u64),
+    {
+        let mut state = BufState::new(self);
+        let mut buf = String::with_capacity(BUFSIZE);
+        let mut edited = String::with_capacity(BUFSIZE);
+
+        let mut lines = 0;
+        let mut read = 0;
+        let mut reported = 0;
+
+        while read_line(input, &mut buf, &mut lines, self.line_ending)? {
+            read += buf.len() as u64;
+            state.push_line(&buf, &mut edited);
+
+            if edited.len() >= BUFSIZE {
+                write_str(output, &edited)?;
+                edited.clear();
+            }
+            if read - reported >= PROGRESS_INTERVAL {
+                progress(read);
+                reported = read;
+            }
+        }
+
+        state.finish(&mut edited);
+        write_str(output, &edited)?;
+        progress(read);
+
+        Ok(())
+    }
+
+    /// Edit the text's newlines in place
+    ///
+    /// Produces the same result as [`Editor::edit`], but writes it back into
+    /// `text`.
+    ///
+    /// The text is edited in its own buffer, a line at a time, when the
+    /// result can never grow past the input already read. This is the case
+    /// when:
+    ///
+    /// - The `replace` string is no longer than the newlines it replaces
+    ///   (`newlines` bytes for LF, `newlines * 2` bytes for CRLF).
+    /// - For CRLF editors, every `\n` in the text is part of a `\r\n`.
+    ///
+    /// Common edits such as collapsing runs of newlines meet these
+    /// conditions. Otherwise the edited text is allocated as a new
+    /// [`String`] which replaces `text`. Adding a final newline with
+    /// [`Editor::ensure_trailing_newline`] may also need to grow `text`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::replacer("\n", 2);
+    /// let mut text = String::from("foo\n\nbar\n\nbaz");
+    /// editor.edit_in_place(&mut text);
+    /// assert_eq!("foo\nbar\nbaz", text);
+    /// ```
+    pub fn edit_in_place(&self, text: &mut String) {
+        if !self.fits_in_place(text) {
+            *text = self.edit_str(text);
+            return;
+        }
+
+        let mut bytes = mem::replace(text, String::new()).into_bytes();
+        let mut scan = Scan::new(self);
+        let mut replace = self.replace.as_str();
+        let mut edited = String::new();
+        let mut read = 0;
+        let mut write = 0;
+
+        // the edited text never gets ahead of the input read, so each line
+        // is written over input which has already been edited
+        while read < bytes.len() {
+            let end = bytes[read..]
+                .iter()
+                .position(|&byte| byte == b'\n')
+                .map_or(bytes.len(), |index| read + index + 1);
+            let line = str::from_utf8(&bytes[read..end]).expect("lines end at ASCII newlines");
+            scan.push_line(self, line, &mut edited, &mut replace);
+
+            bytes[write..write + edited.len()].copy_from_slice(edited.as_bytes());
+            write += edited.len();
+            edited.clear();
+            read = end;
+        }
+
+        scan.finish(self, &mut edited, &mut replace);
+        bytes[write..write + edited.len()].copy_from_slice(edited.as_bytes());
+        bytes.truncate(write + edited.len());
+
+        *text = String::from_utf8(bytes).expect("edit only splits text at ASCII newlines");
+
+        if self.trailing_newline {
+            self.end_with_newline(text, 0);
+        }
+    }
+
+    /// Edit the input's newlines, counting the edits made
+    ///
+    /// Produces the same [`String`] as [`Editor::edit`], along with the
+    /// number of times the `newlines` trigger was met and the `replace`
+    /// string written.
+    /// [No-op](Editor::is_noop) editors make no edits, as the text they
+    /// write is never different.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::replacer("\n", 2);
+    /// let (output, edits) = editor.edit_counted("foo\n\nbar\n\nbaz");
+    /// assert_eq!("foo\nbar\nbaz", output);
+    /// assert_eq!(2, edits);
+    /// ```
+    #[inline]
+    pub fn edit_counted(&self, input: &str) -> (String, usize) {
+        let mut output = String::with_capacity(input.len() + self.replace.len());
+        let edits = self.edit_into(input, &mut output);
+        (output, edits)
+    }
+
+    /// Edit the input's newlines, computing each replacement with a closure
+    ///
+    /// Works like [`Editor::edit`], but every time the `newlines` trigger is
+    /// met `replace` is called with the index of the match (starting at 0)
+    /// and its result is used instead of this editor's `replace` string.
+    ///
+    /// The closure can hold state, so it is passed to each call rather than
+    /// stored on the editor. This keeps the editor reusable through `&self`.
+    ///
+    /// # Examples
+    ///
+    /// Number every section break.
+    ///
+    /// ```rust
+    /// # use linurgy::{Editor, NewlineType};
+    /// let editor = Editor::new(String::new(), 2, NewlineType::Lf);
+    /// let output = editor.edit_with("foo\n\nbar\n\nbaz", |index| {
+    ///     format!("\n---{}---\n", index + 1)
+    /// });
+    /// assert_eq!("foo\n---1---\nbar\n---2---\nbaz", output);
+    /// ```
+    #[inline]
+    pub fn edit_with<F>(&self, input: &str, replace: F) -> String
+    where
+        F: FnMut(usize) -> String,
+    {
+        let mut output = String::with_capacity(input.len());
+        self.edit_into_with(input, &mut output, &mut ReplaceFn(replace));
+        output
+    }
+
+    /// Edit the input's newlines, computing each replacement from the
+    /// length of the run it replaces
+    ///
+    /// Works like [`Editor::edit_with`], but `replace` is called with the
+    /// number of newlines the replacement consumes rather than the index of
+    /// the match. How many that is depends on the editor's [`TriggerMode`]:
+    ///
+    /// - [`Exactly`](TriggerMode::Exactly): always `newlines`, as every
+    ///   `newlines` newlines in a run are replaced separately. A run of
+    ///   twice the trigger calls `replace` twice.
+    /// - [`AtLeast`](TriggerMode::AtLeast): the length of the whole run,
+    ///   which is `newlines` or more.
+    /// - [`AtMost`](TriggerMode::AtMost): the length of the whole run,
+    ///   between one and `newlines`.
+    /// - [`Between`](TriggerMode::Between): the length of the whole run,
+    ///   between its minimum and `newlines`.
+    ///
+    /// Runs which are [skipped](Editor::skip_edits), come after
+    /// [`max_edits`](Editor::max_edits) is reached, or are replaced by a
+    /// [`fallback`](Editor::fallback) are not passed to `replace`.
+    ///
+    /// # Examples
+    ///
+    /// A double newline becomes a rule, and four or more a page break.
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::replacer_at_least("", 2);
+    /// let output = editor.edit_with_run_len("a\n\nb\n\n\n\n\nc", |len| {
+    ///     if len >= 4 {
+    ///         "<page>"
+    ///     } else {
+    ///         "<hr>"
+    ///     }
+    /// });
+    /// assert_eq!("a<hr>b<page>c", output);
+    /// ```
+    #[inline]
+    pub fn edit_with_run_len<F, S>(&self, input: &str, replace: F) -> String
+    where
+        F: FnMut(usize) -> S,
+        S: AsRef<str>,
+    {
+        let mut output = String::with_capacity(input.len());
+        self.edit_into_with(input, &mut output, &mut ReplaceRunFn(replace));
+        output
+    }
+
+    /// Edit the input buffer's newlines into the output writer, counting the
+    /// edits made
+    ///
+    /// Writes the same output as [`Editor::edit_buffered`]. On success,
+    /// returns the number of times the `newlines` trigger was met and the
+    /// `replace` string written.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::error::Error;
+    /// # use std::io::Cursor;
+    /// # use linurgy::factory;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let editor = factory::replacer("\n", 2);
+    /// let mut input = Cursor::new("foo\n\nbar\n\nbaz");
+    /// let mut output = Vec::new();
+    /// let edits = editor.edit_buffered_counted(&mut input, &mut output)?;
+    /// assert_eq!(2, edits);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn edit_buffered_counted<I, O>(
+        &self,
+        input: &mut I,
+        output: &mut O,
+    ) -> Result<usize, EditError>
+    where
+        I: BufRead,
+        O: Write,
+    {
+        let (edits, _) = self.edit_buffered_inner(input, output, BUFSIZE, false)?;
+        Ok(edits)
+    }
+
+    /// Edit the input buffer's newlines into the output writer, counting the
+    /// bytes written
+    ///
+    /// Writes the same output as [`Editor::edit_buffered`]. On success,
+    /// returns the total number of bytes written to `output`. Useful for
+    /// reporting progress on large inputs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::error::Error;
+    /// # use std::io::Cursor;
+    /// # use linurgy::factory;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let editor = factory::appender("---", 1);
+    /// let mut input = Cursor::new("foo\nbar");
+    /// let mut output = Vec::new();
+    /// let written = editor.edit_buffered_written(&mut input, &mut output)?;
+    /// assert_eq!(10, written);
+    /// assert_eq!(output.len(), written);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn edit_buffered_written<I, O>(
+        &self,
+        input: &mut I,
+        output: &mut O,
+    ) -> Result<usize, EditError>
+    where
+        I: BufRead,
+        O: Write,
+    {
+        let (_, written) = self.edit_buffered_inner(input, output, BUFSIZE, false)?;
+        Ok(written)
+    }
+
+    /// Edit the input buffer's newlines into the output writer, with a
+    /// custom internal buffer capacity
+    ///
+    /// Writes the same output as [`Editor::edit_buffered`], which uses a
+    /// capacity of 1024 bytes. The input buffer holds a single line at a
+    /// time, and edited text is written once it reaches `capacity` bytes, so
+    /// callers who know their average line length can tune this to reduce
+    /// reallocations and writes. Lines longer than `capacity` are still
+    /// edited correctly.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::error::Error;
+    /// # use std::io::Cursor;
+    /// # use std::str::from_utf8;
+    /// # use linurgy::factory;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let editor = factory::replacer(" ", 1);
+    /// let mut input = Cursor::new("foo\nbar");
+    /// let mut output = Vec::new();
+    /// editor.edit_buffered_with_capacity(&mut input, &mut output, 64 * 1024)?;
+    /// assert_eq!("foo bar", from_utf8(&output)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    pub fn edit_buffered_with_capacity<I, O>(
+        &self,
+        input: &mut I,
+        output: &mut O,
+        capacity: usize,
+    ) -> Result<(), EditError>
+    where
+        I: BufRead,
+        O: Write,
+    {
+        self.edit_buffered_inner(input, output, capacity, false)?;
+        Ok(())
+    }
+
+    /// Edit the input buffer's newlines into the output writer, flushing
+    /// after every line
+    ///
+    /// Writes the same output as [`Editor::edit_buffered`], but each line is
+    /// written and `output` flushed as soon as it has been edited. Use this
+    /// when the output is watched as it arrives, such as piping a log file
+    /// being written to, so other programs see each line straight away.
+    ///
+    /// Writing and flushing every line is much slower than the larger writes
+    /// of [`Editor::edit_buffered`], so only use this when latency matters
+    /// more than throughput. Newlines which may end the output are still held
+    /// back until more text follows, as they are for
+    /// [`Editor::ensure_trailing_newline`].
+    ///
+    /// # Errors
+    ///
+    /// As for [`Editor::edit_buffered`], with a failed flush reported as
+    /// [`EditError::WriteFailed`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use std::io::{stdin, stdout};
+    /// # use linurgy::{factory, EditError};
+    /// # fn main() -> Result<(), EditError> {
+    /// let editor = factory::appender("---\n", 1);
+    /// editor.edit_buffered_flushing(&mut stdin().lock(), &mut stdout())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    pub fn edit_buffered_flushing<I, O>(
+        &self,
+        input: &mut I,
+        output: &mut O,
+    ) -> Result<(), EditError>
+    where
+        I: BufRead,
+        O: Write,
+    {
+        self.edit_buffered_inner(input, output, BUFSIZE, true)?;
+        Ok(())
+    }
+
+    /// Edit only the lines from `start_line` to `end_line`, inclusive
+    ///
+    /// Lines are numbered from 1, and each line includes the newline ending
+    /// it. Text outside the range is copied unchanged. The range is edited
+    /// as if it were the whole input, so a run of newlines crossing either
+    /// end of the range only counts the newlines inside it, and options such
+    /// as [`Editor::wrap`] apply to the range alone.
+    ///
+    /// A `start_line` of `0` is treated as `1`, and an `end_line` past the
+    /// last line edits to the end of the input. If `start_line` is after
+    /// `end_line` or past the last line, nothing is edited.
+    ///
+    /// # Examples
+    ///
+    /// Collapse the blank lines in the body, but not the header.
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::replacer("\n", 2);
+    /// let output = editor.edit_range("head\n\nfoo\n\nbar\n", 3, 5);
+    /// assert_eq!("head\n\nfoo\nbar\n", output);
+    /// ```
+    pub fn edit_range(&self, input: &str, start_line: usize, end_line: usize) -> String {
+        if self.line_ending == NewlineType::Auto {
+            return self.resolve(input).edit_range(input, start_line, end_line);
+        }
+
+        let start = self.line_offset(input, cmp::max(start_line, 1));
+        let end = self.line_offset(input, end_line.saturating_add(1));
+
+        if start >= end {
+            return input.to_string();
+        }
+
+        let mut output = String::with_capacity(input.len() + self.replace.len());
+        output.push_str(&input[..start]);
+        self.edit_into(&input[start..end], &mut output);
+        output.push_str(&input[end..]);
+
+        output
+    }
+
+    /// Byte offset where line number `line` of the input starts, or the
+    /// length of the input if it has fewer lines.
+    #[inline]
+    fn line_offset(&self, input: &str, line: usize) -> usize {
+        let end_char = self.line_ending.end_char();
+
+        if line <= 1 {
+            return 0;
+        }
+
+        input
+            .match_indices(end_char)
+            .nth(line - 2)
+            .map(|(index, _)| index + end_char.len_utf8())
+            .unwrap_or_else(|| input.len())
+    }
+
+    /// Split the input into segments separated by the `newlines` trigger
+    ///
+    /// Each time the trigger is met, the newlines are removed and a new
+    /// segment begins, instead of writing the `replace` string. Like
+    /// [`str::split`], separators at the start or end of the input produce
+    /// empty segments, and empty input produces a single empty segment.
+    ///
+    /// Runs longer than the trigger are split each time the trigger is met,
+    /// with any remaining newlines kept at the start of the next segment.
+    /// Text from [`Editor::wrap`] stays on the first and last segments.
+    ///
+    /// # Examples
+    ///
+    /// Split text into paragraphs.
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::replacer("", 2);
+    /// let paragraphs = editor.split("foo\nbar\n\nbaz\n\n");
+    /// assert_eq!(vec!["foo\nbar", "baz", ""], paragraphs);
+    /// ```
+    pub fn split(&self, input: &str) -> Vec<String> {
+        let mut output = String::with_capacity(input.len());
+        let mut splits = SplitAt(Vec::new());
+        self.edit_into_with(input, &mut output, &mut splits);
+
+        let mut segments = Vec::with_capacity(splits.0.len() + 1);
+        let mut start = 0;
+        for &end in &splits.0 {
+            segments.push(String::from(&output[start..end]));
+            start = end;
+        }
+        segments.push(String::from(&output[start..]));
+
+        segments
+    }
+
+    /// Edit the input's newlines, producing the output one line at a time
+    ///
+    /// Returns an iterator over the lines of the edited text, as if
+    /// [`str::lines`] were called on the output of [`Editor::edit`]. Lines are
+    /// split on `\n` with any trailing `\r` removed, so newlines inside the
+    /// `replace` string also start new lines.
+    ///
+    /// The input is edited lazily, a line at a time, as the iterator is
+    /// advanced.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::inserter("\n---", 2);
+    /// let mut lines = editor.edit_lines("foo\n\nbar");
+    /// assert_eq!(Some("foo".to_string()), lines.next());
+    /// assert_eq!(Some("---".to_string()), lines.next());
+    /// assert_eq!(Some("".to_string()), lines.next());
+    /// assert_eq!(Some("bar".to_string()), lines.next());
+    /// assert_eq!(None, lines.next());
+    /// ```
+    #[inline]
+    pub fn edit_lines<'a>(&'a self, input: &'a str) -> impl Iterator<Item = String> + 'a {
+        let mut state = BufState::new(self);
+        state.resolve(input);
+
+        EditLines {
+            input,
+            state,
+            edited: String::new(),
+            finished: false,
+        }
+    }
+
+    /// Edit the input's newlines, then reverse the order of its lines
+    ///
+    /// The output of [`Editor::edit`] is split into lines on this editor's
+    /// output [`NewlineType`], and the lines are joined back together last
+    /// to first. A trailing newline ends the last line rather than starting
+    /// an empty one, so it stays at the end of the output. Every newline
+    /// written between lines is the output newline type, so a CRLF editor
+    /// writes `\r\n` between lines even if they ended with a bare `\n`.
+    ///
+    /// # Examples
+    ///
+    /// Show the newest log entries first, without blank lines.
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::replacer("\n", 2);
+    /// assert_eq!("3\n2\n1\n", editor.edit_reversed("1\n\n2\n3\n"));
+    /// assert_eq!("3\n2\n1", editor.edit_reversed("1\n\n2\n3"));
+    /// ```
+    pub fn edit_reversed(&self, input: &str) -> String {
+        if self.line_ending == NewlineType::Auto {
+            return self.resolve(input).edit_reversed(input);
+        }
+
+        let edited = self.edit(input);
+        let newline = self.output_ending();
+        let end_char = newline.end_char();
+
+        let trailing = edited.ends_with(end_char);
+        let text = if trailing {
+            &edited[..edited.len() - end_char.len_utf8()]
+        } else {
+            &edited[..]
+        };
+
+        let mut output = String::with_capacity(edited.len());
+        for (index, mut line) in text.rsplit(end_char).enumerate() {
+            if index > 0 {
+                output.push_str(newline.as_str());
+            }
+            if newline == NewlineType::Crlf && line.ends_with('\r') {
+                line = &line[..line.len() - 1];
+            }
+            output.push_str(line);
+        }
+
+        if trailing {
+            output.push_str(newline.as_str());
+        }
+
+        output
+    }
+
+    /// Remove the indentation shared by every line, then edit the input's
+    /// newlines
+    ///
+    /// Finds the longest run of spaces and tabs that every non-blank line
+    /// starts with, and removes it from each of them before editing, like
+    /// Python's `textwrap.dedent`. Lines keep any indentation beyond that,
+    /// so nested code stays nested. Lines of only whitespace are ignored
+    /// when finding the shared indentation, and lose their leading spaces
+    /// and tabs. A tab and a space never match each other.
+    ///
+    /// This differs from
+    /// [`strip_leading_whitespace`](Editor::strip_leading_whitespace), which
+    /// removes all indentation from every line on its own. Finding what the
+    /// lines share needs the whole input first, so there is no buffered
+    /// version of this method.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let input = "    if x {\n        y();\n\n    }\n";
+    ///
+    /// let editor = factory::replacer("\n", 2);
+    /// assert_eq!("if x {\n    y();\n}\n", editor.edit_dedented(input));
+    ///
+    /// let editor = editor.strip_leading_whitespace(true);
+    /// assert_eq!("if x {\ny();\n}\n", editor.edit(input));
+    /// ```
+    pub fn edit_dedented(&self, input: &str) -> String {
+        if self.line_ending == NewlineType::Auto {
+            return self.resolve(input).edit_dedented(input);
+        }
+
+        let end_char = self.line_ending.end_char();
+        let margin = input
+            .split(end_char)
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| &line[..line.len() - line.trim_start_matches(is_space_or_tab).len()])
+            .fold(None, |margin: Option<&str>, indent| match margin {
+                // whitespace is ASCII, so any byte index is a char boundary
+                Some(margin) => {
+                    let len = margin
+                        .bytes()
+                        .zip(indent.bytes())
+                        .take_while(|&(a, b)| a == b)
+                        .count();
+                    Some(&margin[..len])
+                }
+                None => Some(indent),
+            })
+            .unwrap_or("");
+
+        let mut dedented = String::with_capacity(input.len());
+        for (index, line) in input.split(end_char).enumerate() {
+            if index > 0 {
+                dedented.push(end_char);
+            }
+            if line.trim().is_empty() {
+                dedented.push_str(line.trim_start_matches(is_space_or_tab));
+            } else {
+                dedented.push_str(&line[margin.len()..]);
+            }
+        }
+
+        self.edit(dedented)
+    }
+
+    /// Create an editor which undoes this one's edits
+    ///
+    /// Editors only match runs of newlines, so an edit can only be reversed
+    /// by another editor when the `replace` string is made up entirely of
+    /// this editor's line endings. Returns `None` when the edit can't be
+    /// undone, which is the case when:
+    ///
+    /// - The `replace` string contains any other text, such as the markers
+    ///   added by [`factory::appender`].
+    /// - It contains fewer newlines than the `newlines` trigger, so runs of
+    ///   different lengths can produce the same output.
+    /// - Output is altered by [`Editor::ensure_trailing_newline`] or
+    ///   [`Editor::wrap`].
+    /// - Edits are limited by [`Editor::max_edits`] or [`Editor::skip_edits`].
+    ///
+    /// A no-op editor (trigger of 0) is its own inverse.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let double = factory::appender("\n", 1);
+    /// let single = double.inverse().unwrap();
+    /// assert_eq!(factory::replacer("\n", 2), single);
+    ///
+    /// let input = "foo\nbar\n\nbaz\n";
+    /// assert_eq!(input, single.edit(double.edit(input)));
+    ///
+    /// assert_eq!(None, factory::appender("---", 2).inverse());
+    /// ```
+    pub fn inverse(&self) -> Option<Editor> {
+        if self.trailing_newline
+            || self.is_wrapped()
+            || self.max_edits.is_some()
+            || self.skip_edits > 0
+            || self.strip_cr
+            || self.reversed_crlf
+            || self.blank_whitespace
+            || self.lines_affect_scan()
+            || self.line_ending == NewlineType::Auto
+            || self.output_ending.is_some()
+            || self.trigger_mode != TriggerMode::Exactly
+            || self.template
+            || self.fallback.is_some()
+        {
+            return None;
+        }
+
+        if self.newlines == 0 {
+            return Some(Editor::new(String::new(), 0, self.line_ending));
+        }
+
+        let replace_newlines = self.replace_newlines()?;
+        if replace_newlines < self.newlines {
+            return None;
+        }
+
+        let replace = self.line_ending.as_str().repeat(self.newlines);
+
+        Some(Editor::new(replace, replace_newlines, self.line_ending))
+    }
+
+    /// Number of line endings in the `replace` string, if that is all it
+    /// contains.
+    #[inline]
+    fn replace_newlines(&self) -> Option<usize> {
+        let newline = self.line_ending.as_str();
+        let count = self.replace.len() / newline.len();
+
+        // compared without building the run of newlines, so no-op editors
+        // can be found without allocating
+        if self.replace.len() == newline.len() * count
+            && self.replace.matches(newline).count() == count
+        {
+            Some(count)
+        } else {
+            None
+        }
+    }
+
+    /// Chain another editor after this one
+    ///
+    /// Returns a [`Pipeline`] which runs this editor first, then `next` on
+    /// its output.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let pipeline = factory::replacer("\n", 2).then(factory::inserter(";", 1));
+    /// let output = pipeline.edit("foo\n\nbar\nbaz");
+    /// assert_eq!("foo;\nbar;\nbaz", output);
+    /// ```
+    #[inline]
+    pub fn then(self, next: Editor) -> Pipeline {
+        Pipeline::new(vec![self, next])
+    }
+
+    /// Can the edited text always be written over the input already read.
+    #[inline]
+    fn fits_in_place(&self, text: &str) -> bool {
+        if self.is_wrapped()
+            || self.blank_whitespace
+            || self.lines_affect_scan()
+            || self.reversed_crlf
+            || self.output_ending.is_some()
+            || self.trigger_mode != TriggerMode::Exactly
+            || self.template
+            || self.fallback.is_some()
+        {
+            return false;
+        }
+
+        let newlines = self.newlines;
+        match self.line_ending {
+            NewlineType::Lf => self.replace.len() <= newlines,
+            NewlineType::Crlf => {
+                self.replace.len() <= newlines * 2
+                    && text.matches('\n').count() == text.matches("\r\n").count()
+            }
+            // bytes are edited on `\n`, so separators are never in place
+            NewlineType::LineSeparator | NewlineType::ParagraphSeparator => false,
+            // edited once the line ending is known
+            NewlineType::Auto => false,
+        }
+    }
+
+    /// Edit the input buffer into the output writer using buffers of the
+    /// given capacity.
+    ///
+    /// Returns the number of edits made and the number of bytes written.
+    #[cfg(feature = "std")]
+    fn edit_buffered_inner<I, O>(
+        &self,
+        input: &mut I,
+        output: &mut O,
+        capacity: usize,
+        flush: bool,
+    ) -> Result<(usize, usize), EditError>
+    where
+        I: BufRead,
+        O: Write,
+    {
+        if self.is_noop() {
+            let written = copy_buffered(input, output, flush)?;
+            return Ok((0, written));
+        }
+
+        let mut state = BufState::new(self);
+        let mut buf = String::with_capacity(capacity);
+        let mut edited = String::with_capacity(capacity);
+
+        let mut lines = 0;
+        let mut written = 0;
+
+        // edited lines are collected to make fewer, larger writes
+        while read_line(input, &mut buf, &mut lines, self.line_ending)? {
+            state.push_line(&buf, &mut edited);
+            if flush || edited.len() >= capacity {
+                written += write_str(output, &edited)?;
+                edited.clear();
+            }
+            if flush {
+                output.flush().map_err(EditError::WriteFailed)?;
+            }
+        }
+
+        state.finish(&mut edited);
+        written += write_str(output, &edited)?;
+        if flush {
+            output.flush().map_err(EditError::WriteFailed)?;
+        }
+
+        Ok((state.edits(), written))
+    }
+
+    /// Edit the input, pushing the result onto the end of `output`.
+    ///
+    /// Returns the number of edits made.
+    #[inline]
+    pub(crate) fn edit_into<O>(&self, input: &str, output: &mut O) -> usize
+    where
+        O: Output,
+    {
+        self.edit_into_spans(input, output, None)
+    }
+
+    /// Edit the input, pushing the result onto the end of `output` and the
+    /// span of each edit onto `spans`.
+    ///
+    /// Returns the number of edits made.
+    ///
+    /// Every in-memory path which counts or reports edits comes through
+    /// here, so this is where they all decide a [no-op](Editor::is_noop)
+    /// editor copies the input and makes no edits, as the buffered path
+    /// does.
+    pub(crate) fn edit_into_spans<O>(
+        &self,
+        input: &str,
+        output: &mut O,
+        spans: Option<&mut Vec<EditSpan>>,
+    ) -> usize
+    where
+        O: Output,
+    {
+        if self.line_ending == NewlineType::Auto {
+            return self.resolve(input).edit_into_spans(input, output, spans);
+        }
+
+        if self.is_noop() {
+            output.push_str(input);
+            return 0;
+        }
+
+        match (self.template, spans) {
+            (true, Some(spans)) => {
+                let mut replace = Spans::new(Template(&self.replace), spans);
+                self.edit_into_with(input, output, &mut replace)
+            }
+            (true, None) => self.edit_into_with(input, output, &mut Template(&self.replace)),
+            (false, Some(spans)) => {
+                let mut replace = Spans::new(self.replace.as_str(), spans);
+                self.edit_into_with(input, output, &mut replace)
+            }
+            (false, None) => self.edit_into_with(input, output, &mut self.replace.as_str()),
+        }
+    }
+
+    /// Edit the input with `replace`, pushing the result onto the end of
+    /// `output`.
+    ///
+    /// Returns the number of edits made.
+    #[inline]
+    pub(crate) fn edit_into_with<O, R>(&self, input: &str, output: &mut O, replace: &mut R) -> usize
+    where
+        O: Output,
+        R: Replacement,
+    {
+        if self.line_ending == NewlineType::Auto {
+            return self.resolve(input).edit_into_with(input, output, replace);
+        }
+
+        let start = output.len();
+
+        let mut scan = Scan::new(self);
+        scan.push_str(self, input, output, replace);
+        scan.finish(self, output, replace);
+
+        if self.trailing_newline {
+            self.end_with_newline(output, start);
+        }
+
+        scan.edits(self)
+    }
+
+    /// This editor, or a copy for the line ending detected in `input` if it
+    /// is [`NewlineType::Auto`].
+    #[inline]
+    pub(crate) fn resolve(&self, input: &str) -> Cow<'_, Editor> {
+        match self.line_ending {
+            NewlineType::Auto => Cow::Owned(self.with_line_ending(NewlineType::detect(input))),
+            _ => Cow::Borrowed(self),
+        }
+    }
+
+    /// Replace any newlines at the end of `output[start..]` with a single
+    /// line ending.
+    #[inline]
+    pub(crate) fn end_with_newline<O>(&self, output: &mut O, start: usize)
+    where
+        O: Output,
+    {
+        if output.len() > start {
+            let len = output.len() - self.trailing_newlines_len(&output.as_str()[start..]);
+            output.truncate(len);
+            output.push_str(self.output_ending().as_str());
+        }
+    }
+
+    /// Length in bytes of the run of newlines at the end of `text`.
+    #[inline]
+    fn trailing_newlines_len(&self, text: &str) -> usize {
+        match self.output_ending() {
+            // a `\r` is only part of a newline if `\n` comes next
+            NewlineType::Crlf => {
+                let bytes = text.as_bytes();
+                let mut end = bytes.len();
+                loop {
+                    if bytes[..end].ends_with(b"\r\n") {
+                        end -= 2;
+                    } else if bytes[..end].ends_with(b"\n") {
+                        end -= 1;
+                    } else {
+                        break;
+                    }
+                }
+                text.len() - end
+            }
+            line_ending => text.len() - text.trim_end_matches(line_ending.end_char()).len(),
+        }
+    }
+}
+
+/// What happens to the newlines of a run as they are counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tail {
+    /// Still counting towards the trigger
+    Counting,
+
+    /// The run is too long to be edited as a whole, so it is left as it is
+    Passing,
+}
+
+impl Default for Tail {
+    #[inline]
+    fn default() -> Self {
+        Tail::Counting
+    }
+}
+
+/// Text an edit is pushed onto.
+pub(crate) trait Output: fmt::Write {
+    /// Push `text` onto the end.
+    fn push_str(&mut self, text: &str);
+
+    /// The text pushed so far.
+    fn as_str(&self) -> &str;
+
+    /// Shorten to `len` bytes.
+    fn truncate(&mut self, len: usize);
+
+    /// Length of the text pushed so far, in bytes.
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.as_str().len()
+    }
+}
+
+impl Output for String {
+    #[inline(always)]
+    fn push_str(&mut self, text: &str) {
+        String::push_str(self, text);
+    }
+
+    #[inline(always)]
+    fn as_str(&self) -> &str {
+        self
+    }
+
+    #[inline(always)]
+    fn truncate(&mut self, len: usize) {
+        String::truncate(self, len);
+    }
+}
+
+/// Output which borrows the input for as long as the two are the same.
+///
+/// Nothing is allocated until the text pushed first differs from the input.
+/// Then the input matched so far is copied into a new string, and the rest
+/// of the output is pushed onto that.
+pub(crate) struct CowOutput<'a> {
+    input: &'a str,
+
+    /// Length of the input the output matches, until they differ.
+    len: usize,
+
+    /// The output, once it differs from the input.
+    owned: Option<String>,
+}
+
+impl<'a> CowOutput<'a> {
+    #[inline]
+    pub(crate) fn new(input: &'a str) -> Self {
+        CowOutput {
+            input,
+            len: 0,
+            owned: None,
+        }
+    }
+
+    /// The output, borrowing the input if it is the same text.
+    #[inline]
+    pub(crate) fn into_cow(self) -> Cow<'a, str> {
+        match self.owned {
+            None if self.len == self.input.len() => Cow::Borrowed(self.input),
+            None => Cow::Owned(String::from(&self.input[..self.len])),
+            // a change can be undone, such as a newline trimmed then pushed
+            // again
+            Some(owned) => {
+                if owned == self.input {
+                    Cow::Borrowed(self.input)
+                } else {
+                    Cow::Owned(owned)
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Output for CowOutput<'a> {
+    #[inline]
+    fn push_str(&mut self, text: &str) {
+        if let Some(ref mut owned) = self.owned {
+            owned.push_str(text);
+        } else if self.input[self.len..].starts_with(text) {
+            self.len += text.len();
+        } else {
+            let mut owned = String::with_capacity(self.input.len() + text.len());
+            owned.push_str(&self.input[..self.len]);
+            owned.push_str(text);
+            self.owned = Some(owned);
+        }
+    }
+
+    #[inline]
+    fn as_str(&self) -> &str {
+        match self.owned {
+            Some(ref owned) => owned,
+            None => &self.input[..self.len],
+        }
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        match self.owned {
+            Some(ref mut owned) => owned.truncate(len),
+            None => self.len = len,
+        }
+    }
+}
+
+impl<'a> fmt::Write for CowOutput<'a> {
+    #[inline]
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        self.push_str(text);
+        Ok(())
+    }
+}
+
+/// Source of the text written each time the trigger is met.
+pub(crate) trait Replacement {
+    /// Push the replacement for match number `index`, a run of `newlines`
+    /// newlines at `matched` in the input, onto `output`.
+    fn push_to<O>(&mut self, output: &mut O, index: usize, newlines: usize, matched: Range<usize>)
+    where
+        O: Output;
+}
+
+impl Replacement for &str {
+    #[inline(always)]
+    fn push_to<O>(
+        &mut self,
+        output: &mut O,
+        _index: usize,
+        _newlines: usize,
+        _matched: Range<usize>,
+    ) where
+        O: Output,
+    {
+        output.push_str(self);
+    }
+}
+
+/// Replacement computed by a closure for each match.
+struct ReplaceFn<F>(F);
+
+impl<F> Replacement for ReplaceFn<F>
+where
+    F: FnMut(usize) -> String,
+{
+    #[inline(always)]
+    fn push_to<O>(&mut self, output: &mut O, index: usize, _newlines: usize, _matched: Range<usize>)
+    where
+        O: Output,
+    {
+        output.push_str(&(self.0)(index));
+    }
+}
+
+/// Replacement computed by a closure from the length of each run replaced.
+struct ReplaceRunFn<F>(F);
+
+impl<F, S> Replacement for ReplaceRunFn<F>
+where
+    F: FnMut(usize) -> S,
+    S: AsRef<str>,
+{
+    #[inline(always)]
+    fn push_to<O>(&mut self, output: &mut O, _index: usize, newlines: usize, _matched: Range<usize>)
+    where
+        O: Output,
+    {
+        output.push_str((self.0)(newlines).as_ref());
+    }
+}
+
+/// Replacement with placeholders expanded for each match.
+struct Template<'a>(&'a str);
+
+impl<'a> Replacement for Template<'a> {
+    #[inline]
+    fn push_to<O>(&mut self, output: &mut O, index: usize, newlines: usize, _matched: Range<usize>)
+    where
+        O: Output,
+    {
+        push_template(output, self.0, index + 1, newlines);
+    }
+}
+
+/// The replacement an editor writes itself, expanding placeholders if it is
+/// a template.
+struct OwnReplacement<'a>(&'a Editor);
+
+impl<'a> Replacement for OwnReplacement<'a> {
+    #[inline]
+    fn push_to<O>(&mut self, output: &mut O, index: usize, newlines: usize, _matched: Range<usize>)
+    where
+        O: Output,
+    {
+        if self.0.template {
+            push_template(output, &self.0.replace, index + 1, newlines);
+        } else {
+            output.push_str(&self.0.replace);
+        }
+    }
+}
+
+/// Whether `text` contains any character which is part of a newline.
+#[inline]
+fn contains_newline(text: &str) -> bool {
+    text.contains(|c| c == '\r' || c == '\n' || c == '\u{2028}' || c == '\u{2029}')
+}
+
+/// Call `on_line` with each line completed by `text`, holding the start of
+/// the next line in `line`.
+#[cfg(feature = "std")]
+fn call_lines<F>(text: &str, line: &mut String, end_char: char, on_line: &mut F)
+where
+    F: FnMut(&str),
+{
+    let mut rest = text;
+
+    while let Some(index) = rest.find(end_char) {
+        let (complete, next) = rest.split_at(index + end_char.len_utf8());
+        if line.is_empty() {
+            on_line(complete);
+        } else {
+            line.push_str(complete);
+            on_line(line);
+            line.clear();
+        }
+        rest = next;
+    }
+
+    line.push_str(rest);
+}
+
+/// Is `c` whitespace which can be trimmed from a line.
+#[inline(always)]
+fn is_space_or_tab(c: char) -> bool {
+    c == ' ' || c == '\t'
+}
+
+/// Push `template` onto `output`, with `{n}` expanded to `number` and
+/// `{count}` to `count`.
+///
+/// `{{` and `}}` are literal braces. Any other brace is kept as it is.
+pub(crate) fn push_template<O>(output: &mut O, template: &str, number: usize, count: usize)
+where
+    O: Output,
+{
+    let mut rest = template;
+
+    while let Some(index) = rest.find(|c| c == '{' || c == '}') {
+        output.push_str(&rest[..index]);
+        rest = &rest[index..];
+
+        let len = if rest.starts_with("{n}") {
+            let _ = fmt::Write::write_fmt(output, format_args!("{}", number));
+            "{n}".len()
+        } else if rest.starts_with("{count}") {
+            let _ = fmt::Write::write_fmt(output, format_args!("{}", count));
+            "{count}".len()
+        } else if rest.starts_with("{{") || rest.starts_with("}}") {
+            output.push_str(&rest[..1]);
+            2
+        } else {
+            output.push_str(&rest[..1]);
+            1
+        };
+
+        rest = &rest[len..];
+    }
+
+    output.push_str(rest);
+}
+
+/// Records where each match is in the output, writing nothing.
+///
+/// Only text a replacement writes is ever taken back off the output, so the
+/// offsets stay within it.
+struct SplitAt(Vec<usize>);
+
+impl Replacement for SplitAt {
+    #[inline(always)]
+    fn push_to<O>(
+        &mut self,
+        output: &mut O,
+        _index: usize,
+        _newlines: usize,
+        _matched: Range<usize>,
+    ) where
+        O: Output,
+    {
+        self.0.push(output.len());
+    }
+}
+
+/// Read the next line of `input` into `buf`, replacing its contents.
+///
+/// Lines end with the last character of `line_ending`. `lines` counts the
+/// lines read so far, so errors can report where they happened. Returns
+/// `false` at EOF.
+#[cfg(feature = "std")]
+#[inline]
+pub(crate) fn read_line<I>(
+    input: &mut I,
+    buf: &mut String,
+    lines: &mut usize,
+    line_ending: NewlineType,
+) -> Result<bool, EditError>
+where
+    I: BufRead,
+{
+    buf.clear();
+
+    let len = match line_ending {
+        NewlineType::Lf | NewlineType::Crlf | NewlineType::Auto => input.read_line(buf),
+        _ => read_separated(input, buf, line_ending.as_bytes()),
+    }
+    .map_err(|error| EditError::read(error, *lines + 1))?;
+
+    // EOF
+    if len == 0 {
+        return Ok(false);
+    }
+
+    *lines += 1;
+    Ok(true)
+}
+
+/// Read into `buf` up to and including the next `separator`.
+///
+/// Like [`BufRead::read_line`], but splits on a multi-byte separator.
+#[cfg(feature = "std")]
+fn read_separated<I>(input: &mut I, buf: &mut String, separator: &[u8]) -> io::Result<usize>
+where
+    I: BufRead,
+{
+    let mut bytes = mem::replace(buf, String::new()).into_bytes();
+    let len = read_until_separator(input, &mut bytes, separator)?;
+
+    *buf = String::from_utf8(bytes)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+
+    Ok(len)
+}
+
+/// Read the next line of `input` into `buf` as bytes, replacing its
+/// contents.
+///
+/// Like [`read_line`], but the line does not need to be valid UTF-8.
+#[cfg(feature = "std")]
+pub(crate) fn read_line_bytes<I>(
+    input: &mut I,
+    buf: &mut Vec<u8>,
+    line_ending: NewlineType,
+) -> Result<bool, EditError>
+where
+    I: BufRead,
+{
+    buf.clear();
+
+    let len = match line_ending {
+        NewlineType::Lf | NewlineType::Crlf | NewlineType::Auto => input.read_until(b'\n', buf),
+        _ => read_until_separator(input, buf, line_ending.as_bytes()),
+    }
+    .map_err(EditError::Io)?;
+
+    // EOF
+    Ok(len > 0)
+}
+
+/// Append bytes up to and including the next `separator` onto `buf`,
+/// returning the number of bytes read.
+#[cfg(feature = "std")]
+fn read_until_separator<I>(input: &mut I, buf: &mut Vec<u8>, separator: &[u8]) -> io::Result<usize>
+where
+    I: BufRead,
+{
+    let last = separator[separator.len() - 1];
+    let start = buf.len();
+
+    loop {
+        // EOF
+        if input.read_until(last, buf)? == 0 || buf.ends_with(separator) {
+            break;
+        }
+    }
+
+    Ok(buf.len() - start)
+}
+
+/// Write edited text to `output`, returning the number of bytes written.
+#[cfg(feature = "std")]
+#[inline]
+pub(crate) fn write_str<O>(output: &mut O, text: &str) -> Result<usize, EditError>
+where
+    O: Write,
+{
+    output
+        .write_all(text.as_bytes())
+        .map_err(EditError::WriteFailed)?;
+
+    Ok(text.len())
+}
+
+/// Newline counting state for editing input one line at a time.
+///
+/// This is the one scan every edit goes through. Input read from a buffer
+/// is pushed as each line is read, and input in memory is split into the
+/// same lines by [`Scan::push_str`].
+///
+/// Lines are expected in the form produced by [`read_line`]: each one ends
+/// with the editor's newline, except possibly the last.
+#[derive(Debug)]
+pub(crate) struct Scan {
+    /// Number of newlines counted towards the trigger.
+    newlines: usize,
+
+    /// Number of times the trigger has been met, including skipped runs.
+    matches: usize,
+
+    /// What happens to the rest of the current run of newlines.
+    tail: Tail,
+
+    /// Whether any input has been edited.
+    started: bool,
+
+    /// Whether the `open` at the end of the last replacement is held back
+    /// until the segment it opens has some content. If no more lines follow,
+    /// the segment is left out along with the final `close`.
+    open_held: bool,
+
+    /// Whether the last line ended with a `\n` which can start a reversed
+    /// `\n\r` newline.
+    bare_lf: bool,
+
+    /// Whether newlines are being removed from the start of the input.
+    leading: bool,
+
+    /// Newlines held back in case they end the input, to be removed.
+    trailing: usize,
+
+    /// Byte offset in the input just after its last text, where known.
+    /// Only newlines from here on can end the input.
+    content_end: usize,
+
+    /// Whether the input so far ends with text rather than a newline.
+    ends_with_text: bool,
+
+    /// Byte offset in the input of the next line.
+    offset: usize,
+
+    /// Byte offset in the input where the current run of newlines starts.
+    run_start: usize,
+
+    /// Byte offset in the input just after the last newline counted.
+    run_end: usize,
+}
+
+impl Scan {
+    #[inline]
+    pub(crate) fn new(editor: &Editor) -> Self {
+        Scan {
+            newlines: 0,
+            matches: 0,
+            tail: Tail::Counting,
+            started: false,
+            open_held: false,
+            bare_lf: false,
+            leading: editor.lines.trim_leading_newlines,
+            trailing: 0,
+            content_end: 0,
+            ends_with_text: false,
+            offset: 0,
+            run_start: 0,
+            run_end: 0,
+        }
+    }
+
+    /// Number of edits made so far.
+    #[inline]
+    pub(crate) fn edits(&self, editor: &Editor) -> usize {
+        editor.edits_made(self.matches)
+    }
+
+    /// Continue output which another scan has started, so `open` is not
+    /// pushed again.
+    #[cfg(feature = "rayon")]
+    #[inline]
+    pub(crate) fn resume(&mut self) {
+        self.started = true;
+    }
+
+    /// Edit `input`, which runs to the end of the text, pushing the result
+    /// onto `output`.
+    ///
+    /// As the whole of the rest of the text is known, newlines which end it
+    /// are found up front rather than held back.
+    #[inline]
+    pub(crate) fn push_str<O, R>(
+        &mut self,
+        editor: &Editor,
+        input: &str,
+        output: &mut O,
+        replace: &mut R,
+    ) where
+        O: Output,
+        R: Replacement,
+    {
+        if editor.lines.trim_trailing_newlines {
+            self.content_end = self.offset + editor.content_end(input);
+        }
+
+        let mut rest = input;
+        while !rest.is_empty() {
+            let end = editor.line_len(rest);
+            self.push_line(editor, &rest[..end], output, replace);
+            rest = &rest[end..];
+        }
+    }
+
+    /// Edit a single line, pushing the result onto `output`.
+    #[inline]
+    pub(crate) fn push_line<O, R>(
+        &mut self,
+        editor: &Editor,
+        line: &str,
+        output: &mut O,
+        replace: &mut R,
+    ) where
+        O: Output,
+        R: Replacement,
+    {
+        let joins_cr = editor.joins_reversed_crlf();
+        let mut line = line;
+
+        // a `\r` after a bare `\n` is the end of a reversed newline
+        if self.bare_lf && line.starts_with('\r') {
+            line = &line[1..];
+            self.offset += 1;
+        }
+        let end = self.offset + line.len();
+
+        // lines split in memory keep the `\r` with the rest of the newline
+        if joins_cr && line.ends_with("\n\r") {
+            line = &line[..line.len() - 1];
+            self.bare_lf = false;
+        } else {
+            self.bare_lf = joins_cr && line.ends_with('\n') && !line.ends_with("\r\n");
+        }
+
+        if !self.started && !line.is_empty() {
+            output.push_str(&editor.open);
+            self.started = true;
+        }
+
+        // held newlines don't end the input, so edit them as they came
+        if self.trailing > 0 && self.has_text(editor, line) {
+            for _ in 0..mem::replace(&mut self.trailing, 0) {
+                self.reopen(editor, output);
+                self.push_newline(editor, output, replace);
+            }
+        }
+
+        if !editor.trim_start(editor.trim_end(line)).is_empty() {
+            self.reopen(editor, output);
+        }
+
+        self.edit_line(editor, line, end, output, replace);
+    }
+
+    /// End the input, pushing the end of the last run of newlines and any
+    /// text which closes the output.
+    #[inline]
+    pub(crate) fn finish<O, R>(&mut self, editor: &Editor, output: &mut O, replace: &mut R)
+    where
+        O: Output,
+        R: Replacement,
+    {
+        self.end_run(editor, output, replace);
+
+        if self.ends_with_text && editor.appends_at_eof() {
+            output.push_str(&editor.text);
+        }
+
+        if self.started && !self.open_held {
+            output.push_str(&editor.close);
+        }
+    }
+
+    /// End a chunk of the input which more text follows, without ending the
+    /// output.
+    #[cfg(feature = "rayon")]
+    #[inline]
+    pub(crate) fn end_chunk<O, R>(&mut self, editor: &Editor, output: &mut O, replace: &mut R)
+    where
+        O: Output,
+        R: Replacement,
+    {
+        self.end_run(editor, output, replace);
+        self.reopen(editor, output);
+    }
+
+    /// Edit a line ending at byte offset `end` in the input.
+    #[inline]
+    fn edit_line<O, R>(
+        &mut self,
+        editor: &Editor,
+        line: &str,
+        end: usize,
+        output: &mut O,
+        replace: &mut R,
+    ) where
+        O: Output,
+        R: Replacement,
+    {
+        let text = match self.line_text(editor, line) {
+            Some(text) => text,
+            None => {
+                // no newline to count
+                let text = editor.trim_start(editor.trim_end(line));
+                if !text.is_empty() {
+                    self.ends_with_text = true;
+                }
+                self.push_text(editor, text, output, replace);
+                self.offset = end;
+                return;
+            }
+        };
+
+        let content = editor.trim_start(editor.trim_end(text));
+        if !content.is_empty() && !editor.is_blank(content) {
+            self.push_text(editor, content, output, replace);
+            self.leading = false;
+            self.ends_with_text = true;
+        }
+
+        // a run starts after the last text of its first line
+        let newline = self.offset + editor.trim_end(text).len();
+        if self.newlines == 0 {
+            self.run_start = newline;
+        }
+        self.offset = end;
+        self.run_end = end;
+
+        // newlines before any text are removed without being counted
+        if self.leading {
+            return;
+        }
+
+        // held newlines may yet be removed, leaving the text at the end
+        if editor.lines.trim_trailing_newlines && newline >= self.content_end {
+            self.trailing += 1;
+        } else {
+            self.push_newline(editor, output, replace);
+            self.ends_with_text = false;
+        }
+    }
+
+    /// Count a newline towards the trigger, pushing any edit onto `output`.
+    #[inline]
+    fn push_newline<O, R>(&mut self, editor: &Editor, output: &mut O, replace: &mut R)
+    where
+        O: Output,
+        R: Replacement,
+    {
+        let newline_str = editor.output_ending().as_str();
+        if self.tail == Tail::Passing {
+            output.push_str(newline_str);
+            return;
+        }
+
+        // limit reached, or a trigger which is never met, so newlines pass
+        // straight through
+        if editor.newlines == 0 || !editor.can_edit(self.matches) {
+            output.push_str(newline_str);
+            return;
+        }
+
+        match editor.whole_runs() {
+            None => {
+                self.newlines += 1;
+                if self.newlines < editor.newlines {
+                    return;
+                }
+
+                self.edit_run(editor, output, replace);
+            }
+            Some((_, longest)) if self.newlines == longest => {
+                // too long to edit
+                self.flush(editor, output);
+                output.push_str(newline_str);
+                self.tail = Tail::Passing;
+            }
+            Some(_) => self.newlines += 1,
+        }
+    }
+
+    /// Replace the run of newlines which meets the trigger, or pass it
+    /// through if it is skipped.
+    #[inline]
+    fn edit_run<O, R>(&mut self, editor: &Editor, output: &mut O, replace: &mut R)
+    where
+        O: Output,
+        R: Replacement,
+    {
+        if editor.skips(self.matches) {
+            self.flush(editor, output);
+        } else {
+            let start = output.len();
+            let index = editor.edits_made(self.matches);
+            replace.push_to(output, index, self.newlines, self.run_start..self.run_end);
+
+            // the segment this opens is left out if it ends the input empty,
+            // which is decided before anything after it is pushed
+            let open = editor.open.as_str();
+            if editor.skip_empty_end && output.as_str()[start..].ends_with(open) {
+                output.truncate(output.len() - open.len());
+                self.open_held = true;
+            }
+            self.newlines = 0;
+        }
+        self.matches += 1;
+    }
+
+    /// End the current run of newlines, editing it if it is replaced as a
+    /// whole.
+    #[inline]
+    fn end_run<O, R>(&mut self, editor: &Editor, output: &mut O, replace: &mut R)
+    where
+        O: Output,
+        R: Replacement,
+    {
+        self.tail = Tail::Counting;
+
+        match editor.whole_runs() {
+            Some((shortest, _)) if self.newlines > 0 && self.newlines >= shortest => {
+                self.edit_run(editor, output, replace);
+            }
+            _ => match editor.fallback {
+                Some(ref fallback) if self.newlines > 0 => {
+                    output.push_str(fallback);
+                    self.newlines = 0;
+                }
+                _ => self.flush(editor, output),
+            },
+        }
+    }
+
+    /// Push text which ends the current run of newlines.
+    #[inline]
+    fn push_text<O, R>(&mut self, editor: &Editor, text: &str, output: &mut O, replace: &mut R)
+    where
+        O: Output,
+        R: Replacement,
+    {
+        self.end_run(editor, output, replace);
+
+        // the run was only edited now, so reopen its segment here
+        if !text.is_empty() {
+            self.reopen(editor, output);
+        }
+
+        output.push_str(text);
+    }
+
+    /// Push any `open` held back, as the segment it opens has content.
+    #[inline]
+    fn reopen<O>(&mut self, editor: &Editor, output: &mut O)
+    where
+        O: Output,
+    {
+        if mem::replace(&mut self.open_held, false) {
+            output.push_str(&editor.open);
+        }
+    }
+
+    /// Does the line have any text which is kept, so any newlines before it
+    /// are not the end of the input.
+    #[inline]
+    fn has_text(&self, editor: &Editor, line: &str) -> bool {
+        match self.line_text(editor, line) {
+            Some(text) => {
+                let content = editor.trim_start(editor.trim_end(text));
+                !content.is_empty() && !editor.is_blank(content)
+            }
+            None => !editor.trim_start(editor.trim_end(line)).is_empty(),
+        }
+    }
+
+    /// The line without its newline, or `None` if it doesn't end with one.
+    ///
+    /// As with [`Editor::edit`], CRLF editors also count a bare `\n` as a
+    /// newline.
+    #[inline]
+    fn line_text<'l>(&self, editor: &Editor, line: &'l str) -> Option<&'l str> {
+        let line_end = editor.line_ending.end_char();
+
+        if !line.ends_with(line_end) {
+            return None;
+        }
+
+        let mut end = line.len() - line_end.len_utf8();
+        if editor.skips_cr() && line[..end].ends_with('\r') {
+            end -= 1;
+        }
+
+        Some(&line[..end])
+    }
+
+    #[inline]
+    fn flush<O>(&mut self, editor: &Editor, output: &mut O)
+    where
+        O: Output,
+    {
+        let newline_str = editor.output_ending().as_str();
+        while self.newlines > 0 {
+            output.push_str(newline_str);
+            self.newlines -= 1;
+        }
+    }
+}
+
+/// [`Scan`] of input read from a buffer, a line at a time.
+///
+/// Output is pushed as each line is edited, so an editor which ends with a
+/// newline holds back newlines in case they end the output.
+#[derive(Debug)]
+pub(crate) struct BufState<'a> {
+    editor: Cow<'a, Editor>,
+    scan: Scan,
+
+    /// Newlines held back in case they end the output.
+    held: String,
+
+    /// Whether anything other than newlines has been output.
+    emitted: bool,
+}
+
+impl<'a> BufState<'a> {
+    #[inline]
+    pub(crate) fn new(editor: &'a Editor) -> Self {
+        Self {
+            editor: Cow::Borrowed(editor),
+            scan: Scan::new(editor),
+            held: String::new(),
+            emitted: false,
+        }
+    }
+
+    /// Number of edits made so far.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub(crate) fn edits(&self) -> usize {
+        self.scan.edits(&self.editor)
+    }
+
+    /// The editor, once its line ending is resolved.
+    #[inline]
+    pub(crate) fn editor(&self) -> &Editor {
+        &self.editor
+    }
+
+    /// The character each line ends with.
+    #[inline]
+    pub(crate) fn line_end(&self) -> char {
+        self.editor.line_ending.end_char()
+    }
+
+    /// Detect the line ending from `input` if the editor is
+    /// [`NewlineType::Auto`].
+    ///
+    /// Otherwise it is detected from the first line pushed.
+    #[inline]
+    pub(crate) fn resolve(&mut self, input: &str) {
+        if self.editor.line_ending == NewlineType::Auto {
+            let editor = self.editor.with_line_ending(NewlineType::detect(input));
+            self.editor = Cow::Owned(editor);
+        }
+    }
+
+    /// Edit a single line, pushing the result onto `output`.
+    #[inline]
+    pub(crate) fn push_line(&mut self, line: &str, output: &mut String) {
+        self.resolve(line);
+        let start = output.len();
+        output.push_str(&self.held);
+        self.held.clear();
+
+        let editor = &*self.editor;
+        self.scan
+            .push_line(editor, line, output, &mut OwnReplacement(editor));
+
+        if self.editor.trailing_newline {
+            self.hold_trailing(output, start);
+        }
+    }
+
+    /// Push any trailing newlines onto `output`.
+    #[inline]
+    pub(crate) fn finish(&mut self, output: &mut String) {
+        let start = output.len();
+        output.push_str(&self.held);
+        self.held.clear();
+
+        let editor = &*self.editor;
+        self.scan
+            .finish(editor, output, &mut OwnReplacement(editor));
+
+        if self.editor.trailing_newline {
+            self.hold_trailing(output, start);
+            // a held `\r` not followed by `\n` is content
+            let newlines = self.editor.trailing_newlines_len(&self.held);
+            output.push_str(&self.held[..self.held.len() - newlines]);
+            if self.emitted || !self.held.is_empty() {
+                output.push_str(self.editor.output_ending().as_str());
+            }
+            self.held.clear();
+        }
+    }
+
+    /// Hold back newlines at the end of `output[start..]` until more
+    /// content follows them.
+    ///
+    /// For CRLF editors a `\r` at the very end is also held, along with the
+    /// newlines before it, as it forms a newline if the next text starts
+    /// with `\n`.
+    #[inline]
+    fn hold_trailing(&mut self, output: &mut String, start: usize) {
+        let mut end = output.len();
+        if self.editor.output_ending() == NewlineType::Crlf && output[start..].ends_with('\r') {
+            end -= 1;
+        }
+        let content_end = end - self.editor.trailing_newlines_len(&output[start..end]);
+
+        if content_end > start {
+            self.emitted = true;
+        }
+
+        self.held.push_str(&output[content_end..]);
+        output.truncate(content_end);
+    }
+}
+
+/// Iterator over the lines of edited text.
+#[derive(Debug)]
+struct EditLines<'a> {
+    input: &'a str,
+    state: BufState<'a>,
+    edited: String,
+    finished: bool,
+}
+
+impl<'a> Iterator for EditLines<'a> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        loop {
+            if let Some(index) = self.edited.find('\n') {
+                let mut line: String = self.edited.drain(..=index).collect();
+                line.pop();
+                if line.ends_with('\r') {
+                    line.pop();
+                }
+                return Some(line);
+            }
+
+            if self.input.is_empty() {
+                if !self.finished {
+                    self.state.finish(&mut self.edited);
+                    self.finished = true;
+                    continue;
+                }
+
+                if self.edited.is_empty() {
+                    return None;
+                }
+
+                return Some(self.edited.drain(..).collect());
+            }
+
+            let line_end = self.state.line_end();
+            let end = match self.input.find(line_end) {
+                Some(index) => index + line_end.len_utf8(),
+                None => self.input.len(),
+            };
+            let (line, rest) = self.input.split_at(end);
+            self.state.push_line(line, &mut self.edited);
+            self.input = rest;
+        }
+    }
+}
+
+/// Copy the input straight into the output, returning the number of bytes
+/// written.
+///
+/// Like [`io::copy`], but reuses the input's buffer and keeps read and write
+/// errors apart. Flushes after every write when `flush` is set.
+#[cfg(feature = "std")]
+pub(crate) fn copy_buffered<I, O>(
+    input: &mut I,
+    output: &mut O,
+    flush: bool,
+) -> Result<usize, EditError>
+where
+    I: BufRead,
+    O: Write,
+{
+    let mut written = 0;
+
+    loop {
+        let len = match input.fill_buf() {
+            // EOF
+            Ok([]) => break,
+            Ok(buf) => {
+                output.write_all(buf).map_err(EditError::WriteFailed)?;
+                if flush {
+                    output.flush().map_err(EditError::WriteFailed)?;
+                }
+                buf.len()
+            }
+            Err(ref error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(EditError::Io(error)),
+        };
+
+        input.consume(len);
+        written += len;
+    }
+
+    Ok(written)
+}
+
+impl Default for Editor {
+    /// Will do nothing on `edit`
+    fn default() -> Self {
+        Editor {
+            replace: String::new(),
+            newlines: 0,
+            line_ending: NewlineType::Lf,
+            edit_type: EditType::Replace,
+            text: String::new(),
+            trailing_newline: false,
+            open: String::new(),
+            close: String::new(),
+            max_edits: None,
+            skip_edits: 0,
+            strip_cr: false,
+            blank_whitespace: false,
+            output_ending: None,
+            skip_empty_end: false,
+            trigger_mode: TriggerMode::Exactly,
+            template: false,
+            fallback: None,
+            reversed_crlf: false,
+            lines: LineOptions::default(),
+        }
+    }
+}
+
+impl Default for TriggerMode {
+    /// [`TriggerMode::Exactly`]
+    #[inline]
+    fn default() -> Self {
+        TriggerMode::Exactly
+    }
+}
+
+impl LineOptions {
+    /// Is every option off.
+    #[inline]
+    pub(crate) fn is_default(&self) -> bool {
+        *self == LineOptions::default()
+    }
+
+    /// Can these options change the text the newline scan writes, for an
+    /// editor which `appends` text.
+    ///
+    /// Appending at EOF only affects appenders, so it is ignored otherwise.
+    #[inline]
+    pub(crate) fn affects_scan(&self, appends: bool) -> bool {
+        let options = LineOptions {
+            append_eof: self.append_eof && appends,
+            ..*self
+        };
+        !options.is_default()
+    }
+}
+
+impl NewlineType {
+    /// Iterate over every concrete newline type, in the order they are
+    /// declared
+    ///
+    /// Useful for listing the choices of a command line option, or for
+    /// tests which should cover every newline type, including any added in
+    /// future versions. [`NewlineType::Auto`] is left out, as it is not a
+    /// newline of its own but resolves to [`Lf`](NewlineType::Lf) or
+    /// [`Crlf`](NewlineType::Crlf) for each input. Each type yielded has a
+    /// different [`as_str`](NewlineType::as_str).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::NewlineType;
+    /// let endings: Vec<_> = NewlineType::all().map(|newline| newline.as_str()).collect();
+    /// assert_eq!(vec!["\n", "\r\n", "\u{2028}", "\u{2029}"], endings);
+    /// ```
+    #[inline]
+    pub fn all() -> impl Iterator<Item = NewlineType> {
+        [
+            NewlineType::Lf,
+            NewlineType::Crlf,
+            NewlineType::LineSeparator,
+            NewlineType::ParagraphSeparator,
+        ]
+        .iter()
+        .cloned()
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NewlineType::Lf | NewlineType::Auto => "\n",
+            NewlineType::Crlf => "\r\n",
+            NewlineType::LineSeparator => "\u{2028}",
+            NewlineType::ParagraphSeparator => "\u{2029}",
+        }
+    }
+
+    /// The line ending as bytes, such as `b"\n"` or `b"\r\n"`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::NewlineType;
+    /// assert_eq!(b"\r\n", NewlineType::Crlf.as_bytes());
+    /// ```
+    #[inline]
+    pub fn as_bytes(&self) -> &'static [u8] {
+        match self {
+            NewlineType::Lf | NewlineType::Auto => b"\n",
+            NewlineType::Crlf => b"\r\n",
+            NewlineType::LineSeparator => "\u{2028}".as_bytes(),
+            NewlineType::ParagraphSeparator => "\u{2029}".as_bytes(),
+        }
+    }
+
+    /// Length of the line ending in bytes: 1 for LF, 2 for CRLF, and 3 for the
+    /// Unicode separators.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::NewlineType;
+    /// assert_eq!(1, NewlineType::Lf.len());
+    /// assert_eq!(2, NewlineType::Crlf.len());
+    /// assert_eq!(3, NewlineType::LineSeparator.len());
+    /// ```
+    #[inline]
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        match self {
+            NewlineType::Lf | NewlineType::Auto => 1,
+            NewlineType::Crlf => 2,
+            NewlineType::LineSeparator | NewlineType::ParagraphSeparator => 3,
+        }
+    }
+
+    /// Peek at the first line ending of a buffered reader, without consuming
+    /// any input
+    ///
+    /// Looks through the bytes already buffered by `input`, filling its
+    /// buffer if it is empty. Returns [`NewlineType::Crlf`] if the first `\n`
+    /// comes after a `\r`, [`NewlineType::Lf`] if it doesn't, and `None` if
+    /// there is no `\n` in the buffer. Every byte is left in the reader, so
+    /// the whole input can still be edited afterwards. This is the line
+    /// ending a [`NewlineType::Auto`] editor reads from a stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from filling the buffer, other than
+    /// [`io::ErrorKind::Interrupted`], which is retried.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::io::{self, Cursor, Read};
+    /// # use linurgy::NewlineType;
+    /// # fn main() -> io::Result<()> {
+    /// let mut input = Cursor::new("foo\r\nbar");
+    /// assert_eq!(Some(NewlineType::Crlf), NewlineType::peek(&mut input)?);
+    ///
+    /// let mut text = String::new();
+    /// input.read_to_string(&mut text)?;
+    /// assert_eq!("foo\r\nbar", text);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn peek<R>(input: &mut R) -> io::Result<Option<NewlineType>>
+    where
+        R: BufRead + ?Sized,
+    {
+        loop {
+            match input.fill_buf() {
+                Ok(buf) => {
+                    let newline = buf.iter().position(|&byte| byte == b'\n');
+                    return Ok(newline.map(|index| match index {
+                        0 => NewlineType::Lf,
+                        _ if buf[index - 1] == b'\r' => NewlineType::Crlf,
+                        _ => NewlineType::Lf,
+                    }));
+                }
+                Err(ref error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    /// Short name of the line ending, such as `LF`.
+    #[inline]
+    fn name(&self) -> &'static str {
+        match self {
+            NewlineType::Lf => "LF",
+            NewlineType::Crlf => "CRLF",
+            NewlineType::LineSeparator => "LS",
+            NewlineType::ParagraphSeparator => "PS",
+            NewlineType::Auto => "auto",
+        }
+    }
+
+    /// The character a line ending ends with.
+    #[inline]
+    pub(crate) fn end_char(&self) -> char {
+        match self {
+            NewlineType::Lf | NewlineType::Crlf | NewlineType::Auto => '\n',
+            NewlineType::LineSeparator => '\u{2028}',
+            NewlineType::ParagraphSeparator => '\u{2029}',
+        }
+    }
+
+    /// The predominant line ending of `input`: CRLF if `\r\n` newlines
+    /// outnumber bare `\n`, otherwise LF.
+    pub(crate) fn detect(input: &str) -> NewlineType {
+        let newlines = input.matches('\n').count();
+        let crlf = input.matches("\r\n").count();
+
+        if crlf > newlines - crlf {
+            NewlineType::Crlf
+        } else {
+            NewlineType::Lf
+        }
+    }
+}
+
+impl fmt::Display for NewlineType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl AsRef<str> for NewlineType {
+    /// The line ending, as from [`NewlineType::as_str`]
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<NewlineType> for &'static str {
+    /// The line ending, as from [`NewlineType::as_str`]
+    #[inline]
+    fn from(newline: NewlineType) -> Self {
+        newline.as_str()
+    }
+}
+
+impl Ord for Editor {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.newlines
+            .cmp(&other.newlines)
+            .then_with(|| self.line_ending.cmp(&other.line_ending))
+            .then_with(|| self.replace.cmp(&other.replace))
+            .then_with(|| self.edit_type.cmp(&other.edit_type))
+            .then_with(|| self.text.cmp(&other.text))
+            .then_with(|| self.trailing_newline.cmp(&other.trailing_newline))
+            .then_with(|| self.open.cmp(&other.open))
+            .then_with(|| self.close.cmp(&other.close))
+            .then_with(|| self.max_edits.cmp(&other.max_edits))
+            .then_with(|| self.skip_edits.cmp(&other.skip_edits))
+            .then_with(|| self.strip_cr.cmp(&other.strip_cr))
+            .then_with(|| self.blank_whitespace.cmp(&other.blank_whitespace))
+            .then_with(|| self.output_ending.cmp(&other.output_ending))
+            .then_with(|| self.skip_empty_end.cmp(&other.skip_empty_end))
+            .then_with(|| self.trigger_mode.cmp(&other.trigger_mode))
+            .then_with(|| self.template.cmp(&other.template))
+            .then_with(|| self.fallback.cmp(&other.fallback))
+            .then_with(|| self.reversed_crlf.cmp(&other.reversed_crlf))
+            .then_with(|| self.lines.cmp(&other.lines))
+    }
+}
+
+impl PartialOrd for Editor {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl FromStr for Editor {
+    type Err = ParseEditorError;
+
+    /// Parse an editor from the form `edit_type:trigger:text`
+    ///
+    /// - `edit_type`: `append`, `insert`, or `replace`.
+    /// - `trigger`: number of newlines to trigger the edit.
+    /// - `text`: text to append, insert, or replace with. It is used as is,
+    ///   without unescaping, and may contain `:`.
+    ///
+    /// A `:crlf` suffix creates an editor for
+    /// [`CRLF`](NewlineType#variant.Crlf) newlines. The editors are the same
+    /// as those created by the [`factory`] functions.
+    ///
+    /// The suffix is always removed from the text, so an editor for
+    /// [`LF`](NewlineType#variant.Lf) newlines can't have text ending in
+    /// `:crlf`. Create one with the [`factory`] functions instead. A CRLF
+    /// editor can, by repeating the suffix: `replace:1:a:crlf:crlf` replaces
+    /// with `a:crlf`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::{factory, Editor};
+    /// let editor: Editor = "append:2:---".parse().unwrap();
+    /// assert_eq!(factory::appender("---", 2), editor);
+    ///
+    /// let editor: Editor = "replace:1: :crlf".parse().unwrap();
+    /// assert_eq!(factory::replacer_crlf(" ", 1), editor);
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.splitn(3, ':');
+
+        let (edit_type, trigger, text) = match (fields.next(), fields.next(), fields.next()) {
+            (Some(edit_type), Some(trigger), Some(text)) => (edit_type, trigger, text),
+            _ => return Err(ParseEditorError::InvalidFormat),
+        };
+
+        let build: fn(&str, usize, NewlineType) -> Editor = match edit_type {
+            "append" => factory::appender_with,
+            "insert" => factory::inserter_with,
+            "replace" => factory::replacer_with,
+            _ => return Err(ParseEditorError::InvalidEditType(edit_type.to_string())),
+        };
+
+        let newlines = trigger.parse().map_err(ParseEditorError::InvalidTrigger)?;
+
+        let (text, line_ending) = if text.ends_with(":crlf") {
+            (&text[..text.len() - ":crlf".len()], NewlineType::Crlf)
+        } else {
+            (text, NewlineType::Lf)
+        };
+
+        Ok(build(text, newlines, line_ending))
+    }
+}
+
+impl fmt::Display for Editor {
+    /// Human readable summary of the edit, with text escaped
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::replacer("\n\n", 2);
+    /// assert_eq!(r#"replace 2 LF newline(s) with "\n\n""#, editor.to_string());
+    ///
+    /// let editor = factory::appender_crlf("\t", 1);
+    /// assert_eq!(r#"append "\t" after 1 CRLF newline(s)"#, editor.to_string());
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = self.line_ending.name();
+        let count = match self.trigger_mode {
+            TriggerMode::Exactly => self.newlines.to_string(),
+            TriggerMode::AtLeast => format!("{} or more", self.newlines),
+            TriggerMode::AtMost => format!("up to {}", self.newlines),
+            TriggerMode::Between(shortest) => format!("{} to {}", shortest, self.newlines),
+        };
+
+        match self.edit_type {
+            EditType::Append => write!(
+                f,
+                "append {:?} after {} {} newline(s)",
+                self.text, count, name
+            )?,
+            EditType::Insert => write!(
+                f,
+                "insert {:?} before {} {} newline(s)",
+                self.text, count, name
+            )?,
+            EditType::Replace => write!(
+                f,
+                "replace {} {} newline(s) with {:?}",
+                count, name, self.replace
+            )?,
+        }
+
+        if self.template {
+            write!(f, ", expanding placeholders")?;
+        }
+
+        if let Some(ref fallback) = self.fallback {
+            write!(f, ", replacing shorter runs with {:?}", fallback)?;
+        }
+
+        if self.is_wrapped() {
+            write!(f, ", wrapped in {:?} and {:?}", self.open, self.close)?;
+        }
+
+        if self.skip_empty_end {
+            write!(f, ", skipping an empty last segment")?;
+        }
+
+        if self.skip_edits > 0 {
+            write!(f, ", skipping the first {}", self.skip_edits)?;
+        }
+
+        if let Some(max) = self.max_edits {
+            write!(f, ", at most {} time(s)", max)?;
+        }
+
+        if self.trailing_newline {
+            write!(f, ", ensuring a trailing newline")?;
+        }
+
+        if self.strip_cr {
+            write!(f, ", stripping CR")?;
+        }
+
+        if self.reversed_crlf {
+            write!(f, ", reading reversed CRLF")?;
+        }
+
+        if self.blank_whitespace {
+            write!(f, ", treating whitespace lines as blank")?;
+        }
+
+        if !self.lines.is_default() {
+            write!(f, "{}", self.lines)?;
+        }
+
+        if let Some(output_ending) = self.output_ending {
+            write!(f, ", writing {} newlines", output_ending.name())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for LineOptions {
+    /// Each option which is on, as part of [`Editor`]'s description.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.trim_trailing {
+            write!(f, ", trimming trailing whitespace")?;
+        }
+
+        if self.trim_leading {
+            write!(f, ", stripping leading whitespace")?;
+        }
+
+        if self.trim_leading_newlines {
+            write!(f, ", trimming leading newlines")?;
+        }
+
+        if self.trim_trailing_newlines {
+            write!(f, ", trimming trailing newlines")?;
+        }
+
+        if self.append_eof {
+            write!(f, ", appending at EOF")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every other way of editing `input` must give the same text as
+    /// [`Editor::edit`], and every way of counting edits the same count.
+    fn assert_same_as_edit(editor: &Editor, input: &str) {
+        let expected = editor.edit(input);
+        let context = format!("\neditor: {}\ninput: {:?}\n", editor, input);
+
+        for &capacity in &[1, 2, 3, 5, 8192] {
+            let mut reader = BufReader::with_capacity(capacity, input.as_bytes());
+            let mut output = Vec::new();
+            editor.edit_buffered(&mut reader, &mut output).unwrap();
+            assert_eq!(
+                expected.as_bytes(),
+                &output[..],
+                "capacity {}{}",
+                capacity,
+                context
+            );
+        }
+
+        let mut text = input.to_string();
+        editor.edit_in_place(&mut text);
+        assert_eq!(expected, text, "in place{}", context);
+
+        assert_eq!(
+            expected.len(),
+            editor.output_len(input),
+            "output_len{}",
+            context
+        );
+        assert_eq!(expected, editor.edit_cow(input), "cow{}", context);
+        assert_eq!(
+            expected,
+            editor
+                .edit_if_changed(input)
+                .unwrap_or_else(|| input.to_string()),
+            "if changed{}",
+            context
+        );
+
+        let (output, edits) = editor.edit_counted(input);
+        assert_eq!(expected, output, "counted{}", context);
+        let mut output = Vec::new();
+        let buffered = editor
+            .edit_buffered_counted(&mut input.as_bytes(), &mut output)
+            .unwrap();
+        assert_eq!(edits, buffered, "buffered count{}", context);
+        assert_eq!(edits, editor.preview(input).len(), "preview{}", context);
+
+        let segments = editor.split(input);
+        assert_eq!(edits + 1, segments.len(), "split{}", context);
+        if !editor.template && !editor.trailing_newline && !editor.skip_empty_end {
+            let joined = segments.join(editor.replacement());
+            assert_eq!(expected, joined, "split joined{}", context);
+        }
+
+        let lines: Vec<String> = editor.edit_lines(input).collect();
+        let expected_lines: Vec<&str> = expected.lines().collect();
+        assert_eq!(expected_lines, lines, "lines{}", context);
+
+        #[cfg(feature = "rayon")]
+        for size in 1..=input.len() {
+            let output = editor.edit_chunked(input, size);
+            assert_eq!(expected, output, "chunks of {}{}", size, context);
+        }
+    }
+
+    /// [`Editor::edit`] must make `edits` edits to `input` to give
+    /// `expected`, and every other way of editing it must agree.
+    fn assert_edits(expected: &str, edits: usize, editor: &Editor, input: &str) {
+        let context = format!("\neditor: {}\ninput: {:?}\n", editor, input);
+        assert_eq!(expected, editor.edit(input), "standard{}", context);
+        assert_eq!(edits, editor.edit_counted(input).1, "count{}", context);
+
+        assert_same_as_edit(editor, input);
+    }
+
+    mod standard {
+        use super::*;
+
+        editor_tests!(assert_edit);
+
+        fn assert_edit(test: EditTest) {
+            let replace = test.replace.to_string();
+            let editor = Editor::new(replace, test.newlines, test.line_ending);
+
+            assert_eq!(
+                test.expected,
+                editor.edit(test.input),
+                "\ntest: {}\n",
+                test.name
+            );
+        }
+    }
+
+    mod buffered {
+        use super::*;
+        use crate::factory;
+        use std::io::{BufReader, Cursor};
+
+        editor_tests!(assert_edit_buffered);
+
+        fn assert_edit_buffered(test: EditTest) {
+            let mut input = BufReader::new(test.input.as_bytes());
+
+            let mut output: Vec<u8> = Vec::new();
+
+            let replace = test.replace.to_string();
+            let editor = Editor::new(replace, test.newlines, test.line_ending);
+
+            editor.edit_buffered(&mut input, &mut output).unwrap();
+
+            let actual = String::from_utf8_lossy(&output);
+
+            assert_eq!(test.expected, actual, "\ntest: {}\n", test.name);
+        }
+
+        #[test]
+        fn owned() {
+            let editor = factory::appender("-", 2);
+            let input = BufReader::new("foo\n\nbar\nbaz".as_bytes());
+
+            let output = editor.edit_buffered_owned(input, Vec::new()).unwrap();
+
+            assert_eq!(b"foo\n\n-bar\nbaz", &output[..]);
+        }
+
+        #[test]
+        fn owned_appends_to_writer() {
+            let editor = factory::replacer(" ", 1);
+            let output = b"> ".to_vec();
+
+            let output = editor
+                .edit_buffered_owned(Cursor::new("foo\nbar\n"), output)
+                .unwrap();
+
+            assert_eq!(b"> foo bar ", &output[..]);
+        }
+
+        /// Reads at most one byte at a time, and is not [`BufRead`].
+        struct ByteReader<'a>(&'a [u8]);
+
+        impl Read for ByteReader<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                let len = cmp::min(1, cmp::min(buf.len(), self.0.len()));
+                buf[..len].copy_from_slice(&self.0[..len]);
+                self.0 = &self.0[len..];
+                Ok(len)
+            }
+        }
+
+        #[test]
+        fn read_only_input() {
+            let editor = factory::appender_crlf("-", 2);
+            let input = "foo\r\n\r\nbär\r\nbaz\n\n";
+            let mut output = Vec::new();
+
+            editor
+                .edit_read(ByteReader(input.as_bytes()), &mut output)
+                .unwrap();
+
+            assert_eq!(editor.edit(input).as_bytes(), &output[..]);
+            assert_eq!("foo\r\n\r\n-bär\r\nbaz\r\n\r\n-".as_bytes(), &output[..]);
+        }
+
+        #[test]
+        fn read_invalid_utf8() {
+            let editor = factory::replacer("-", 1);
+            let mut output = Vec::new();
+
+            let result = editor.edit_read(ByteReader(b"foo\n\xff"), &mut output);
+
+            assert!(result.is_err());
+        }
+    }
+
+    mod with_callback {
+        use super::*;
+        use crate::factory;
+        use std::io::BufReader;
+
+        fn lines(editor: &Editor, input: &str, capacity: usize) -> Vec<String> {
+            let mut reader = BufReader::with_capacity(capacity, input.as_bytes());
+            let mut output = Vec::new();
+            let mut lines = Vec::new();
+
+            editor
+                .edit_buffered_with_callback(&mut reader, &mut output, |line| {
+                    lines.push(line.to_string())
+                })
+                .unwrap();
+
+            assert_eq!(editor.edit(input).as_bytes(), &output[..], "{}", editor);
+            assert_eq!(editor.edit(input), lines.concat(), "{}", editor);
+            lines
+        }
+
+        #[test]
+        fn output_lines() {
+            let editor = factory::replacer("\n", 2);
+            assert_eq!(vec!["a\n", "b\n", "c"], lines(&editor, "a\n\nb\n\nc", 1));
+        }
+
+        #[test]
+        fn replacement_joins_lines() {
+            let editor = factory::replacer(" ", 1);
+            assert_eq!(vec!["a b c  d "], lines(&editor, "a\nb\nc\n\nd\n", 8192));
+        }
+
+        #[test]
+        fn replacement_splits_lines() {
+            let editor = factory::wrap_crlf("<", ">", 2);
+            assert_eq!(
+                vec!["<a>\r\n", "\r\n", "<b\r\n", "c>"],
+                lines(&editor, "a\n\nb\r\nc", 8192)
+            );
+        }
+
+        #[test]
+        fn held_trailing_newline() {
+            let editor = factory::appender("+", 1).ensure_trailing_newline(true);
+            assert_eq!(
+                vec!["a\n", "+b\n", "+\n", "+\n", "+\n"],
+                lines(&editor, "a\nb\n\n\n", 1)
+            );
+        }
+
+        #[test]
+        fn output_newline_type() {
+            let editor = Editor::new_converting(
+                "".to_string(),
+                0,
+                NewlineType::Lf,
+                NewlineType::LineSeparator,
+            );
+            assert_eq!(
+                vec!["a\u{2028}", "\u{2028}", "b\r"],
+                lines(&editor, "a\n\nb\r", 8192)
+            );
+        }
+
+        #[test]
+        fn large_input() {
+            let editor = factory::inserter("-", 1);
+            let input = "foo\nbar\n\n".repeat(500);
+            assert_eq!(1500, lines(&editor, &input, 64).len());
+        }
+
+        #[test]
+        fn empty_input() {
+            assert!(lines(&factory::replacer("-", 1), "", 8192).is_empty());
+            assert!(lines(&Editor::default(), "", 8192).is_empty());
+        }
+    }
+
+    mod with_progress {
+        use super::*;
+        use crate::factory;
+        use std::io::BufReader;
+
+        fn reports(editor: &Editor, input: &str, capacity: usize) -> Vec<u64> {
+            let mut reader = BufReader::with_capacity(capacity, input.as_bytes());
+            let mut output = Vec::new();
+            let mut reports = Vec::new();
+
+            editor
+                .edit_buffered_with_progress(&mut reader, &mut output, |read| reports.push(read))
+                .unwrap();
+
+            assert_eq!(editor.edit(input).as_bytes(), &output[..], "{}", editor);
+            reports
+        }
+
+        #[test]
+        fn final_report_is_input_len() {
+            let editor = factory::replacer("-", 2);
+            let input = "foo\n\nbär\n";
+            assert_eq!(vec![input.len() as u64], reports(&editor, input, 1));
+        }
+
+        #[test]
+        fn large_input_reports_increase() {
+            let editor = factory::appender("---", 1);
+            let input = "foo\r\nbar\n\n".repeat(20_000);
+            let reports = reports(&editor, &input, 8192);
+
+            assert_eq!(Some(&(input.len() as u64)), reports.last());
+            assert!(reports.len() > 3);
+            assert!(reports.windows(2).all(|pair| pair[0] < pair[1]));
+            assert!(reports[0] >= PROGRESS_INTERVAL);
+        }
+
+        #[test]
+        fn removed_input_still_reported() {
+            let editor = factory::replacer("", 1).trim_trailing_newlines(true);
+            let input = "\n".repeat(200_000);
+            let reports = reports(&editor, &input, 8192);
+
+            assert_eq!(Some(&200_000), reports.last());
+            assert_eq!(4, reports.len());
+        }
+
+        #[test]
+        fn empty_input() {
+            assert_eq!(vec![0], reports(&factory::replacer("-", 1), "", 8192));
+            assert_eq!(vec![0], reports(&Editor::default(), "", 8192));
+        }
+
+        #[test]
+        fn separators() {
+            let editor = factory::replacer_with("-", 1, NewlineType::ParagraphSeparator);
+            let input = "a\u{2029}b\u{2029}";
+            assert_eq!(vec![input.len() as u64], reports(&editor, input, 2));
+        }
+    }
+
+    mod output_len {
+        use super::*;
+        use crate::factory;
+
+        editor_tests!(assert_output_len);
+
+        fn assert_output_len(test: EditTest) {
+            let replace = test.replace.to_string();
+            let editor = Editor::new(replace, test.newlines, test.line_ending);
+
+            assert_eq!(
+                test.expected.len(),
+                editor.output_len(test.input),
+                "\ntest: {}\n",
+                test.name
+            );
+        }
+
+        #[test]
+        fn same_as_edit_with_options() {
+            let editors = [
+                factory::wrap("<p>", "</p>", 2).skip_empty_last_segment(true),
+                factory::appender_crlf("-", 1).ensure_trailing_newline(true),
+                factory::replacer_at_least("", 2).treat_whitespace_lines_as_blank(true),
+                factory::replacer_at_most(" ", 1).max_edits(Some(2)),
+                factory::template_replacer("[{n}]", 1).skip_edits(1),
+                factory::replacer_with("\r\n", 1, NewlineType::Auto),
+                factory::normalizer(NewlineType::Crlf, NewlineType::LineSeparator),
+                Editor::default(),
+            ];
+            let input = "\n\nfoo\r\n \n\nbar\n\n\n\n\u{2028}baz\r\n\n\n";
+
+            for editor in &editors {
+                assert_eq!(
+                    editor.edit(input).len(),
+                    editor.output_len(input),
+                    "{}",
+                    editor
+                );
+                assert_eq!(0, editor.output_len(""), "{}", editor);
+            }
+        }
+    }
+
+    mod edit_to_vec {
+        use super::*;
+        use crate::factory;
+
+        editor_tests!(assert_edit_to_vec);
+
+        fn assert_edit_to_vec(test: EditTest) {
+            let replace = test.replace.to_string();
+            let editor = Editor::new(replace, test.newlines, test.line_ending);
+
+            assert_eq!(
+                test.expected.as_bytes(),
+                &editor.edit_to_vec(test.input)[..],
+                "\ntest: {}\n",
+                test.name
+            );
+        }
+
+        #[test]
+        fn same_as_edit_with_options() {
+            let editors = [
+                factory::wrap_crlf("<p>", "</p>", 2).ensure_trailing_newline(true),
+                factory::template_replacer("[{n}]", 1).max_edits(Some(2)),
+                factory::replacer_with("\u{2028}", 1, NewlineType::Auto),
+                Editor::default(),
+            ];
+            let input = "\n\nfoo\r\n\r\nbar\n\u{2028}baz\r\n";
+
+            for editor in &editors {
+                assert_eq!(
+                    editor.edit(input).into_bytes(),
+                    editor.edit_to_vec(input),
+                    "{}",
+                    editor
+                );
+            }
+        }
+    }
+
+    mod lines {
+        use super::*;
+
+        editor_tests!(assert_edit_lines);
+
+        #[test]
+        fn replace_with_newlines_splits_lines() {
+            let editor = Editor::new("\n<hr>\n".to_string(), 2, NewlineType::Lf);
+            let actual: Vec<String> = editor.edit_lines("foo\n\nbar\nbaz").collect();
+            assert_eq!(vec!["foo", "<hr>", "bar", "baz"], actual);
+        }
+
+        #[test]
+        fn replace_with_newlines_splits_lines_crlf() {
+            let editor = Editor::new("\r\n<hr>\r\n".to_string(), 2, NewlineType::Crlf);
+            let actual: Vec<String> = editor.edit_lines("foo\r\n\r\nbar\r\n").collect();
+            assert_eq!(vec!["foo", "<hr>", "bar"], actual);
+        }
+
+        fn assert_edit_lines(test: EditTest) {
+            let replace = test.replace.to_string();
+            let editor = Editor::new(replace, test.newlines, test.line_ending);
+
+            let expected: Vec<&str> = test.expected.lines().collect();
+            let actual: Vec<String> = editor.edit_lines(test.input).collect();
+
+            assert_eq!(expected, actual, "\ntest: {}\n", test.name);
+        }
+    }
+
+    mod with {
+        use super::*;
+
+        #[test]
+        fn index_increments_each_match() {
+            let editor = Editor::new(String::new(), 1, NewlineType::Lf);
+            let output = editor.edit_with("a\nb\nc\n", |index| index.to_string());
+            assert_eq!("a0b1c2", output);
+        }
+
+        #[test]
+        fn index_increments_each_match_crlf() {
+            let editor = Editor::new(String::new(), 2, NewlineType::Crlf);
+            let output =
+                editor.edit_with("a\r\n\r\nb\r\nc\r\n\r\n", |index| format!("[{}]", index));
+            assert_eq!("a[0]b\r\nc[1]", output);
+        }
+
+        #[test]
+        fn not_called_without_match() {
+            let editor = Editor::new(String::new(), 3, NewlineType::Lf);
+            let output = editor.edit_with("a\nb\n\nc", |_| panic!("called without match"));
+            assert_eq!("a\nb\n\nc", output);
+        }
+
+        #[test]
+        fn closure_state_is_kept() {
+            let editor = Editor::new(String::new(), 1, NewlineType::Lf);
+            let mut calls = Vec::new();
+            editor.edit_with("a\nb\nc", |index| {
+                calls.push(index);
+                String::from(" ")
+            });
+            assert_eq!(vec![0, 1], calls);
+        }
+
+        fn rule_or_page(len: usize) -> &'static str {
+            if len >= 4 {
+                "<page>"
+            } else {
+                "<hr>"
+            }
+        }
+
+        #[test]
+        fn run_len_exactly_is_trigger() {
+            let editor = factory::replacer("", 2);
+            let output = editor.edit_with_run_len("a\n\nb\n\n\n\nc\n\n\nd", rule_or_page);
+            assert_eq!("a<hr>b<hr><hr>c<hr>\nd", output);
+        }
+
+        #[test]
+        fn run_len_at_least_is_whole_run() {
+            let editor = factory::replacer_at_least("", 2);
+            let output = editor.edit_with_run_len("a\n\nb\n\n\n\nc\n\n\nd\ne", rule_or_page);
+            assert_eq!("a<hr>b<page>c<hr>d\ne", output);
+
+            let mut lens = Vec::new();
+            editor.edit_with_run_len("a\n\nb\n\n\n\n\nc\n\n\n", |len| {
+                lens.push(len);
+                String::new()
+            });
+            assert_eq!(vec![2, 5, 3], lens);
+        }
+
+        #[test]
+        fn run_len_at_most_is_whole_run() {
+            let editor = factory::replacer_at_most("", 3);
+            let output = editor.edit_with_run_len("a\nb\n\n\nc\n\n\n\nd", |len| len.to_string());
+            assert_eq!("a1b3c\n\n\n\nd", output);
+        }
+
+        #[test]
+        fn run_len_not_called_for_fallback_or_skipped() {
+            let editor = factory::replacer_at_least("", 2)
+                .fallback(Some(" "))
+                .skip_edits(1);
+            let output = editor.edit_with_run_len("a\nb\n\nc\n\n\nd", |len| len.to_string());
+            assert_eq!("a b\n\nc3d", output);
+        }
+    }
+
+    mod noop {
+        use super::*;
+        use crate::factory;
+
+        #[test]
+        fn default_editor_is_noop() {
+            assert_noop(&Editor::default());
+        }
+
+        #[test]
+        fn zero_newlines_is_noop() {
+            assert_noop(&Editor::new("foo".to_string(), 0, NewlineType::Lf));
+        }
+
+        #[test]
+        fn replace_with_same_newlines_is_noop() {
+            assert_noop(&factory::appender("", 3));
+            assert_noop(&factory::inserter("", 2));
+            assert_noop(&factory::normalizer(NewlineType::Lf, NewlineType::Lf));
+        }
+
+        #[test]
+        fn replace_with_other_text_is_not_noop() {
+            assert!(!factory::replacer("", 1).is_noop());
+            assert!(!factory::replacer("\n", 2).is_noop());
+            assert!(!factory::appender("-", 1).is_noop());
+            assert!(!factory::normalizer(NewlineType::Crlf, NewlineType::Lf).is_noop());
+        }
+
+        #[test]
+        fn crlf_is_not_noop() {
+            let editor = Editor::new("foo".to_string(), 0, NewlineType::Crlf);
+            assert!(!editor.is_noop());
+            assert_eq!("foo\r\nbar", editor.edit("foo\nbar"));
+
+            assert!(!factory::inserter_crlf("", 1).is_noop());
+        }
+
+        #[test]
+        fn buffered_noop_copies_large_input() {
+            let line = "foo\n\nbar\r\n\r\nbaz\rqux\n\n\n";
+            let input = line.repeat(10_000) + "end\r";
+
+            for editor in &[Editor::default(), factory::appender("", 2)] {
+                let mut output = Vec::new();
+                let written = editor
+                    .edit_buffered_written(&mut input.as_bytes(), &mut output)
+                    .unwrap();
+
+                assert_eq!(input.len(), written);
+                assert!(input.as_bytes() == &output[..], "{:?}", editor);
+            }
+        }
+
+        #[test]
+        fn buffered_noop_copies_invalid_utf8() {
+            let input = b"foo\n\xff\n";
+            let mut output = Vec::new();
+            Editor::default()
+                .edit_buffered(&mut &input[..], &mut output)
+                .unwrap();
+            assert_eq!(&input[..], &output[..]);
+        }
+
+        #[test]
+        fn trailing_newline_is_not_noop() {
+            let editor = Editor::default().ensure_trailing_newline(true);
+            assert!(!editor.is_noop());
+        }
+
+        fn assert_noop(editor: &Editor) {
+            assert!(editor.is_noop(), "{:?}", editor);
+
+            let input = "foo\n\nbar\r\nbaz\n\n\n";
+            assert_eq!(input, editor.edit(input));
+        }
+    }
+
+    mod is_idempotent {
+        use super::*;
+        use crate::factory;
+
+        const INPUTS: &[&str] = &[
+            "",
+            "foo",
+            "foo\n",
+            "foo\n\nbar\n\n\nbaz\n\n\n\n",
+            "\n\n\nfoo\r\n\r\n\r\nbar\r\n\n\rbaz\r\n",
+            "foo\u{2028}\u{2028}\u{2028}bar\u{2029}\u{2029}",
+        ];
+
+        fn assert_idempotent(editor: &Editor) {
+            assert!(editor.is_idempotent(), "{:?}", editor);
+
+            for input in INPUTS {
+                let once = editor.edit(input);
+                assert_eq!(
+                    once,
+                    editor.edit(&once),
+                    "\neditor: {}\ninput: {:?}\n",
+                    editor,
+                    input
+                );
+            }
+        }
+
+        fn assert_not_idempotent(editor: &Editor, input: &str) {
+            assert!(!editor.is_idempotent(), "{:?}", editor);
+
+            let once = editor.edit(input);
+            assert_ne!(once, editor.edit(&once), "{:?}", editor);
+        }
+
+        #[test]
+        fn noop() {
+            assert_idempotent(&Editor::default());
+            assert_idempotent(&factory::appender("", 2));
+            assert_idempotent(&factory::replacer("\n", 0));
+        }
+
+        #[test]
+        fn replace_without_newlines() {
+            assert_idempotent(&factory::replacer(" ", 1));
+            assert_idempotent(&factory::replacer("", 2));
+            assert_idempotent(&factory::replacer_crlf("-", 2));
+            assert_idempotent(&factory::replacer_with("", 1, NewlineType::LineSeparator));
+            assert_idempotent(&factory::template_replacer("<{n}>", 2));
+            assert_idempotent(&Editor::new_converting(
+                "".to_string(),
+                2,
+                NewlineType::Crlf,
+                NewlineType::Lf,
+            ));
+        }
+
+        #[test]
+        fn trigger_modes() {
+            assert_idempotent(&factory::replacer_at_least("", 2));
+            assert_idempotent(&factory::replacer_at_least(" ", 1));
+            assert_idempotent(&factory::replacer_at_most(" ", 2));
+        }
+
+        #[test]
+        fn trailing_newline() {
+            assert_idempotent(&factory::replacer("", 2).ensure_trailing_newline(true));
+            assert_idempotent(&factory::replacer_at_least("", 3).ensure_trailing_newline(true));
+            assert_idempotent(&factory::replacer("", 0).ensure_trailing_newline(true));
+
+            let editor = factory::replacer("-", 1).ensure_trailing_newline(true);
+            assert_not_idempotent(&editor, "foo\n");
+            let editor = factory::replacer_at_most("-", 2).ensure_trailing_newline(true);
+            assert_not_idempotent(&editor, "foo\n\n\n");
+        }
+
+        #[test]
+        fn replace_with_newlines() {
+            assert_not_idempotent(&factory::appender("\n", 1), "foo\nbar");
+            assert_not_idempotent(&factory::inserter("-", 1), "foo\nbar");
+            assert_not_idempotent(&factory::replacer("\n", 2), "foo\n\n\n\nbar");
+            assert_not_idempotent(&factory::replacer_crlf("\r\n", 2), "a\r\n\r\n\r\n\r\nb");
+        }
+
+        #[test]
+        fn limited_edits() {
+            assert_not_idempotent(&factory::wrap("<", ">", 1), "foo");
+            assert_not_idempotent(&factory::replacer("-", 1).max_edits(Some(1)), "a\nb\nc");
+            assert!(!factory::replacer("-", 1).skip_edits(1).is_idempotent());
+            assert_not_idempotent(&factory::replacer("-", 2).strip_cr(true), "\r\r\n");
+        }
+
+        #[test]
+        fn auto_line_ending() {
+            let editor = factory::replacer_with("", 2, NewlineType::Auto);
+            assert_not_idempotent(&editor, "a\r\n\r\nb\n\n\n\nc");
+        }
+    }
+
+    mod inverse {
+        use super::*;
+
+        #[test]
+        fn extra_line_inverse_removes_line() {
+            let editor = Editor::new("\n\n".to_string(), 1, NewlineType::Lf);
+            let expected = Editor::new("\n".to_string(), 2, NewlineType::Lf);
+            assert_eq!(Some(expected), editor.inverse());
+        }
+
+        #[test]
+        fn extra_line_inverse_removes_line_crlf() {
+            let editor = Editor::new("\r\n\r\n".to_string(), 1, NewlineType::Crlf);
+            let expected = Editor::new("\r\n".to_string(), 2, NewlineType::Crlf);
+            assert_eq!(Some(expected), editor.inverse());
+        }
+
+        #[test]
+        fn zero_newlines_inverse_does_nothing() {
+            let editor = Editor::new("should not be used".to_string(), 0, NewlineType::Lf);
+            let expected = Editor::new(String::new(), 0, NewlineType::Lf);
+            assert_eq!(Some(expected), editor.inverse());
+        }
+
+        #[test]
+        fn text_not_invertible() {
+            let editor = Editor::new("\n\n---".to_string(), 2, NewlineType::Lf);
+            assert_eq!(None, editor.inverse());
+        }
+
+        #[test]
+        fn fewer_newlines_not_invertible() {
+            let editor = Editor::new("\n".to_string(), 2, NewlineType::Lf);
+            assert_eq!(None, editor.inverse());
+        }
+
+        #[test]
+        fn lf_in_crlf_not_invertible() {
+            let editor = Editor::new("\n\n".to_string(), 1, NewlineType::Crlf);
+            assert_eq!(None, editor.inverse());
+        }
+
+        #[test]
+        fn trailing_newline_not_invertible() {
+            let editor =
+                Editor::new("\n\n".to_string(), 1, NewlineType::Lf).ensure_trailing_newline(true);
+            assert_eq!(None, editor.inverse());
+        }
+
+        #[test]
+        fn round_trip() {
+            let inputs = [
+                "foo\nbar\n\nbaz\n\n\n\n\nqux",
+                "\n\n\nfoo\n\n\n\n\n\n\n",
+                "foo",
+            ];
+            let editors = [
+                Editor::new("\n\n".to_string(), 1, NewlineType::Lf),
+                Editor::new("\n\n\n".to_string(), 2, NewlineType::Lf),
+                Editor::new("\n\n\n\n\n".to_string(), 3, NewlineType::Lf),
+                Editor::new("\n\n".to_string(), 2, NewlineType::Lf),
+            ];
+
+            for editor in editors.iter() {
+                let inverse = editor.inverse().unwrap();
+                for input in inputs.iter() {
+                    let edited = editor.edit(input);
+                    assert_eq!(*input, inverse.edit(&edited), "\neditor: {:?}\n", editor);
+                }
+            }
+        }
+    }
+
+    mod trailing_newline {
+        use super::*;
+        use std::io::BufReader;
+
+        #[test]
+        fn adds_missing_newline() {
+            assert_trailing("foo\nbar\n", "foo\nbar", 2, "", NewlineType::Lf);
+        }
+
+        #[test]
+        fn keeps_single_newline() {
+            assert_trailing("foo\nbar\n", "foo\nbar\n", 2, "", NewlineType::Lf);
+        }
+
+        #[test]
+        fn trims_many_newlines() {
+            assert_trailing("foo\nbar\n", "foo\nbar\n\n\n\n", 5, "", NewlineType::Lf);
+        }
+
+        #[test]
+        fn trims_newlines_from_replace() {
+            assert_trailing(
+                "foo-\n\nbar-\n",
+                "foo\n\nbar\n\n",
+                2,
+                "-\n\n",
+                NewlineType::Lf,
+            );
+        }
+
+        #[test]
+        fn keeps_newlines_before_content() {
+            assert_trailing("foo\n\n\nbar\n", "foo\n\n\nbar\n\n", 0, "", NewlineType::Lf);
+        }
+
+        #[test]
+        fn replace_after_last_newline() {
+            assert_trailing("foo\n-\n", "foo\n", 1, "\n-", NewlineType::Lf);
+        }
+
+        #[test]
+        fn append_on_trigger_at_end() {
+            assert_trailing("foo\n\n-\n", "foo\n\n", 2, "\n\n-", NewlineType::Lf);
+            assert_trailing(
+                "foo\r\n\r\n-\r\n",
+                "foo\r\n\r\n",
+                2,
+                "\r\n\r\n-",
+                NewlineType::Crlf,
+            );
+        }
+
+        #[test]
+        fn empty_stays_empty() {
+            assert_trailing("", "", 1, "-", NewlineType::Lf);
+        }
+
+        #[test]
+        fn only_newlines() {
+            assert_trailing("\n", "\n\n\n", 0, "", NewlineType::Lf);
+        }
+
+        #[test]
+        fn adds_missing_newline_crlf() {
+            assert_trailing("foo\r\nbar\r\n", "foo\r\nbar", 2, "", NewlineType::Crlf);
+        }
+
+        #[test]
+        fn keeps_single_newline_crlf() {
+            assert_trailing("foo\r\n", "foo\r\n", 2, "", NewlineType::Crlf);
+        }
+
+        #[test]
+        fn keeps_carriage_return_at_end_crlf() {
+            assert_trailing("foo\r\r\n", "foo\r", 2, "", NewlineType::Crlf);
+            assert_trailing("foo\r\r\n", "foo\r\r\n\r\n", 3, "", NewlineType::Crlf);
+        }
+
+        #[test]
+        fn trims_many_newlines_crlf() {
+            assert_trailing(
+                "foo-\r\n",
+                "foo\r\n\r\n\r\n",
+                2,
+                "-\r\n\r\n",
+                NewlineType::Crlf,
+            );
+        }
+
+        fn assert_trailing(
+            expected: &str,
+            input: &str,
+            newlines: usize,
+            replace: &str,
+            line_ending: NewlineType,
+        ) {
+            let editor = Editor::new(replace.to_string(), newlines, line_ending)
+                .ensure_trailing_newline(true);
+
+            assert_eq!(expected, editor.edit(input), "standard");
+
+            let mut buf_input = BufReader::new(input.as_bytes());
+            let mut output = Vec::new();
+            editor.edit_buffered(&mut buf_input, &mut output).unwrap();
+            assert_eq!(expected, String::from_utf8_lossy(&output), "buffered");
+
+            let mut text = String::from(input);
+            editor.edit_in_place(&mut text);
+            assert_eq!(expected, text, "in place");
+        }
+    }
+
+    mod in_place {
+        use super::*;
+
+        editor_tests!(assert_edit_in_place);
+
+        #[test]
+        fn shrinking_does_not_allocate() {
+            let editor = Editor::new("\n".to_string(), 2, NewlineType::Lf);
+            let mut text = String::from("foo\n\nbar\n\n\nbaz\n\n");
+            let ptr = text.as_ptr();
+
+            editor.edit_in_place(&mut text);
+
+            assert_eq!("foo\nbar\n\nbaz\n", text);
+            assert_eq!(ptr, text.as_ptr());
+        }
+
+        #[test]
+        fn shrinking_does_not_allocate_crlf() {
+            let editor = Editor::new("\r\n".to_string(), 2, NewlineType::Crlf);
+            let mut text = String::from("foo\r\n\r\nbar\r\nbaz\r\n\r\n");
+            let ptr = text.as_ptr();
+
+            editor.edit_in_place(&mut text);
+
+            assert_eq!("foo\r\nbar\r\nbaz\r\n", text);
+            assert_eq!(ptr, text.as_ptr());
+        }
+
+        #[test]
+        fn bare_lf_in_crlf_mode_grows() {
+            let editor = Editor::new("".to_string(), 3, NewlineType::Crlf);
+            let mut text = String::from("foo\n\nbar");
+
+            editor.edit_in_place(&mut text);
+
+            assert_eq!(editor.edit("foo\n\nbar"), text);
+        }
+
+        #[test]
+        fn multi_byte_chars() {
+            let editor = Editor::new("é".to_string(), 2, NewlineType::Lf);
+            let mut text = String::from("ü\n\nö\nä\n\n");
+
+            editor.edit_in_place(&mut text);
+
+            assert_eq!("üéö\näé", text);
+        }
+
+        fn assert_edit_in_place(test: EditTest) {
+            let replace = test.replace.to_string();
+            let editor = Editor::new(replace, test.newlines, test.line_ending);
+
+            let mut text = String::from(test.input);
+            editor.edit_in_place(&mut text);
+
+            assert_eq!(test.expected, text, "\ntest: {}\n", test.name);
+        }
+    }
+
+    mod as_ref {
+        use super::*;
+        use std::borrow::Cow;
+
+        #[test]
+        fn edit_string() {
+            let editor = Editor::new("-".to_string(), 1, NewlineType::Lf);
+            let input = String::from("foo\nbar");
+            assert_eq!("foo-bar", editor.edit(&input));
+            assert_eq!("foo-bar", editor.edit(input));
+        }
+
+        #[test]
+        fn edit_cow() {
+            let editor = Editor::new("-".to_string(), 1, NewlineType::Lf);
+            let borrowed: Cow<str> = Cow::Borrowed("foo\nbar");
+            let owned: Cow<str> = Cow::Owned("foo\nbar".to_string());
+            assert_eq!("foo-bar", editor.edit(&borrowed));
+            assert_eq!("foo-bar", editor.edit(owned));
+        }
+    }
+
+    mod peek {
+        use super::*;
+        use std::io::{BufReader, Cursor, Read};
+
+        #[test]
+        fn crlf_first() {
+            let input = "\r\nfoo\nbar\n\n";
+            let mut cursor = Cursor::new(input);
+
+            assert_eq!(
+                Some(NewlineType::Crlf),
+                NewlineType::peek(&mut cursor).unwrap()
+            );
+            assert_eq!(0, cursor.position());
+
+            let editor = factory::replacer_with("-", 1, NewlineType::Auto);
+            let mut output = Vec::new();
+            editor.edit_buffered(&mut cursor, &mut output).unwrap();
+            // a CRLF editor also reads bare `\n` newlines
+            assert_eq!(b"-foo-bar--", &output[..]);
+
+            let mut cursor = Cursor::new(input);
+            NewlineType::peek(&mut cursor).unwrap();
+            let mut output = Vec::new();
+            cursor.read_to_end(&mut output).unwrap();
+            assert_eq!(input.as_bytes(), &output[..]);
+        }
+
+        #[test]
+        fn lf_first() {
+            let mut cursor = Cursor::new("\nfoo\r\n");
+            assert_eq!(
+                Some(NewlineType::Lf),
+                NewlineType::peek(&mut cursor).unwrap()
+            );
+
+            let mut cursor = Cursor::new("foo\rbar\nbaz\r\n");
+            assert_eq!(
+                Some(NewlineType::Lf),
+                NewlineType::peek(&mut cursor).unwrap()
+            );
+        }
+
+        #[test]
+        fn no_newline_buffered() {
+            assert_eq!(None, NewlineType::peek(&mut Cursor::new("")).unwrap());
+            assert_eq!(None, NewlineType::peek(&mut Cursor::new("foo\r")).unwrap());
+
+            let mut reader = BufReader::with_capacity(4, "foo\r\nbar".as_bytes());
+            assert_eq!(None, NewlineType::peek(&mut reader).unwrap());
+            assert_eq!(b"foo\r", reader.buffer());
+        }
+
+        #[test]
+        fn same_as_buffered_auto() {
+            for &input in &["a\r\nb\n\nc", "a\nb\r\n\r\nc", "\r\n\r\n\n"] {
+                let line_ending = NewlineType::peek(&mut Cursor::new(input)).unwrap().unwrap();
+                let auto = factory::replacer_with("-", 1, NewlineType::Auto);
+                let editor = factory::replacer_with("-", 1, line_ending);
+
+                let mut auto_output = Vec::new();
+                auto.edit_buffered(&mut Cursor::new(input), &mut auto_output)
+                    .unwrap();
+                let mut output = Vec::new();
+                editor
+                    .edit_buffered(&mut Cursor::new(input), &mut output)
+                    .unwrap();
+
+                assert_eq!(output, auto_output, "input: {:?}", input);
+            }
+        }
+    }
+
+    mod newline_str {
+        use super::*;
+
+        #[test]
+        fn as_ref_writes_newline() {
+            let mut output = Vec::new();
+            for newline in &[
+                NewlineType::Lf,
+                NewlineType::Crlf,
+                NewlineType::LineSeparator,
+            ] {
+                output.write_all(newline.as_ref().as_bytes()).unwrap();
+            }
+            assert_eq!("\n\r\n\u{2028}".as_bytes(), &output[..]);
+        }
+
+        #[test]
+        fn into_static_str() {
+            let crlf: &str = NewlineType::Crlf.into();
+            assert_eq!("\r\n", crlf);
+            assert_eq!("\n", <&str>::from(NewlineType::Auto));
+            assert_eq!("\u{2029}", <&str>::from(NewlineType::ParagraphSeparator));
+        }
+
+        #[test]
+        fn all_variants() {
+            // fails to compile when a variant is added, as a reminder to
+            // add it to `NewlineType::all`
+            fn declared(newline: NewlineType) -> Option<usize> {
+                match newline {
+                    NewlineType::Lf => Some(0),
+                    NewlineType::Crlf => Some(1),
+                    NewlineType::LineSeparator => Some(2),
+                    NewlineType::ParagraphSeparator => Some(3),
+                    NewlineType::Auto => None,
+                }
+            }
+
+            let all: Vec<_> = NewlineType::all().collect();
+            assert_eq!(4, all.len());
+            for (index, &newline) in all.iter().enumerate() {
+                assert_eq!(Some(index), declared(newline));
+            }
+        }
+
+        #[test]
+        fn all_as_str_unique() {
+            let all: Vec<_> = NewlineType::all().map(|newline| newline.as_str()).collect();
+            for (index, newline) in all.iter().enumerate() {
+                assert!(!all[index + 1..].contains(newline), "{:?}", newline);
+            }
+        }
+
+        #[test]
+        fn len_of_every_newline() {
+            for newline in NewlineType::all() {
+                assert_eq!(newline.as_str().len(), newline.len(), "{:?}", newline);
+                assert_eq!(newline.as_str().as_bytes(), newline.as_bytes());
+            }
+        }
+
+        #[test]
+        fn edit_takes_newline() {
+            let editor = Editor::new("-".to_string(), 1, NewlineType::Crlf);
+            assert_eq!("-", editor.edit(NewlineType::Crlf));
+        }
+    }
+
+    mod max_edits {
+        use super::*;
+        use crate::factory;
+
+        #[test]
+        fn only_first_run_edited() {
+            let editor = factory::appender("---\n", 2).max_edits(Some(1));
+            let input = "head\n\nfoo\n\nbar\n\n";
+            assert_edits("head\n\n---\nfoo\n\nbar\n\n", 1, &editor, input);
+        }
+
+        #[test]
+        fn zero_makes_no_edits() {
+            let editor = factory::replacer(" ", 1).max_edits(Some(0));
+            assert!(editor.is_noop());
+            assert_edits("foo\nbar\n", 0, &editor, "foo\nbar\n");
+        }
+
+        #[test]
+        fn limit_above_edits_made() {
+            let editor = factory::replacer(" ", 1).max_edits(Some(5));
+            assert_edits("foo bar ", 2, &editor, "foo\nbar\n");
+        }
+
+        #[test]
+        fn limit_reached_mid_run() {
+            let editor = factory::replacer("-", 2).max_edits(Some(2));
+            assert_edits("foo--\n\n\nbar", 2, &editor, "foo\n\n\n\n\n\n\nbar");
+        }
+
+        #[test]
+        fn crlf() {
+            let editor = factory::replacer_crlf(" ", 1).max_edits(Some(2));
+            let input = "a\r\nb\r\nc\r\nd";
+            assert_edits("a b c\r\nd", 2, &editor, input);
+        }
+
+        #[test]
+        fn long_run_after_limit() {
+            let editor = factory::replacer("-", 1).max_edits(Some(1));
+            let input = "\n".repeat(300);
+            let expected = format!("-{}", "\n".repeat(299));
+            assert_edits(&expected, 1, &editor, &input);
+        }
+
+        #[test]
+        fn trailing_newline() {
+            let editor = factory::replacer("-", 1)
+                .max_edits(Some(1))
+                .ensure_trailing_newline(true);
+            assert_edits("foo-bar\n", 1, &editor, "foo\nbar\n\n\n");
+        }
+    }
+
+    mod skip_edits {
+        use super::*;
+        use crate::factory;
+
+        #[test]
+        fn skip_only() {
+            let editor = factory::replacer("\n", 2).skip_edits(1);
+            let input = "head\n\nfoo\n\nbar\n\nbaz";
+            assert_edits("head\n\nfoo\nbar\nbaz", 2, &editor, input);
+        }
+
+        #[test]
+        fn skip_more_than_matches() {
+            let editor = factory::replacer(" ", 1).skip_edits(10);
+            assert_edits("foo\nbar\n", 0, &editor, "foo\nbar\n");
+        }
+
+        #[test]
+        fn skip_mid_run() {
+            let editor = factory::replacer("-", 2).skip_edits(1);
+            assert_edits("foo\n\n--\nbar", 2, &editor, "foo\n\n\n\n\n\n\nbar");
+        }
+
+        #[test]
+        fn cap_only() {
+            let editor = factory::replacer("-", 1).max_edits(Some(2));
+            assert_edits("a-b-c\nd\n", 2, &editor, "a\nb\nc\nd\n");
+        }
+
+        #[test]
+        fn skip_and_cap_window() {
+            let editor = factory::replacer("-", 1).skip_edits(1).max_edits(Some(2));
+            assert_edits("a\nb-c-d\ne", 2, &editor, "a\nb\nc\nd\ne");
+        }
+
+        #[test]
+        fn replacement_index_counts_edits() {
+            let editor = factory::replacer("", 1).skip_edits(1);
+            let output = editor.edit_with("a\nb\nc\n", |index| index.to_string());
+            assert_eq!("a\nb0c1", output);
+        }
+
+        #[test]
+        fn crlf() {
+            let editor = factory::replacer_crlf(" ", 1).skip_edits(2);
+            assert_edits("a\r\nb\r\nc d", 1, &editor, "a\r\nb\r\nc\r\nd");
+        }
+
+        #[test]
+        fn skipped_trailing_newlines_trimmed() {
+            let editor = factory::replacer("-", 2)
+                .skip_edits(2)
+                .ensure_trailing_newline(true);
+            assert_edits("foo\n", 0, &editor, "foo\n\n\n\n\n");
+        }
+    }
+
+    mod trigger_mode {
+        use super::*;
+        use crate::factory;
+
+        const RUNS: &str = "a\nb\n\nc\n\n\nd";
+
+        #[test]
+        fn exactly_is_default() {
+            assert_eq!(TriggerMode::Exactly, TriggerMode::default());
+            assert_eq!(
+                factory::replacer("-", 2),
+                factory::replacer("-", 2).trigger_mode(TriggerMode::Exactly)
+            );
+            assert_edits("a\nb-c-\nd", 2, &factory::replacer("-", 2), RUNS);
+        }
+
+        #[test]
+        fn at_least() {
+            let editor = factory::replacer_at_least("-", 2);
+            assert_edits("a\nb-c-d", 2, &editor, RUNS);
+        }
+
+        #[test]
+        fn at_most() {
+            let editor = factory::replacer_at_most("-", 2);
+            assert_edits("a-b-c\n\n\nd", 2, &editor, RUNS);
+        }
+
+        #[test]
+        fn between() {
+            let editor = factory::replacer("-", 3).trigger_mode(TriggerMode::Between(2));
+            assert_edits("a\nb-c-d\n\n\n\ne", 2, &editor, "a\nb\n\nc\n\n\nd\n\n\n\ne");
+        }
+
+        #[test]
+        fn between_ends_input() {
+            let editor = factory::replacer("-", 3).trigger_mode(TriggerMode::Between(2));
+            assert_edits("a\n", 0, &editor, "a\n");
+            assert_edits("a-", 1, &editor, "a\n\n\n");
+            assert_edits("a\n\n\n\n", 0, &editor, "a\n\n\n\n");
+        }
+
+        #[test]
+        fn between_short_runs_use_fallback() {
+            let editor = factory::replacer("-", 3)
+                .trigger_mode(TriggerMode::Between(2))
+                .fallback(Some(" "));
+            assert_edits("a b-c\n\n\n\nd", 1, &editor, "a\nb\n\nc\n\n\n\nd");
+        }
+
+        #[test]
+        fn between_up_to_one_is_at_most() {
+            for start in 0..2 {
+                let editor = factory::replacer("-", 2).trigger_mode(TriggerMode::Between(start));
+                assert_edits("a-b-c\n\n\nd", 2, &editor, RUNS);
+            }
+        }
+
+        #[test]
+        fn between_empty_range_never_met() {
+            let editor = factory::replacer("-", 2).trigger_mode(TriggerMode::Between(3));
+            assert!(editor.is_noop());
+            assert_edits(RUNS, 0, &editor, RUNS);
+
+            let editor = editor.fallback(Some(" "));
+            assert!(!editor.is_noop());
+            assert_edits("a b c\n\n\nd", 0, &editor, RUNS);
+        }
+
+        #[test]
+        fn at_least_ends_input() {
+            let editor = factory::replacer_at_least("-", 2);
+            assert_edits("a\n", 0, &editor, "a\n");
+            assert_edits("a-", 1, &editor, "a\n\n");
+            assert_edits("a-", 1, &editor, "a\n\n\n\n");
+        }
+
+        #[test]
+        fn at_least_preview_covers_whole_run() {
+            let editor = factory::replacer_at_least("-", 2);
+            let spans = editor.preview("a\n\n\nb\n\nc");
+
+            assert_eq!(2, spans.len());
+            assert_eq!((1, 3), (spans[0].byte_offset, spans[0].matched_len));
+            assert_eq!((5, 2), (spans[1].byte_offset, spans[1].matched_len));
+        }
+
+        #[test]
+        fn at_most_ends_input() {
+            let editor = factory::replacer_at_most("-", 2);
+            assert_edits("a-", 1, &editor, "a\n");
+            assert_edits("a-", 1, &editor, "a\n\n");
+            assert_edits("a\n\n\n", 0, &editor, "a\n\n\n");
+        }
+
+        #[test]
+        fn crlf() {
+            let editor = factory::replacer_at_most_crlf(" ", 1);
+            assert_edits("a b\r\n\r\nc d", 2, &editor, "a\r\nb\r\n\r\nc\r\nd");
+
+            let editor = factory::replacer_at_least_crlf("\r\n", 2);
+            assert_edits("a\r\nb\r\nc", 1, &editor, "a\r\nb\r\n\n\r\nc");
+        }
+
+        #[test]
+        fn skipped_run_passes_through() {
+            let editor = factory::replacer_at_least("-", 2).skip_edits(1);
+            assert_edits("a\n\n\nb-c", 1, &editor, "a\n\n\nb\n\n\nc");
+
+            let editor = factory::replacer_at_most("-", 2).skip_edits(1);
+            assert_edits("a\nb-c\n\n\n", 1, &editor, "a\nb\n\nc\n\n\n");
+        }
+
+        #[test]
+        fn max_edits_counts_runs() {
+            let editor = factory::replacer_at_least("-", 2).max_edits(Some(1));
+            assert_edits("a-b\n\n\n", 1, &editor, "a\n\n\nb\n\n\n");
+
+            let editor = factory::replacer_at_most("-", 1).max_edits(Some(1));
+            assert_edits("a-b\nc", 1, &editor, "a\nb\nc");
+        }
+
+        #[test]
+        fn whitespace_lines_in_run() {
+            let editor = factory::replacer_at_least("-", 2).treat_whitespace_lines_as_blank(true);
+            assert_edits("a-b", 1, &editor, "a\n \n\t\n\nb");
+
+            let editor = factory::replacer_at_most("-", 2).treat_whitespace_lines_as_blank(true);
+            assert_edits("a-b\n\n\nc", 1, &editor, "a\n \nb\n\n \t\nc");
+        }
+
+        #[test]
+        fn zero_trigger_never_met() {
+            for mode in &[
+                TriggerMode::Exactly,
+                TriggerMode::AtLeast,
+                TriggerMode::AtMost,
+                TriggerMode::Between(1),
+            ] {
+                let editor = factory::replacer("-", 0).trigger_mode(*mode);
+                assert!(editor.is_noop());
+                assert_edits(RUNS, 0, &editor, RUNS);
+
+                let editor = editor.ensure_trailing_newline(true);
+                assert_edits("\n", 0, &editor, &"\n".repeat(300));
+            }
+        }
+
+        #[test]
+        fn replacement_index_counts_runs() {
+            let editor = factory::replacer_at_most("", 1);
+            let output = editor.edit_with("a\nb\n\nc\n", |index| index.to_string());
+            assert_eq!("a0b\n\nc1", output);
+        }
+
+        #[test]
+        fn skip_empty_last_segment() {
+            let editor = factory::wrap("<", ">", 1)
+                .trigger_mode(TriggerMode::AtLeast)
+                .skip_empty_last_segment(true);
+            assert_edits("<a>\n<b>\n", 2, &editor, "a\n\nb\n\n");
+
+            let editor = factory::wrap("<", ">", 1)
+                .trigger_mode(TriggerMode::AtMost)
+                .skip_empty_last_segment(true);
+            assert_edits("<a>\n<b\n\nc>\n", 2, &editor, "a\nb\n\nc\n");
+        }
+
+        #[test]
+        fn replace_with_trigger_is_not_noop() {
+            let editor = factory::replacer("\n", 1).trigger_mode(TriggerMode::AtLeast);
+            assert!(!editor.is_noop());
+            assert_eq!(None, editor.inverse());
+            assert_edits("a\nb", 1, &editor, "a\n\nb");
+
+            let editor = factory::replacer("\n\n", 2).trigger_mode(TriggerMode::AtMost);
+            assert!(!editor.is_noop());
+            assert_edits("a\n\nb", 1, &editor, "a\nb");
+        }
+
+        #[test]
+        fn compared_by_mode() {
+            let exactly = factory::replacer("-", 2);
+            let at_least = factory::replacer_at_least("-", 2);
+            let at_most = factory::replacer_at_most("-", 2);
+
+            assert!(!exactly.behavior_eq(&at_least));
+            assert!(exactly < at_least && at_least < at_most);
+            assert_eq!(
+                "replace 2 or more LF newline(s) with \"-\"",
+                at_least.to_string()
+            );
+            assert_eq!(
+                "replace up to 2 LF newline(s) with \"-\"",
+                at_most.to_string()
+            );
+
+            let between = factory::replacer("-", 3).trigger_mode(TriggerMode::Between(2));
+            assert!(at_most < between);
+            assert_eq!(
+                "replace 2 to 3 LF newline(s) with \"-\"",
+                between.to_string()
+            );
+        }
+    }
+
+    mod long_runs {
+        use super::*;
+        use crate::factory;
+
+        #[test]
+        fn trigger_above_255() {
+            let input = format!("a{}b", "\n".repeat(300));
+            let rest = "\n".repeat(44);
+
+            let expected = format!("a-{}b", rest);
+            assert_edits(&expected, 1, &factory::replacer("-", 256), &input);
+
+            let expected = format!("a-{}b", "\n".repeat(300));
+            assert_edits(&expected, 1, &factory::inserter("-", 256), &input);
+
+            assert_edits("a-b", 1, &factory::replacer_at_least("-", 256), &input);
+            assert_edits(&input, 0, &factory::replacer_at_most("-", 256), &input);
+        }
+
+        #[test]
+        fn run_longer_than_255() {
+            let input = format!("a{}b\n", "\n".repeat(300));
+            let expected = format!("a{}-b\n", "\n".repeat(300));
+            assert_edits(&expected, 1, &factory::appender("-", 300), &input);
+            assert_edits("a-b-", 2, &factory::replacer_at_most("-", 300), &input);
+        }
+
+        #[test]
+        fn every_pair_in_long_run() {
+            let input = "\n".repeat(1000);
+            let editor = factory::replacer("-", 2);
+
+            assert_edits(&"-".repeat(500), 500, &editor, &input);
+
+            let input = "\r\n".repeat(1001);
+            let expected = format!("{}\r\n", "-".repeat(500));
+            assert_edits(&expected, 500, &factory::replacer_crlf("-", 2), &input);
+        }
+
+        #[test]
+        fn zero_trigger_in_long_run() {
+            let input = format!("a{}", "\n".repeat(1000));
+            let editor = factory::replacer("", 0).ensure_trailing_newline(true);
+            assert_edits("a\n", 0, &editor, &input);
+        }
+    }
+
+    mod ordering {
+        use super::*;
+        use crate::factory;
+        use std::collections::BTreeSet;
+
+        #[test]
+        fn newlines_first() {
+            assert!(factory::replacer("z", 1) < factory::replacer("a", 2));
+            assert!(factory::replacer_crlf("a", 1) < factory::replacer("a", 2));
+        }
+
+        #[test]
+        fn then_line_ending() {
+            assert!(factory::replacer("z", 2) < factory::replacer_crlf("a", 2));
+            assert!(NewlineType::Crlf < NewlineType::LineSeparator);
+        }
+
+        #[test]
+        fn then_replace() {
+            assert!(factory::replacer("a", 2) < factory::replacer("b", 2));
+        }
+
+        #[test]
+        fn equal_only_when_identical() {
+            let editor = factory::replacer("\n--", 1);
+            let appender = factory::appender("--", 1);
+            assert_ne!(Ordering::Equal, editor.cmp(&appender));
+            assert_eq!(Ordering::Equal, editor.cmp(&editor.clone()));
+
+            let trailing = editor.clone().ensure_trailing_newline(true);
+            assert!(editor < trailing);
+        }
+
+        #[test]
+        fn deduplicate_in_set() {
+            let set: BTreeSet<Editor> = vec![
+                factory::appender("-", 2),
+                factory::replacer(" ", 1),
+                factory::appender("-", 2),
+                Editor::default(),
+            ]
+            .into_iter()
+            .collect();
+
+            let expected = vec![
+                Editor::default(),
+                factory::replacer(" ", 1),
+                factory::appender("-", 2),
+            ];
+            assert_eq!(expected, set.into_iter().collect::<Vec<_>>());
+        }
+    }
+
+    mod display {
+        use super::*;
+        use crate::factory;
+
+        #[test]
+        fn default_editor() {
+            let expected = r#"replace 0 LF newline(s) with """#;
+            assert_eq!(expected, Editor::default().to_string());
+        }
+
+        #[test]
+        fn replace_escapes_control_characters() {
+            let editor = Editor::new("\r\n\t\u{7}".to_string(), 3, NewlineType::Crlf);
+            let expected = r#"replace 3 CRLF newline(s) with "\r\n\t\u{7}""#;
+            assert_eq!(expected, editor.to_string());
+        }
+
+        #[test]
+        fn insert_text() {
+            let expected = r#"insert "\"-\"" before 2 LF newline(s)"#;
+            assert_eq!(expected, factory::inserter("\"-\"", 2).to_string());
+        }
+
+        #[test]
+        fn unicode_separator() {
+            let editor = Editor::new("\u{2029}".to_string(), 1, NewlineType::LineSeparator);
+            let expected = r#"replace 1 LS newline(s) with "\u{2029}""#;
+            assert_eq!(expected, editor.to_string());
+        }
+
+        #[test]
+        fn wrap_and_trailing_newline() {
+            let editor = factory::wrap("<p>", "</p>", 2).ensure_trailing_newline(true);
+            let expected = concat!(
+                r#"replace 2 LF newline(s) with "</p>\n\n<p>", "#,
+                r#"wrapped in "<p>" and "</p>", ensuring a trailing newline"#
+            );
+            assert_eq!(expected, editor.to_string());
+        }
+
+        #[test]
+        fn line_prefixer() {
+            let expected = concat!(
+                r#"replace 1 LF newline(s) with "\n> ", "#,
+                r#"wrapped in "> " and "", skipping an empty last segment"#
+            );
+            assert_eq!(expected, factory::line_prefixer("> ").to_string());
+        }
+    }
+
+    mod debug {
+        use super::*;
+        use crate::factory;
+
+        fn assert_escaped(debug: &str) {
+            assert!(!debug.contains(|c| c == '\r' || c == '\t'), "{}", debug);
+            assert!(debug.contains(r#"replace: "\n\n""#), "{}", debug);
+            assert!(debug.contains(r#"open: "\t<""#), "{}", debug);
+            assert!(debug.contains(r#""\r\n""#), "{}", debug);
+        }
+
+        #[test]
+        fn escapes_control_characters() {
+            let editor = factory::replacer("\n\n", 1)
+                .wrap("\t<", ">")
+                .fallback(Some("\r\n"));
+
+            let debug = format!("{:?}", editor);
+            assert!(!debug.contains('\n'), "{}", debug);
+            assert!(debug.contains(r#"fallback: Some("\r\n")"#), "{}", debug);
+            assert_escaped(&debug);
+        }
+
+        #[test]
+        fn pretty_escapes_control_characters() {
+            let editor = factory::replacer("\n\n", 1)
+                .wrap("\t<", ">")
+                .fallback(Some("\r\n"));
+
+            // no string is broken over more than one line
+            let debug = format!("{:#?}", editor);
+            for line in debug.lines() {
+                assert_eq!(0, line.matches('"').count() % 2, "{}", line);
+            }
+            assert_escaped(&debug);
+        }
+    }
+
+    mod scan {
+        use super::*;
+        use std::io::Cursor;
+
+        /// Every string of up to `len` characters from `alphabet`.
+        fn inputs(alphabet: &[char], len: usize) -> Vec<String> {
+            let mut inputs = vec![String::new()];
+            let mut last = vec![String::new()];
+
+            for _ in 0..len {
+                last = last
+                    .iter()
+                    .flat_map(|input| {
+                        alphabet.iter().map(move |&c| {
+                            let mut input = input.clone();
+                            input.push(c);
+                            input
+                        })
+                    })
+                    .collect();
+                inputs.extend(last.iter().cloned());
+            }
+
+            inputs
+        }
+
+        /// `edit` skips between newlines, while `edit_buffered` edits one
+        /// line at a time, so they should always agree.
+        fn assert_same_as_buffered(editor: &Editor, alphabet: &[char]) {
+            for input in inputs(alphabet, 6) {
+                let mut output = Vec::new();
+                editor
+                    .edit_buffered(&mut Cursor::new(&input), &mut output)
+                    .unwrap();
+
+                assert_eq!(
+                    String::from_utf8(output).unwrap(),
+                    editor.edit(&input),
+                    "\neditor: {}\ninput: {:?}\n",
+                    editor,
+                    input
+                );
+            }
+        }
+
+        #[test]
+        fn lf() {
+            assert_same_as_buffered(&factory::replacer("-", 2), &['a', '\n', '\r']);
+            assert_same_as_buffered(&factory::inserter("é", 1), &['é', '\n', '\r']);
+            assert_same_as_buffered(
+                &factory::replacer("-", 2).strip_cr(true),
+                &['a', '\n', '\r'],
+            );
+        }
+
+        #[test]
+        fn crlf() {
+            assert_same_as_buffered(&factory::replacer_crlf("-", 2), &['a', '\n', '\r']);
+            assert_same_as_buffered(&factory::appender_crlf("\r", 1), &['a', '\n', '\r']);
+            assert_same_as_buffered(&factory::line_prefixer_crlf(">"), &['a', '\n', '\r']);
+        }
+
+        #[test]
+        fn separators() {
+            let editor = factory::replacer_with("\n", 2, NewlineType::LineSeparator);
+            assert_same_as_buffered(&editor, &['a', '\u{2028}', '\u{2029}']);
+        }
+    }
+
+    mod edit_range {
+        use super::*;
+
+        const INPUT: &str = "a\n\nb\n\nc\n\nd";
+
+        #[test]
+        fn middle_lines() {
+            let editor = factory::replacer("-", 2);
+            assert_eq!("a\n\nb-c\n\nd", editor.edit_range(INPUT, 3, 4));
+        }
+
+        #[test]
+        fn whole_input() {
+            let editor = factory::replacer("-", 2);
+            assert_eq!(editor.edit(INPUT), editor.edit_range(INPUT, 1, 7));
+            assert_eq!(
+                editor.edit(INPUT),
+                editor.edit_range(INPUT, 0, usize::max_value())
+            );
+        }
+
+        #[test]
+        fn run_straddling_start_not_counted() {
+            // line 2 is the second newline of the first run
+            let editor = factory::replacer("-", 2);
+            assert_eq!("a\n\nb-c\n\nd", editor.edit_range(INPUT, 2, 4));
+        }
+
+        #[test]
+        fn run_straddling_end_not_counted() {
+            // the run after line 3 ends on line 4
+            let editor = factory::replacer("-", 2);
+            assert_eq!("a-b\n\nc\n\nd", editor.edit_range(INPUT, 1, 3));
+        }
+
+        #[test]
+        fn end_past_last_line() {
+            let editor = factory::inserter(";", 1);
+            assert_eq!("foo\nbar;\nbaz", editor.edit_range("foo\nbar\nbaz", 2, 100));
+        }
+
+        #[test]
+        fn start_past_last_line_unchanged() {
+            let editor = factory::replacer("-", 1);
+            assert_eq!("foo\nbar\n", editor.edit_range("foo\nbar\n", 3, 10));
+        }
+
+        #[test]
+        fn start_after_end_unchanged() {
+            let editor = factory::replacer("-", 1);
+            assert_eq!("foo\nbar\n", editor.edit_range("foo\nbar\n", 2, 1));
+        }
+
+        #[test]
+        fn crlf_lines() {
+            let editor = factory::replacer_crlf(" ", 1);
+            let output = editor.edit_range("a\r\nb\r\nc\r\nd", 2, 3);
+            assert_eq!("a\r\nb c d", output);
+        }
+
+        #[test]
+        fn wrap_applies_to_range() {
+            let editor = factory::wrap("<p>", "</p>", 2);
+            let output = editor.edit_range("head\nfoo\n\nbar", 2, 4);
+            assert_eq!("head\n<p>foo</p>\n\n<p>bar</p>", output);
+        }
+    }
+
+    mod with_line_ending {
+        use super::*;
+
+        #[test]
+        fn appender_to_crlf() {
+            let editor = factory::appender("-", 1).with_line_ending(NewlineType::Crlf);
+            assert_eq!(factory::appender_crlf("-", 1), editor);
+        }
+
+        #[test]
+        fn inserter_to_lf() {
+            let editor = factory::inserter_crlf("-", 2).with_line_ending(NewlineType::Lf);
+            assert_eq!(factory::inserter("-", 2), editor);
+        }
+
+        #[test]
+        fn replacer_keeps_replace() {
+            let editor = factory::replacer("\n", 2).with_line_ending(NewlineType::Crlf);
+            assert_eq!(factory::replacer_crlf("\n", 2), editor);
+        }
+
+        #[test]
+        fn new_keeps_replace() {
+            let editor = Editor::new("\n-".to_string(), 1, NewlineType::Lf);
+            let expected = Editor::new("\n-".to_string(), 1, NewlineType::LineSeparator);
+            assert_eq!(
+                expected,
+                editor.with_line_ending(NewlineType::LineSeparator)
+            );
+        }
+
+        #[test]
+        fn wrap_to_crlf() {
+            let editor = factory::wrap("<p>", "</p>", 2).with_line_ending(NewlineType::Crlf);
+            assert_eq!(factory::wrap_crlf("<p>", "</p>", 2), editor);
+        }
+
+        #[test]
+        fn options_copied() {
+            let editor = factory::appender("-", 1)
+                .max_edits(Some(2))
+                .skip_edits(1)
+                .ensure_trailing_newline(true)
+                .with_line_ending(NewlineType::Crlf);
+
+            let expected = factory::appender_crlf("-", 1)
+                .max_edits(Some(2))
+                .skip_edits(1)
+                .ensure_trailing_newline(true);
+            assert_eq!(expected, editor);
+        }
+
+        #[test]
+        fn same_line_ending_is_clone() {
+            let editor = factory::wrap("(", ")", 1);
+            assert_eq!(editor, editor.with_line_ending(NewlineType::Lf));
+        }
+    }
+
+    mod clone_with_text {
+        use super::*;
+        use crate::factory;
+
+        #[test]
+        fn keeps_edit_type() {
+            assert_eq!(
+                factory::inserter("b", 2),
+                factory::inserter("a", 2).clone_with_text("b")
+            );
+            assert_eq!(
+                factory::appender_crlf("", 1),
+                factory::appender_crlf("-", 1).clone_with_text("")
+            );
+            assert_eq!(
+                factory::replacer_with("\u{2028}", 3, NewlineType::LineSeparator),
+                factory::replacer_with(" ", 3, NewlineType::LineSeparator)
+                    .clone_with_text("\u{2028}")
+            );
+        }
+
+        #[test]
+        fn keeps_options() {
+            let editor = factory::appender("-", 2)
+                .max_edits(Some(1))
+                .strip_cr(true)
+                .ensure_trailing_newline(true);
+            let expected = factory::appender("+", 2)
+                .max_edits(Some(1))
+                .strip_cr(true)
+                .ensure_trailing_newline(true);
+
+            assert_eq!(expected, editor.clone_with_text("+"));
+        }
+
+        #[test]
+        fn auto_resolves_per_input() {
+            let editor = factory::inserter_with("-", 1, NewlineType::Auto).clone_with_text(";");
+            assert_eq!("a;\r\nb", editor.edit("a\r\nb"));
+            assert_eq!("a;\nb", editor.edit("a\nb"));
+        }
+    }
+
+    mod edit_all {
+        use super::*;
+        use crate::factory;
+
+        #[test]
+        fn same_as_each_edit() {
+            let editor = factory::wrap("<", ">", 2).ensure_trailing_newline(true);
+            let inputs = vec![
+                "foo\n\nbar".to_string(),
+                String::new(),
+                "baz\n\n\n\nqux\n\n".to_string(),
+            ];
+
+            let expected: Vec<String> = inputs.iter().map(|input| editor.edit(input)).collect();
+            assert_eq!(expected, editor.edit_all(&inputs));
+            assert_eq!(expected, editor.edit_all(inputs));
+        }
+
+        #[test]
+        fn auto_resolves_each_input() {
+            let editor = factory::replacer_with("-", 1, NewlineType::Auto);
+            let edited = editor.edit_all(&["a\r\nb", "a\r\nb\nc\n"]);
+            assert_eq!(vec!["a-b", "a\r-b-c-"], edited);
+        }
+
+        #[test]
+        fn no_inputs() {
+            let inputs: [&str; 0] = [];
+            assert!(factory::replacer("-", 1).edit_all(&inputs).is_empty());
+        }
+    }
+
+    mod edit_reversed {
+        use super::*;
+        use crate::factory;
+
+        #[test]
+        fn lf_without_trailing_newline() {
+            let editor = factory::replacer("\n", 2);
+            assert_eq!("c\nb\na", editor.edit_reversed("a\n\nb\nc"));
+        }
+
+        #[test]
+        fn lf_with_trailing_newline() {
+            let editor = factory::replacer("\n", 2);
+            assert_eq!("c\nb\na\n", editor.edit_reversed("a\n\nb\nc\n\n"));
+        }
+
+        #[test]
+        fn crlf_without_trailing_newline() {
+            let editor = factory::inserter_crlf(";", 1);
+            assert_eq!("c\r\nb;\r\na;", editor.edit_reversed("a\r\nb\nc"));
+        }
+
+        #[test]
+        fn crlf_with_trailing_newline() {
+            let editor = factory::inserter_crlf(";", 1);
+            assert_eq!("c;\r\nb;\r\na;\r\n", editor.edit_reversed("a\r\nb\nc\r\n"));
+        }
+
+        #[test]
+        fn replacement_newlines_split_lines() {
+            let editor = factory::replacer_crlf("\n-\n", 2);
+            assert_eq!("b\r\n-\r\na", editor.edit_reversed("a\r\n\r\nb"));
+        }
+
+        #[test]
+        fn converted_newlines() {
+            let editor =
+                Editor::new_converting(String::new(), 2, NewlineType::Auto, NewlineType::Lf);
+            assert_eq!("c\nab\n", editor.edit_reversed("a\r\n\r\nb\r\nc\r\n"));
+        }
+
+        #[test]
+        fn blank_lines_are_kept() {
+            let editor = factory::replacer("", 0);
+            assert_eq!("\n\nb\n\na\n", editor.edit_reversed("a\n\nb\n\n\n"));
+        }
+
+        #[test]
+        fn empty_input() {
+            let editor = factory::replacer("-", 1);
+            assert_eq!("", editor.edit_reversed(""));
+            assert_eq!("\n", factory::replacer("\n", 2).edit_reversed("\n"));
+        }
+    }
+
+    mod fallback {
+        use super::*;
+        use crate::factory;
+
+        #[test]
+        fn single_and_double_newlines() {
+            let editor = factory::replacer("<hr>", 2).fallback(Some("<br>"));
+            assert_edits("a<br>b<hr>c<br>", 1, &editor, "a\nb\n\nc\n");
+        }
+
+        #[test]
+        fn single_and_double_newlines_crlf() {
+            let editor = factory::replacer_crlf("<hr>", 2).fallback(Some("<br>"));
+            assert_edits("a<br>b<hr>c<br>", 1, &editor, "a\r\nb\r\n\nc\r\n");
+        }
+
+        #[test]
+        fn remainder_of_run() {
+            let editor = factory::replacer("<hr>", 2).fallback(Some("<br>"));
+            assert_edits("a<hr><br>b<hr><hr>c", 3, &editor, "a\n\n\nb\n\n\n\nc");
+        }
+
+        #[test]
+        fn at_least() {
+            let editor = factory::replacer_at_least("<hr>", 2).fallback(Some("<br>"));
+            assert_edits("a<br>b<hr>c<hr>", 2, &editor, "a\nb\n\n\n\nc\n\n");
+        }
+
+        #[test]
+        fn at_most_has_no_effect() {
+            let editor = factory::replacer_at_most("-", 1).fallback(Some("<br>"));
+            assert_edits("a-b\n\nc", 1, &editor, "a\nb\n\nc");
+        }
+
+        #[test]
+        fn skipped_and_limited_runs_kept() {
+            let editor = factory::replacer("<hr>", 2)
+                .fallback(Some("<br>"))
+                .skip_edits(1)
+                .max_edits(Some(1));
+            assert_edits(
+                "a<br>b\n\nc<hr>d\n\ne\nf",
+                1,
+                &editor,
+                "a\nb\n\nc\n\nd\n\ne\nf",
+            );
+        }
+
+        #[test]
+        fn whitespace_lines() {
+            let editor = factory::replacer("<hr>", 3)
+                .fallback(Some(" "))
+                .treat_whitespace_lines_as_blank(true);
+            assert_edits("a b<hr>c", 1, &editor, "a\n \nb\n\t\n\nc");
+        }
+
+        #[test]
+        fn trailing_newline() {
+            let editor = factory::replacer("", 3)
+                .fallback(Some("\n"))
+                .ensure_trailing_newline(true);
+            assert_edits("a\nb\n", 0, &editor, "a\n\nb\n\n");
+        }
+
+        #[test]
+        fn none_keeps_runs() {
+            let editor = factory::replacer("-", 2).fallback(Some("+")).fallback(None);
+            assert_eq!(factory::replacer("-", 2), editor);
+        }
+
+        #[test]
+        fn changes_noop_and_inverse() {
+            let editor = factory::appender("", 2).fallback(Some(" "));
+            assert!(!editor.is_noop());
+            assert_eq!("a b\n\nc", editor.edit("a\nb\n\nc"));
+
+            let editor = factory::replacer("\n", 2).fallback(Some(""));
+            assert_eq!(None, editor.inverse());
+        }
+
+        #[test]
+        fn idempotent_without_newlines() {
+            let editor = factory::replacer(" ", 2).fallback(Some(""));
+            assert!(editor.is_idempotent());
+            assert!(!editor.clone().fallback(Some("\n\n")).is_idempotent());
+            assert!(!editor.ensure_trailing_newline(true).is_idempotent());
+        }
+
+        #[test]
+        fn display() {
+            let editor = factory::replacer("-", 2).fallback(Some("+"));
+            assert_eq!(
+                r#"replace 2 LF newline(s) with "-", replacing shorter runs with "+""#,
+                editor.to_string()
+            );
+        }
+    }
+
+    mod template {
+        use super::*;
+        use crate::factory;
+
+        #[test]
+        fn number_increments_each_match() {
+            let editor = factory::template_replacer("<{n}>", 2);
+            assert_edits("a<1>b<2>c\nd<3>", 3, &editor, "a\n\nb\n\nc\nd\n\n");
+        }
+
+        #[test]
+        fn number_increments_each_match_crlf() {
+            let editor = factory::template_replacer_crlf("<{n}>", 1);
+            assert_edits("a<1>b<2><3>", 3, &editor, "a\r\nb\r\n\n");
+        }
+
+        #[test]
+        fn number_counts_edits_not_skipped_runs() {
+            let editor = factory::template_replacer("<{n}>", 1).skip_edits(1);
+            assert_edits("a\nb<1>c<2>", 2, &editor, "a\nb\nc\n");
+        }
+
+        #[test]
+        fn count_is_run_length() {
+            let editor = factory::template_replacer("<{count}>", 3);
+            assert_edits("a<3>b", 1, &editor, "a\n\n\nb");
+
+            let editor = editor.trigger_mode(TriggerMode::AtMost);
+            assert_edits(
+                "a<1>b<3>c\n\n\n\nd<2>",
+                3,
+                &editor,
+                "a\nb\n\n\nc\n\n\n\nd\n\n",
+            );
+
+            let editor = editor.trigger_mode(TriggerMode::AtLeast);
+            assert_edits("a\nb<3>c<4>d\n\n", 2, &editor, "a\nb\n\n\nc\n\n\n\nd\n\n");
+        }
+
+        #[test]
+        fn escaped_braces() {
+            let editor = factory::template_replacer("{{n}} }}{n}{{", 1);
+            assert_edits("a{n} }1{b", 1, &editor, "a\nb");
+        }
+
+        #[test]
+        fn other_braces_are_kept() {
+            let editor = factory::template_replacer("{x} {n {}", 1);
+            assert_edits("a{x} {n {}b", 1, &editor, "a\nb");
+        }
+
+        #[test]
+        fn wrapped_with_skipped_empty_end() {
+            let editor = factory::template_replacer("]\n{n}[", 1)
+                .wrap("[", "]")
+                .skip_empty_last_segment(true);
+            assert_edits("[a]\n1[b]\n2", 2, &editor, "a\nb\n");
+        }
+
+        #[test]
+        fn preview_expands_template() {
+            let spans = factory::template_replacer("{n}:{count}", 2).preview("a\n\nb\n\n");
+            let replacements: Vec<_> = spans.iter().map(|span| span.replacement.as_str()).collect();
+            assert_eq!(vec!["1:2", "2:2"], replacements);
+        }
+
+        #[test]
+        fn not_inverted_or_noop() {
+            let editor = factory::template_replacer("\n", 1);
+            assert!(!editor.is_noop());
+            assert_eq!(None, editor.inverse());
+            assert_ne!(factory::replacer("\n", 1), editor);
+        }
+
+        #[test]
+        fn push_template_without_placeholders() {
+            let mut output = String::from("a");
+            push_template(&mut output, "bc", 1, 2);
+            assert_eq!("abc", output);
+        }
+    }
+
+    mod behavior_eq {
+        use super::*;
+
+        #[test]
+        fn appender_same_as_replacer() {
+            let append = factory::appender("-", 2);
+            let replace = factory::replacer("\n\n-", 2);
+
+            assert_ne!(append, replace);
+            assert!(append.behavior_eq(&replace));
+            assert!(replace.behavior_eq(&append));
+        }
+
+        #[test]
+        fn inserter_same_as_new() {
+            let insert = factory::inserter_crlf("-", 1);
+            let new = Editor::new("-\r\n".to_string(), 1, NewlineType::Crlf);
+
+            assert_ne!(insert, new);
+            assert!(insert.behavior_eq(&new));
+        }
+
+        #[test]
+        fn equal_editors() {
+            let editor = factory::wrap("(", ")", 1).max_edits(Some(2));
+            assert!(editor.behavior_eq(&editor.clone()));
+        }
+
+        #[test]
+        fn options_differ() {
+            let editor = factory::replacer("-", 1);
+
+            assert!(!editor.behavior_eq(&factory::replacer("-", 2)));
+            assert!(!editor.behavior_eq(&factory::replacer_crlf("-", 1)));
+            assert!(!editor.behavior_eq(&editor.clone().max_edits(Some(1))));
+            assert!(!editor.behavior_eq(&editor.clone().skip_edits(1)));
+            assert!(!editor.behavior_eq(&editor.clone().strip_cr(true)));
+            assert!(!editor.behavior_eq(&editor.clone().wrap("(", ")")));
+            assert!(!editor.behavior_eq(&editor.clone().ensure_trailing_newline(true)));
+        }
+    }
+
+    mod auto {
+        use super::*;
+
+        const LF: &str = "foo\n\nbar\nbaz\n\n\nqux\n";
+        const CRLF: &str = "foo\r\n\r\nbar\r\nbaz\r\n\r\n\r\nqux\r\n";
+
+        fn edit_buffered(editor: &Editor, input: &str) -> String {
+            let mut output = Vec::new();
+            editor
+                .edit_buffered(&mut input.as_bytes(), &mut output)
+                .unwrap();
+            String::from_utf8(output).unwrap()
+        }
+
+        #[test]
+        fn same_editor_lf_and_crlf() {
+            let editor = factory::appender_with("-", 2, NewlineType::Auto);
+
+            assert_eq!(factory::appender("-", 2).edit(LF), editor.edit(LF));
+            assert_eq!(factory::appender_crlf("-", 2).edit(CRLF), editor.edit(CRLF));
+        }
+
+        #[test]
+        fn buffered_lf_and_crlf() {
+            let editor = factory::wrap_with("<p>", "</p>", 2, NewlineType::Auto)
+                .ensure_trailing_newline(true);
+
+            let lf = factory::wrap("<p>", "</p>", 2).ensure_trailing_newline(true);
+            let crlf = factory::wrap_crlf("<p>", "</p>", 2).ensure_trailing_newline(true);
+
+            assert_eq!(lf.edit(LF), edit_buffered(&editor, LF));
+            assert_eq!(crlf.edit(CRLF), edit_buffered(&editor, CRLF));
+        }
+
+        #[test]
+        fn predominant_style() {
+            let editor = factory::inserter_with(";", 1, NewlineType::Auto);
+
+            assert_eq!("a;\r\nb;\r\nc;\r\n", editor.edit("a\r\nb\nc\r\n"));
+            assert_eq!("a\r;\nb;\nc;\n", editor.edit("a\r\nb\nc\n"));
+        }
+
+        #[test]
+        fn buffered_uses_first_line() {
+            let editor = factory::inserter_with(";", 1, NewlineType::Auto);
+
+            assert_eq!("a;\r\nb;\r\nc;\r\n", edit_buffered(&editor, "a\r\nb\nc\n"));
+            assert_eq!("a;\nb\r;\nc\r;\n", edit_buffered(&editor, "a\nb\r\nc\r\n"));
+        }
+
+        #[test]
+        fn no_newlines_is_lf() {
+            let editor =
+                factory::replacer_with("-", 1, NewlineType::Auto).ensure_trailing_newline(true);
+            assert_eq!("foo\n", editor.edit("foo"));
+        }
+
+        #[test]
+        fn other_methods() {
+            let editor = factory::replacer_with(" ", 2, NewlineType::Auto);
+            let crlf = factory::replacer_crlf(" ", 2);
+
+            assert_eq!(crlf.edit_cow(CRLF), editor.edit_cow(CRLF));
+            assert_eq!(
+                crlf.edit_lines(CRLF).collect::<Vec<_>>(),
+                editor.edit_lines(CRLF).collect::<Vec<_>>()
+            );
+            assert_eq!(crlf.edit_range(CRLF, 2, 6), editor.edit_range(CRLF, 2, 6));
+            assert_eq!(crlf.analyze(CRLF), editor.analyze(CRLF));
+
+            let mut text = CRLF.to_string();
+            editor.edit_in_place(&mut text);
+            assert_eq!(crlf.edit(CRLF), text);
+        }
+
+        #[test]
+        fn never_noop() {
+            let editor = Editor::new(String::new(), 0, NewlineType::Auto);
+
+            assert!(!editor.is_noop());
+            assert_eq!("a\r\nb\r\nc\r\n", editor.edit("a\r\nb\nc\r\n"));
+            assert_eq!(None, editor.inverse());
+        }
+
+        #[test]
+        fn detect() {
+            assert_eq!(NewlineType::Lf, NewlineType::detect(""));
+            assert_eq!(NewlineType::Lf, NewlineType::detect("a\r\nb\n"));
+            assert_eq!(NewlineType::Crlf, NewlineType::detect("a\r\n"));
+            assert_eq!(NewlineType::Lf, NewlineType::detect("a\rb\r"));
+        }
+    }
+
+    mod converting {
+        use super::*;
+        use NewlineType::{Crlf, Lf};
+
+        /// Runs of 1, 3 and 2 newlines, edited with a trigger of 2.
+        fn input(ending: NewlineType) -> String {
+            "a\nb\n\n\nc\n\nd".replace('\n', ending.as_str())
+        }
+
+        fn assert_converts(input_ending: NewlineType, output_ending: NewlineType) {
+            let replace = format!("-{}", output_ending.as_str());
+            let editor = Editor::new_converting(replace, 2, input_ending, output_ending);
+            let expected = "a\nb-\n\nc-\nd".replace('\n', output_ending.as_str());
+
+            let input = input(input_ending);
+            assert_eq!(
+                expected,
+                editor.edit(&input),
+                "{} to {}",
+                input_ending,
+                output_ending
+            );
+
+            let mut output = Vec::new();
+            editor
+                .edit_buffered(&mut input.as_bytes(), &mut output)
+                .unwrap();
+            assert_eq!(expected, String::from_utf8(output).unwrap());
+        }
+
+        #[test]
+        fn lf_to_lf() {
+            assert_converts(Lf, Lf);
+        }
+
+        #[test]
+        fn lf_to_crlf() {
+            assert_converts(Lf, Crlf);
+        }
+
+        #[test]
+        fn crlf_to_lf() {
+            assert_converts(Crlf, Lf);
+        }
+
+        #[test]
+        fn crlf_to_crlf() {
+            assert_converts(Crlf, Crlf);
+        }
+
+        #[test]
+        fn same_ending_is_new() {
+            let editor = Editor::new_converting("-".to_string(), 1, Crlf, Crlf);
+            assert_eq!(Editor::new("-".to_string(), 1, Crlf), editor);
+
+            let editor = Editor::new_converting("-".to_string(), 1, Lf, NewlineType::Auto);
+            assert_eq!(Editor::new("-".to_string(), 1, Lf), editor);
+        }
+
+        #[test]
+        fn auto_input() {
+            let editor = Editor::new_converting(String::new(), 2, NewlineType::Auto, Lf);
+
+            assert_eq!("a\nb\ncd", editor.edit(input(Lf)));
+            assert_eq!("a\nb\ncd", editor.edit(input(Crlf)));
+        }
+
+        #[test]
+        fn trailing_newline() {
+            let editor =
+                Editor::new_converting(" ".to_string(), 3, Crlf, Lf).ensure_trailing_newline(true);
+            let input = "foo\r\nbar\r\n\r\n";
+
+            assert_eq!("foo\nbar\n", editor.edit(input));
+
+            let mut text = input.to_string();
+            editor.edit_in_place(&mut text);
+            assert_eq!("foo\nbar\n", text);
+        }
+
+        #[test]
+        fn never_noop() {
+            let editor = Editor::new_converting(String::new(), 0, Crlf, Lf);
+
+            assert!(!editor.is_noop());
+            assert_eq!("a\nb", editor.edit_cow("a\r\nb"));
+            assert_eq!(None, editor.inverse());
+        }
+
+        #[test]
+        fn accessors() {
+            let editor = Editor::new_converting(String::new(), 1, NewlineType::Auto, Crlf);
+
+            assert_eq!(NewlineType::Auto, editor.line_ending());
+            assert_eq!(Crlf, editor.output_ending());
+            assert_eq!(Lf, factory::replacer("", 1).output_ending());
+        }
+
+        #[test]
+        fn display() {
+            let editor = Editor::new_converting(" ".to_string(), 2, Crlf, Lf);
+            assert_eq!(
+                "replace 2 CRLF newline(s) with \" \", writing LF newlines",
+                editor.to_string()
+            );
+        }
+    }
+
+    mod blank_whitespace {
+        use super::*;
+
+        const INPUT: &str = "foo\n  \n\t\nbar\n \t \nbaz  \n\n  qux\n  ";
+
+        #[test]
+        fn whitespace_lines_count_towards_run() {
+            let editor = factory::replacer("-", 3).treat_whitespace_lines_as_blank(true);
+            assert_eq!("foo-bar\n\nbaz  \n\n  qux\n  ", editor.edit(INPUT));
+        }
+
+        #[test]
+        fn off_by_default() {
+            let editor = factory::replacer("-", 3);
+            assert_eq!(INPUT, editor.edit(INPUT));
+        }
+
+        #[test]
+        fn whitespace_removed_from_unedited_runs() {
+            let editor = factory::replacer("-", 4).treat_whitespace_lines_as_blank(true);
+            assert_eq!("foo\n\n\nbar\n\nbaz  \n\n  qux\n  ", editor.edit(INPUT));
+        }
+
+        #[test]
+        fn first_line() {
+            let editor = factory::inserter("-", 2).treat_whitespace_lines_as_blank(true);
+            assert_eq!("-\n\nfoo", editor.edit("\t\n\nfoo"));
+        }
+
+        #[test]
+        fn crlf() {
+            let editor = factory::replacer_crlf("-", 2).treat_whitespace_lines_as_blank(true);
+            assert_eq!("foo-bar", editor.edit("foo\r\n \r\nbar"));
+        }
+
+        #[test]
+        fn lone_cr_is_not_whitespace() {
+            let editor = factory::replacer("-", 2).treat_whitespace_lines_as_blank(true);
+            assert_eq!("foo\n \r\nbar", editor.edit("foo\n \r\nbar"));
+        }
+
+        #[test]
+        fn buffered_same_as_edit() {
+            let editor = factory::appender("-", 2)
+                .treat_whitespace_lines_as_blank(true)
+                .ensure_trailing_newline(true);
+            let mut output = Vec::new();
+
+            editor
+                .edit_buffered(&mut INPUT.as_bytes(), &mut output)
+                .unwrap();
+
+            assert_eq!(editor.edit(INPUT), String::from_utf8(output).unwrap());
+        }
+
+        #[test]
+        fn in_place_same_as_edit() {
+            let editor = factory::replacer("\n", 2).treat_whitespace_lines_as_blank(true);
+            let mut text = INPUT.to_string();
+
+            editor.edit_in_place(&mut text);
+
+            assert_eq!(editor.edit(INPUT), text);
+        }
+
+        #[test]
+        fn never_noop() {
+            let editor = Editor::default().treat_whitespace_lines_as_blank(true);
+
+            assert!(!editor.is_noop());
+            assert_eq!("\nfoo", editor.edit_cow(" \nfoo"));
+            assert_eq!(None, editor.inverse());
+        }
+    }
+
+    mod trim_trailing_whitespace {
+        use super::*;
+
+        const INPUT: &str = "foo \t\nbar\t\n \t\n\nbaz  \r\nqux \t";
+
+        #[test]
+        fn kept_by_default() {
+            let editor = factory::inserter("", 1);
+            assert_eq!(INPUT, editor.edit(INPUT));
+        }
+
+        #[test]
+        fn mixed_spaces_and_tabs() {
+            let editor = factory::inserter("", 1).trim_trailing_whitespace(true);
+            assert_eq!("foo\nbar\n\n\nbaz  \r\nqux", editor.edit(INPUT));
+        }
+
+        #[test]
+        fn last_line_without_newline() {
+            let editor = factory::inserter("", 1).trim_trailing_whitespace(true);
+            assert_eq!("foo\nbar", editor.edit("foo \nbar\t \t"));
+            assert_eq!("foo\n", editor.edit("foo\n  "));
+            assert_eq!("", editor.edit(" \t"));
+        }
+
+        #[test]
+        fn crlf() {
+            let editor = factory::inserter_crlf("", 1).trim_trailing_whitespace(true);
+            assert_eq!("foo\r\nbar\r\n\r\n\r\nbaz\r\nqux", editor.edit(INPUT));
+        }
+
+        #[test]
+        fn lf_keeps_spaces_before_cr() {
+            let editor = factory::inserter("", 1).trim_trailing_whitespace(true);
+            assert_eq!("foo \r\nbar", editor.edit("foo \r\nbar "));
+
+            let editor = editor.strip_cr(true);
+            assert_eq!("foo\nbar", editor.edit("foo \r\nbar "));
+        }
+
+        #[test]
+        fn whitespace_lines_are_blank() {
+            let editor = factory::replacer("-", 3).trim_trailing_whitespace(true);
+            assert_eq!("foo\nbar-baz  \r\nqux", editor.edit(INPUT));
+        }
+
+        #[test]
+        fn before_replacement() {
+            let editor = factory::replacer(" ", 1).trim_trailing_whitespace(true);
+            assert_eq!("foo bar baz", editor.edit("foo  \nbar\t\nbaz "));
+        }
+
+        #[test]
+        fn every_method_agrees() {
+            let editors = [
+                factory::inserter("", 1),
+                factory::replacer_crlf("-", 2),
+                factory::appender("+", 2).ensure_trailing_newline(true),
+                factory::wrap("(", ")", 1).skip_empty_last_segment(true),
+                factory::replacer_at_least("", 2).treat_whitespace_lines_as_blank(true),
+                factory::replacer_with(" ", 1, NewlineType::LineSeparator),
+            ];
+            let inputs = [INPUT, "a \u{2028} \u{2028}b ", " ", "\n \n", "a\n\n\t"];
+
+            for editor in &editors {
+                let editor = editor.clone().trim_trailing_whitespace(true);
+                for input in &inputs {
+                    assert_same_as_edit(&editor, input);
+                }
+            }
+        }
+
+        #[test]
+        fn analyze() {
+            let editor = factory::replacer("", 1).trim_trailing_whitespace(true);
+            let stats = editor.analyze("a \n\t\n\nb\n");
+            assert_eq!(vec![(1, 1), (3, 1)], stats.runs().collect::<Vec<_>>());
+        }
+    }
+
+    mod strip_leading_whitespace {
+        use super::*;
+
+        const BLOCK: &str =
+            "    fn main() {\n        if x {\n\t  \n            y();\n        }\n    }\n";
+
+        #[test]
+        fn kept_by_default() {
+            let editor = factory::inserter("", 1);
+            assert_eq!(BLOCK, editor.edit(BLOCK));
+        }
+
+        #[test]
+        fn block() {
+            let editor = factory::inserter("", 1).strip_leading_whitespace(true);
+            assert_eq!("fn main() {\nif x {\n\ny();\n}\n}\n", editor.edit(BLOCK));
+        }
+
+        #[test]
+        fn whitespace_lines_are_blank() {
+            let editor = factory::replacer("\n", 2).strip_leading_whitespace(true);
+            assert_eq!("fn main() {\nif x {\ny();\n}\n}\n", editor.edit(BLOCK));
+        }
+
+        #[test]
+        fn first_and_last_lines() {
+            let editor = factory::replacer("-", 1).strip_leading_whitespace(true);
+            assert_eq!("a-b ", editor.edit(" \ta\n  b "));
+            assert_eq!("a-", editor.edit("a\n \t"));
+        }
+
+        #[test]
+        fn crlf_and_lone_cr() {
+            let editor = factory::inserter_crlf("", 1).strip_leading_whitespace(true);
+            assert_eq!("a\r b\r\nc\r\n", editor.edit(" a\r b\r\n  c\n"));
+        }
+
+        #[test]
+        fn with_trailing() {
+            let editor = factory::inserter("", 1)
+                .strip_leading_whitespace(true)
+                .trim_trailing_whitespace(true);
+            assert_eq!("a b\nc\n", editor.edit("\t a b \n c\t\n"));
+        }
+
+        #[test]
+        fn every_method_agrees() {
+            let editors = [
+                factory::inserter("", 1),
+                factory::replacer("\n", 2),
+                factory::wrap_crlf("<", ">", 1).skip_empty_last_segment(true),
+                factory::appender("+", 1).ensure_trailing_newline(true),
+            ];
+            let inputs = [BLOCK, " a\r\n \r\n\r\n  ", "\t", "a\n\n  "];
+
+            for editor in &editors {
+                let editor = editor.clone().strip_leading_whitespace(true);
+                for input in &inputs {
+                    assert_same_as_edit(&editor, input);
+                }
+            }
+        }
+    }
+
+    mod trim_leading_newlines {
+        use super::*;
+
+        fn trimming(editor: Editor) -> Editor {
+            editor.trim_leading_newlines(true)
+        }
+
+        #[test]
+        fn kept_by_default() {
+            let editor = factory::replacer("-", 2);
+            assert_eq!("-\nfoo", editor.edit("\n\n\nfoo"));
+        }
+
+        #[test]
+        fn one_newline() {
+            let input = "\nfoo\nbar\n\nbaz";
+            assert_eq!(
+                "foo-bar--baz",
+                trimming(factory::replacer("-", 1)).edit(input)
+            );
+            assert_eq!(
+                "foo\nbar-baz",
+                trimming(factory::replacer("-", 2)).edit(input)
+            );
+        }
+
+        #[test]
+        fn two_newlines() {
+            let input = "\n\nfoo\nbar\n\nbaz";
+            assert_eq!(
+                "foo-bar--baz",
+                trimming(factory::replacer("-", 1)).edit(input)
+            );
+            assert_eq!(
+                "foo\nbar-baz",
+                trimming(factory::replacer("-", 2)).edit(input)
+            );
+        }
+
+        #[test]
+        fn three_newlines() {
+            let input = "\n\n\nfoo\n\n\nbar";
+            let editor = trimming(factory::replacer("-", 3));
+            assert_eq!("foo-bar", editor.edit(input));
+            let editor = trimming(factory::replacer_at_least("-", 2));
+            assert_eq!("foo-bar", editor.edit(input));
+        }
+
+        #[test]
+        fn only_newlines() {
+            let editor = trimming(factory::appender("-", 1)).ensure_trailing_newline(true);
+            assert_eq!("", editor.edit("\n\n\n"));
+            assert_eq!("", editor.edit(""));
+
+            let editor = trimming(factory::wrap("<", ">", 2));
+            assert_eq!("<>", editor.edit("\n\n"));
+        }
+
+        #[test]
+        fn crlf_and_blank_lines() {
+            let editor = trimming(factory::replacer_crlf("-", 1));
+            assert_eq!("foo-", editor.edit("\r\n\n\r\nfoo\r\n"));
+            // a lone `\r` is text
+            assert_eq!("\r-foo", editor.edit("\r\r\nfoo"));
+
+            let editor = trimming(factory::replacer("-", 1)).treat_whitespace_lines_as_blank(true);
+            assert_eq!("foo--bar", editor.edit(" \n\t\nfoo\n  \nbar"));
+        }
+
+        #[test]
+        fn preview_skips_leading_run() {
+            let input = "\n\nfoo\n\nbar";
+            let spans = trimming(factory::replacer("-", 2)).preview(input);
+            assert_eq!(1, spans.len());
+            assert_eq!(5, spans[0].byte_offset);
+        }
+
+        #[test]
+        fn every_method_agrees() {
+            let editors = [
+                factory::replacer("-", 1),
+                factory::replacer_crlf("\n", 2).ensure_trailing_newline(true),
+                factory::wrap("<", ">", 1).skip_empty_last_segment(true),
+                factory::replacer_at_most("+", 2).strip_leading_whitespace(true),
+            ];
+            let inputs = ["\nfoo\n\nbar\n", "\r\n\r\n\n a\r\n", "\n\n\n", " \n\tb\n\n"];
+
+            for editor in &editors {
+                let editor = trimming(editor.clone());
+                for input in &inputs {
+                    assert_same_as_edit(&editor, input);
+                }
+            }
+        }
+    }
+
+    mod trim_trailing_newlines {
+        use super::*;
+
+        fn trimming(editor: Editor) -> Editor {
+            editor.trim_trailing_newlines(true)
+        }
+
+        #[test]
+        fn kept_by_default() {
+            let editor = factory::replacer("-", 2);
+            assert_eq!("foo-\n", editor.edit("foo\n\n\n"));
+        }
+
+        #[test]
+        fn no_newlines() {
+            let editor = trimming(factory::replacer("-", 1));
+            assert_eq!("foo-bar", editor.edit("foo\nbar"));
+            assert_eq!("", editor.edit(""));
+        }
+
+        #[test]
+        fn one_newline() {
+            let input = "foo\n\nbar\n";
+            assert_eq!("foo--bar", trimming(factory::replacer("-", 1)).edit(input));
+            assert_eq!("foo-bar", trimming(factory::replacer("-", 2)).edit(input));
+        }
+
+        #[test]
+        fn three_newlines() {
+            let input = "foo\n\n\nbar\n\n\n";
+            assert_eq!("foo-bar", trimming(factory::replacer("-", 3)).edit(input));
+            let expected = "foo\n-\n-\n-bar";
+            assert_eq!(expected, trimming(factory::appender("-", 1)).edit(input));
+            let editor = trimming(factory::replacer_at_least("-", 2));
+            assert_eq!("foo-bar", editor.edit(input));
+        }
+
+        #[test]
+        fn only_newlines() {
+            let editor = trimming(factory::appender("-", 1));
+            assert_eq!("", editor.edit("\n\n\n"));
+
+            let editor = trimming(factory::wrap("<", ">", 2));
+            assert_eq!("<foo>", editor.edit("foo\n\n"));
+        }
+
+        #[test]
+        fn with_trailing_newline() {
+            let editor = trimming(factory::inserter(";", 1)).ensure_trailing_newline(true);
+            assert_eq!("foo;\nbar\n", editor.edit("foo\nbar\n\n\n"));
+            assert_eq!("foo;\nbar\n", editor.edit("foo\nbar"));
+        }
+
+        #[test]
+        fn crlf_and_blank_lines() {
+            let editor = trimming(factory::replacer_crlf("-", 1));
+            assert_eq!("foo", editor.edit("foo\r\n\n\r\n"));
+            // a lone `\r` is text
+            assert_eq!("foo-\r", editor.edit("foo\r\n\r"));
+
+            let editor = trimming(factory::replacer("-", 1)).treat_whitespace_lines_as_blank(true);
+            assert_eq!("foo--bar", editor.edit("foo\n \nbar\n\t\n  \n"));
+            // without a newline after it, the last line is text
+            assert_eq!("foo--  ", editor.edit("foo\n\t\n  "));
+        }
+
+        #[test]
+        fn preview_skips_trailing_run() {
+            let input = "foo\n\nbar\n\n";
+            let spans = trimming(factory::replacer("-", 2)).preview(input);
+            assert_eq!(1, spans.len());
+            assert_eq!(3, spans[0].byte_offset);
+        }
+
+        #[test]
+        fn every_method_agrees() {
+            let editors = [
+                factory::replacer("-", 1),
+                factory::replacer_crlf("\n", 2).ensure_trailing_newline(true),
+                factory::line_prefixer("> ").skip_empty_last_segment(true),
+                factory::replacer_at_most("+", 2).trim_leading_newlines(true),
+            ];
+            let inputs = [
+                "\nfoo\n\nbar\n",
+                "a\r\n\r\n\n a\r\n\r\n",
+                "\n\n\n",
+                "b\n\n \n\t",
+            ];
+
+            for editor in &editors {
+                let editor = trimming(editor.clone());
+                for input in &inputs {
+                    assert_same_as_edit(&editor, input);
+                }
+            }
+        }
+    }
+
+    mod append_at_eof {
+        use super::*;
+
+        #[test]
+        fn off_by_default() {
+            let editor = factory::appender(";", 1);
+            assert_eq!("foo\n;bar", editor.edit("foo\nbar"));
+        }
+
+        #[test]
+        fn no_trailing_newline() {
+            let editor = factory::appender(";", 1).append_at_eof(true);
+            assert_eq!("foo\n;bar;", editor.edit("foo\nbar"));
+            assert_eq!("foo;", editor.edit("foo"));
+        }
+
+        #[test]
+        fn trailing_newline_unchanged() {
+            let editor = factory::appender(";", 1).append_at_eof(true);
+            assert_eq!("foo\n;bar\n;", editor.edit("foo\nbar\n"));
+            assert_eq!("", editor.edit(""));
+        }
+
+        #[test]
+        fn not_counted_as_edit() {
+            let editor = factory::appender_crlf("-", 2)
+                .max_edits(Some(0))
+                .append_at_eof(true);
+            assert_eq!(
+                ("foo\r\n\r\nbar-".to_string(), 0),
+                editor.edit_counted("foo\r\n\r\nbar")
+            );
+        }
+
+        #[test]
+        fn only_appenders() {
+            let input = "foo\nbar";
+            assert_eq!(
+                "foo;\nbar",
+                factory::inserter(";", 1).append_at_eof(true).edit(input)
+            );
+            assert_eq!(
+                "foo;bar",
+                factory::replacer(";", 1).append_at_eof(true).edit(input)
+            );
+        }
+
+        #[test]
+        fn before_close() {
+            let editor = factory::appender(";", 1).wrap("<", ">").append_at_eof(true);
+            assert_eq!("<foo\n;bar;>", editor.edit("foo\nbar"));
+        }
+
+        #[test]
+        fn with_trimmed_newlines() {
+            let editor = factory::appender(";", 1)
+                .trim_trailing_newlines(true)
+                .append_at_eof(true);
+            assert_eq!("foo\n;bar;", editor.edit("foo\nbar\n\n"));
+        }
+
+        #[test]
+        fn every_method_agrees() {
+            let editors = [
+                factory::appender(";", 1),
+                factory::appender_crlf("\r\n--", 2).ensure_trailing_newline(true),
+                factory::appender(";", 1).trim_trailing_whitespace(true),
+                factory::appender("+", 1).trim_trailing_newlines(true),
+            ];
+            let inputs = ["foo\nbar", "a\r\n\r\nb\r\n", "b\n\n \t", "", "\n\n"];
+
+            for editor in &editors {
+                let editor = editor.clone().append_at_eof(true);
+                for input in &inputs {
+                    assert_same_as_edit(&editor, input);
+                }
+            }
+        }
+    }
+
+    mod line_options {
+        use super::*;
+
+        #[test]
+        fn default_is_default() {
+            assert!(LineOptions::default().is_default());
+            assert!(Editor::default().lines.is_default());
+            assert!(!factory::replacer("-", 1)
+                .trim_leading_newlines(true)
+                .lines
+                .is_default());
+        }
+
+        #[test]
+        fn append_eof_only_affects_appenders() {
+            let options = factory::replacer("-", 1).append_at_eof(true).lines;
+            assert!(!options.is_default());
+            assert!(options.affects_scan(true));
+            assert!(!options.affects_scan(false));
+
+            let editor = factory::appender(";", 0).append_at_eof(true);
+            assert!(!editor.is_noop());
+            assert_eq!("foo;", editor.edit("foo"));
+            assert_eq!(None, editor.inverse());
+            assert!(factory::appender("", 0).append_at_eof(true).is_noop());
+            assert!(factory::inserter(";", 0).append_at_eof(true).is_noop());
+        }
+
+        #[test]
+        fn trims_affect_any_scan() {
+            let editors = [
+                factory::replacer("-", 1).trim_trailing_whitespace(true),
+                factory::replacer("-", 1).strip_leading_whitespace(true),
+                factory::replacer("-", 1).trim_leading_newlines(true),
+                factory::replacer("-", 1).trim_trailing_newlines(true),
+            ];
+
+            for editor in &editors {
+                assert!(editor.lines.affects_scan(false), "{}", editor);
+                assert!(editor.lines_affect_scan(), "{}", editor);
+            }
+        }
+
+        #[test]
+        fn ordered_by_field() {
+            let trim_trailing = factory::replacer("-", 1).trim_trailing_whitespace(true);
+            let append_eof = factory::replacer("-", 1).append_at_eof(true);
+            assert!(append_eof < trim_trailing);
+            assert!(factory::replacer("-", 1) < append_eof);
+        }
+
+        #[test]
+        fn not_noop() {
+            let inserter = factory::inserter("", 1);
+            let editors = [
+                (
+                    inserter.clone().trim_trailing_whitespace(true),
+                    ", trimming trailing whitespace",
+                ),
+                (
+                    inserter.clone().strip_leading_whitespace(true),
+                    ", stripping leading whitespace",
+                ),
+                (
+                    inserter.clone().trim_leading_newlines(true),
+                    ", trimming leading newlines",
+                ),
+                (
+                    inserter.clone().trim_trailing_newlines(true),
+                    ", trimming trailing newlines",
+                ),
+            ];
+
+            for &(ref editor, description) in &editors {
+                assert!(!editor.is_noop(), "{}", editor);
+                assert_eq!(None, editor.inverse(), "{}", editor);
+                assert_ne!(&inserter, editor);
+                assert!(editor.to_string().ends_with(description), "{}", editor);
+            }
+        }
+
+        #[test]
+        fn display() {
+            let editor = factory::appender(";", 1)
+                .strip_leading_whitespace(true)
+                .append_at_eof(true);
+            assert_eq!(
+                "append \";\" after 1 LF newline(s), stripping leading whitespace, appending at EOF",
+                editor.to_string()
+            );
+        }
+    }
+
+    mod edit_dedented {
+        use super::*;
+
+        const BLOCK: &str =
+            "    fn main() {\n        if x {\n\t  \n            y();\n        }\n    }\n";
+
+        #[test]
+        fn block() {
+            let editor = factory::inserter("", 1);
+            assert_eq!(
+                "fn main() {\n    if x {\n\n        y();\n    }\n}\n",
+                editor.edit_dedented(BLOCK)
+            );
+        }
+
+        #[test]
+        fn differs_from_strip() {
+            let editor = factory::replacer("\n", 2);
+            assert_eq!(
+                "fn main() {\n    if x {\n        y();\n    }\n}\n",
+                editor.edit_dedented(BLOCK)
+            );
+            assert_eq!(
+                "fn main() {\nif x {\ny();\n}\n}\n",
+                editor.strip_leading_whitespace(true).edit(BLOCK)
+            );
+        }
+
+        #[test]
+        fn unindented_line_keeps_everything() {
+            let editor = factory::inserter("", 1);
+            let input = "  a\nb\n    c";
+            assert_eq!(input, editor.edit_dedented(input));
+        }
+
+        #[test]
+        fn tabs_and_spaces_differ() {
+            let editor = factory::inserter("", 1);
+            assert_eq!("a\n b", editor.edit_dedented("\t a\n\t  b"));
+            assert_eq!("\ta\n b", editor.edit_dedented(" \ta\n  b"));
+        }
+
+        #[test]
+        fn crlf() {
+            let editor = factory::replacer_crlf(" ", 1);
+            assert_eq!("a    b \r", editor.edit_dedented("  a\r\n     b\r\n  \r"));
+        }
+
+        #[test]
+        fn auto() {
+            let editor = factory::replacer_with("-", 2, NewlineType::Auto);
+            assert_eq!("a- b\r\n", editor.edit_dedented(" a\r\n\r\n  b\r\n"));
+        }
+
+        #[test]
+        fn no_lines() {
+            let editor = factory::inserter("", 1);
+            assert_eq!("", editor.edit_dedented(""));
+            assert_eq!("\n", editor.edit_dedented("  \n"));
+        }
+    }
+
+    mod strip_cr {
+        use super::*;
+        use std::io::Cursor;
+
+        const INPUT: &str = "foo\r\nbar\r\n\r\nbaz\rqux\r\n";
+
+        #[test]
+        fn lf_keeps_cr_by_default() {
+            let editor = factory::inserter(";", 2);
+            assert_eq!("foo\r\nbar\r\n\r\nbaz\rqux\r\n", editor.edit(INPUT));
+        }
+
+        #[test]
+        fn edit() {
+            let editor = factory::inserter(";", 2).strip_cr(true);
+            assert_eq!("foo\nbar;\n\nbaz\rqux\n", editor.edit(INPUT));
+        }
+
+        #[test]
+        fn edit_buffered() {
+            let editor = factory::inserter(";", 2).strip_cr(true);
+            let mut input = Cursor::new(INPUT);
+            let mut output = Vec::new();
+
+            editor.edit_buffered(&mut input, &mut output).unwrap();
+
+            assert_eq!(editor.edit(INPUT).as_bytes(), &output[..]);
+        }
+
+        #[test]
+        fn edit_in_place() {
+            let editor = factory::replacer("\n", 2).strip_cr(true);
+            let mut text = INPUT.to_string();
+
+            editor.edit_in_place(&mut text);
+
+            assert_eq!("foo\nbar\nbaz\rqux\n", text);
+        }
+
+        #[test]
+        fn trailing_cr_kept() {
+            let editor = factory::replacer("-", 1).strip_cr(true);
+            assert_eq!("a-b\r", editor.edit("a\r\nb\r"));
+        }
+
+        #[test]
+        fn other_newline_types_unaffected() {
+            let editor = factory::replacer_crlf("-", 1);
+            assert_eq!(
+                editor.edit(INPUT),
+                editor.clone().strip_cr(true).edit(INPUT)
+            );
+        }
+
+        #[test]
+        fn not_noop() {
+            assert!(!factory::inserter("", 1).strip_cr(true).is_noop());
+        }
+
+        #[test]
+        fn no_inverse() {
+            assert_eq!(None, factory::appender("\n", 1).strip_cr(true).inverse());
+        }
+    }
+
+    mod reversed_crlf {
+        use super::*;
+
+        const INPUT: &str = "foo\n\r\n\rbar\n\rbaz\r\n\r\nqux\n\r\n";
+
+        #[test]
+        fn lf_keeps_cr_by_default() {
+            let editor = factory::replacer("-", 1);
+            assert_eq!("a-\rb", editor.edit("a\n\rb"));
+        }
+
+        #[test]
+        fn lf() {
+            let editor = factory::replacer("-", 1).reversed_crlf(true);
+            assert_eq!("a-b", editor.edit("a\n\rb"));
+            assert_eq!("a-b-c-", editor.edit("a\n\rb\n\rc\n\r"));
+        }
+
+        #[test]
+        fn crlf() {
+            let editor = factory::replacer_crlf("-", 1).reversed_crlf(true);
+            assert_eq!("a-b", editor.edit("a\n\rb"));
+        }
+
+        #[test]
+        fn written_as_line_ending() {
+            let editor = factory::inserter("", 1).reversed_crlf(true);
+            assert_eq!("a\nb\n\nc\n", editor.edit("a\n\rb\n\r\n\rc\n\r"));
+
+            let editor = factory::inserter_crlf("", 1).reversed_crlf(true);
+            assert_eq!("a\r\nb\r\n\r\nc\r\n", editor.edit("a\n\rb\n\r\n\rc\n\r"));
+        }
+
+        #[test]
+        fn runs() {
+            let editor = factory::replacer("-", 3).reversed_crlf(true);
+            assert_eq!("a-b\nc", editor.edit("a\n\r\n\r\n\rb\n\rc"));
+
+            let editor = factory::replacer_crlf("-", 2).reversed_crlf(true);
+            assert_eq!("foo-bar\r\nbaz-qux-", editor.edit(INPUT));
+        }
+
+        #[test]
+        fn crlf_pairs_first() {
+            let editor = factory::replacer_crlf("-", 2).reversed_crlf(true);
+            assert_eq!("a-b", editor.edit("a\r\n\r\nb"));
+            assert_eq!("a-\rb", editor.edit("a\r\n\r\n\rb"));
+
+            let editor = factory::replacer("-", 2);
+            assert_eq!(
+                editor.edit("a\r\n\r\nb"),
+                editor.clone().reversed_crlf(true).edit("a\r\n\r\nb")
+            );
+        }
+
+        #[test]
+        fn lf_after_pair_is_bare() {
+            let editor = factory::replacer("-", 2).reversed_crlf(true);
+            assert_eq!("a-b", editor.edit("a\n\r\nb"));
+            assert_eq!("a-b-", editor.edit("a\n\r\n\rb\n\r\n\r"));
+        }
+
+        #[test]
+        fn auto() {
+            let editor = factory::replacer_with("-", 1, NewlineType::Auto).reversed_crlf(true);
+            assert_eq!("a-b-c", editor.edit("a\n\rb\nc"));
+        }
+
+        #[test]
+        fn separators_unaffected() {
+            let editor = factory::replacer_with("-", 1, NewlineType::LineSeparator);
+            let input = "a\n\rb\u{2028}\n\r";
+            assert_eq!(
+                editor.edit(input),
+                editor.clone().reversed_crlf(true).edit(input)
+            );
+
+            let editor = factory::inserter_with("", 1, NewlineType::LineSeparator);
+            assert!(editor.reversed_crlf(true).is_noop());
+        }
+
+        #[test]
+        fn every_method_agrees() {
+            let editors = [
+                factory::replacer("-", 1),
+                factory::replacer_crlf("-", 2),
+                factory::appender("+", 2).ensure_trailing_newline(true),
+                factory::wrap_crlf("(", ")", 1),
+                factory::replacer_at_least("", 2).strip_cr(true),
+                factory::inserter("", 1),
+            ];
+            let inputs = [INPUT, "a\n\rb", "\r\n\r", "\n\r\r\n\r\n\r", "a\n\r"];
+
+            for editor in &editors {
+                let editor = editor.clone().reversed_crlf(true);
+                for input in &inputs {
+                    assert_same_as_edit(&editor, input);
+                }
+            }
+        }
+
+        #[test]
+        fn analyze() {
+            let editor = factory::replacer("", 1).reversed_crlf(true);
+            let stats = editor.analyze("a\n\r\n\rb\n\r");
+            assert_eq!(vec![(1, 1), (2, 1)], stats.runs().collect::<Vec<_>>());
+            assert!(stats.ends_with_newline());
+
+            let stats = editor.analyze("a\r\n\r");
+            assert_eq!(vec![(1, 1)], stats.runs().collect::<Vec<_>>());
+            assert!(!stats.ends_with_newline());
+        }
+
+        #[test]
+        fn preview() {
+            let editor = factory::replacer("-", 2).reversed_crlf(true);
+            let spans = editor.preview("a\n\r\n\rb");
+            assert_eq!(1, spans.len());
+            assert_eq!(1, spans[0].byte_offset);
+            assert_eq!(4, spans[0].matched_len);
+        }
+
+        #[test]
+        fn not_noop() {
+            assert!(!factory::inserter("", 1).reversed_crlf(true).is_noop());
+            assert!(!factory::replacer("", 1).reversed_crlf(true).is_idempotent());
+            assert_eq!(
+                None,
+                factory::appender("\n", 1).reversed_crlf(true).inverse()
+            );
+        }
+
+        #[test]
+        fn display() {
+            let editor = factory::replacer("-", 1).reversed_crlf(true);
+            assert!(editor.to_string().ends_with(", reading reversed CRLF"));
+            assert!(!editor.behavior_eq(&factory::replacer("-", 1)));
+        }
+    }
+
+    mod from_str {
+        use super::*;
+
+        #[test]
+        fn append() {
+            assert_eq!(Ok(factory::appender("---", 2)), "append:2:---".parse());
+        }
+
+        #[test]
+        fn insert() {
+            assert_eq!(Ok(factory::inserter(";", 1)), "insert:1:;".parse());
+        }
+
+        #[test]
+        fn replace() {
+            assert_eq!(Ok(factory::replacer("\n\n", 1)), "replace:1:\n\n".parse());
+        }
+
+        #[test]
+        fn crlf_suffix() {
+            assert_eq!(
+                Ok(factory::appender_crlf("-", 2)),
+                "append:2:-:crlf".parse()
+            );
+        }
+
+        #[test]
+        fn empty_text() {
+            assert_eq!(Ok(factory::replacer("", 3)), "replace:3:".parse());
+            assert_eq!(Ok(factory::replacer_crlf("", 3)), "replace:3::crlf".parse());
+        }
+
+        #[test]
+        fn text_with_colons() {
+            assert_eq!(Ok(factory::inserter("a:b", 1)), "insert:1:a:b".parse());
+        }
+
+        #[test]
+        fn text_ending_in_crlf_suffix() {
+            // the suffix is taken as the line ending, not part of the text
+            assert_eq!(
+                Ok(factory::replacer_crlf("a", 1)),
+                "replace:1:a:crlf".parse()
+            );
+            assert_eq!(
+                Ok(factory::replacer_crlf("a:crlf", 1)),
+                "replace:1:a:crlf:crlf".parse()
+            );
+        }
+
+        #[test]
+        fn missing_fields() {
+            assert_eq!(Err(ParseEditorError::InvalidFormat), "".parse::<Editor>());
+            assert_eq!(
+                Err(ParseEditorError::InvalidFormat),
+                "append".parse::<Editor>()
+            );
+            assert_eq!(
+                Err(ParseEditorError::InvalidFormat),
+                "append:2".parse::<Editor>()
+            );
+        }
+
+        #[test]
+        fn invalid_edit_type() {
+            let expected = ParseEditorError::InvalidEditType("Append".to_string());
+            assert_eq!(Err(expected), "Append:2:-".parse::<Editor>());
+        }
+
+        #[test]
+        fn trigger_above_255() {
+            let editor: Editor = "replace:256:-".parse().unwrap();
+            assert_eq!(factory::replacer("-", 256), editor);
+        }
+
+        #[test]
+        fn invalid_trigger() {
+            let too_large = "append:99999999999999999999999:-";
+            for input in &["append::-", "append:two:-", "append:-1:-", too_large] {
+                match input.parse::<Editor>() {
+                    Err(ParseEditorError::InvalidTrigger(_)) => {}
+                    res => panic!("unexpected result for {:?}: {:?}", input, res),
+                }
+            }
+        }
+    }
+
+    mod wrap {
+        use super::*;
+        use crate::factory;
+
+        #[test]
+        fn empty_input_writes_nothing() {
+            assert_edits("", 0, &factory::wrap("<", ">", 1), "");
+        }
+
+        #[test]
+        fn single_segment() {
+            assert_edits("<foo>", 0, &factory::wrap("<", ">", 1), "foo");
+        }
+
+        #[test]
+        fn newlines_only() {
+            assert_edits("<>\n<>", 1, &factory::wrap("<", ">", 1), "\n");
+        }
+
+        #[test]
+        fn trailing_newlines_inside_close() {
+            let editor = factory::replacer("-", 2).wrap("[", "]");
+            assert_edits("[foo-bar\n]", 1, &editor, "foo\n\nbar\n");
+        }
+
+        #[test]
+        fn trailing_newline_after_close() {
+            let editor = factory::wrap("<p>", "</p>", 2).ensure_trailing_newline(true);
+            assert_edits("<p>foo</p>\n\n<p>bar\n</p>\n", 1, &editor, "foo\n\nbar\n");
+        }
+
+        #[test]
+        fn not_noop_or_invertible() {
+            let editor = Editor::default().wrap("<", ">");
+            assert!(!editor.is_noop());
+            assert_eq!(None, editor.inverse());
+            assert_edits("<foo\n>", 0, &editor, "foo\n");
+        }
+
+        #[test]
+        fn skip_empty_last_segment() {
+            let editor = factory::wrap("<", ">", 1).skip_empty_last_segment(true);
+            assert_edits("<>\n", 1, &editor, "\n");
+            assert_edits("<foo>\n<>\n", 2, &editor, "foo\n\n");
+            assert_edits("<foo>\n<bar>", 1, &editor, "foo\nbar");
+        }
+
+        #[test]
+        fn replacement_without_open_not_skipped() {
+            let editor = factory::replacer("=", 1)
+                .wrap("{", "}")
+                .skip_empty_last_segment(true);
+            assert_edits("{a=}", 1, &editor, "a\n");
+            assert_edits("{a=b=}", 2, &editor, "a\nb\n");
+
+            let editor = factory::appender("+", 1)
+                .wrap("{", "}")
+                .skip_empty_last_segment(true);
+            assert_edits("{\n+}", 1, &editor, "\n");
+        }
+
+        #[test]
+        fn text_ending_with_open_kept() {
+            let editor = factory::replacer("=", 1)
+                .wrap("{", "}")
+                .skip_empty_last_segment(true);
+            assert_edits("{a{=}", 1, &editor, "a{\n");
+        }
+
+        #[test]
+        fn unedited_last_segment_not_skipped() {
+            let editor = factory::wrap("<p>", "</p>", 2)
+                .max_edits(Some(1))
+                .skip_empty_last_segment(true);
+            assert_edits("<p>a</p>\n\n<p>b\n\n</p>", 1, &editor, "a\n\nb\n\n");
+        }
+    }
+
+    mod split {
+        use super::*;
+        use crate::factory;
+
+        #[test]
+        fn empty_input() {
+            assert_eq!(vec![""], factory::replacer("", 1).split(""));
+        }
+
+        #[test]
+        fn zero_newlines_single_segment() {
+            let input = "foo\n\nbar";
+            assert_eq!(vec![input], Editor::default().split(input));
+        }
+
+        #[test]
+        fn paragraphs() {
+            let editor = factory::replacer("", 2);
+            let actual = editor.split("foo\nbar\n\nbaz\n\nqux");
+            assert_eq!(vec!["foo\nbar", "baz", "qux"], actual);
+        }
+
+        #[test]
+        fn leading_and_trailing_separators() {
+            let editor = factory::replacer("", 2);
+            assert_eq!(vec!["", "foo", ""], editor.split("\n\nfoo\n\n"));
+        }
+
+        #[test]
+        fn runs_longer_than_trigger() {
+            let editor = factory::replacer("", 2);
+            let actual = editor.split("foo\n\n\nbar\n\n\n\nbaz");
+            assert_eq!(vec!["foo", "\nbar", "", "baz"], actual);
+        }
+
+        #[test]
+        fn replace_text_ignored() {
+            let editor = factory::appender("<hr>", 1);
+            assert_eq!(vec!["foo", "bar"], editor.split("foo\nbar"));
+        }
+
+        #[test]
+        fn crlf() {
+            let editor = factory::inserter_crlf("-", 2);
+            let actual = editor.split("foo\r\nbar\r\n\r\nbaz");
+            assert_eq!(vec!["foo\r\nbar", "baz"], actual);
+        }
+
+        #[test]
+        fn line_prefixer() {
+            let editor = factory::line_prefixer("> ");
+            assert_eq!(vec!["> ", ""], editor.split("\n"));
+            assert_eq!(vec!["> ", "", ""], editor.split("\n\n"));
+            assert_eq!(vec!["> a", "", "b", ""], editor.split("a\n\nb\n"));
+        }
+
+        #[test]
+        fn skip_empty_last_segment() {
+            let editor = factory::appender("+", 1)
+                .wrap("{", "}")
+                .skip_empty_last_segment(true);
+            assert_eq!(vec!["{", "}"], editor.split("\n"));
+
+            let editor = factory::wrap("<", ">", 1).skip_empty_last_segment(true);
+            assert_eq!(vec!["<foo", ">"], editor.split("foo\n"));
+        }
+    }
+
+    mod edit_fmt {
+        use super::*;
+        use crate::factory;
+        use std::fmt::Write as _;
+
+        const INPUT: &str = "foo\n\nbar\r\n\r\nbaz\n\n\nqux\u{2028}\u{2028}\n\n";
+
+        /// Records each call to `write_str`.
+        #[derive(Default)]
+        struct Chunks(Vec<String>);
+
+        impl fmt::Write for Chunks {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0.push(s.to_string());
+                Ok(())
+            }
+        }
+
+        /// Fails every write.
+        struct Failing;
+
+        impl fmt::Write for Failing {
+            fn write_str(&mut self, _: &str) -> fmt::Result {
+                Err(fmt::Error)
+            }
+        }
+
+        fn assert_same_as_edit(editor: &Editor) {
+            let mut output = String::new();
+            editor.edit_fmt(INPUT, &mut output).unwrap();
+            assert_eq!(editor.edit(INPUT), output, "\neditor: {}\n", editor);
+        }
+
+        #[test]
+        fn into_string() {
+            assert_same_as_edit(&factory::appender("---", 2));
+            assert_same_as_edit(&factory::inserter_crlf(";", 1));
+            assert_same_as_edit(&factory::replacer_with(" ", 2, NewlineType::LineSeparator));
+            assert_same_as_edit(&factory::wrap("(", ")", 2).ensure_trailing_newline(true));
+            assert_same_as_edit(&factory::replacer("-", 1).max_edits(Some(2)).skip_edits(1));
+            assert_same_as_edit(&factory::replacer("-", 2).strip_cr(true));
+            assert_same_as_edit(&Editor::default());
+        }
+
+        #[test]
+        fn appends_to_string() {
+            let mut output = String::from("> ");
+            factory::replacer(" ", 1)
+                .edit_fmt("foo\nbar", &mut output)
+                .unwrap();
+            assert_eq!("> foo bar", output);
+        }
+
+        #[test]
+        fn custom_writer() {
+            let editor = factory::replacer("-", 1);
+            let mut output = Chunks::default();
+
+            editor.edit_fmt("foo\nbar\nbaz", &mut output).unwrap();
+
+            assert_eq!("foo-bar-baz", output.0.concat());
+            assert_eq!(1, output.0.len());
+        }
+
+        #[test]
+        fn large_input_written_in_chunks() {
+            let editor = factory::replacer("-", 1);
+            let input = "foo\n".repeat(BUFSIZE);
+            let mut output = Chunks::default();
+
+            editor.edit_fmt(&input, &mut output).unwrap();
+
+            assert_eq!(editor.edit(&input), output.0.concat());
+            assert!(output.0.len() > 1);
+        }
+
+        #[test]
+        fn formatter() {
+            let editor = factory::appender("-", 1);
+            let mut output = String::new();
+
+            write!(output, "[{}]", Display(&editor, "foo\nbar")).unwrap();
+
+            assert_eq!("[foo\n-bar]", output);
+        }
+
+        #[test]
+        fn write_error() {
+            let editor = factory::replacer("-", 1);
+            assert_eq!(Err(fmt::Error), editor.edit_fmt("foo\nbar", &mut Failing));
+            assert_eq!(
+                Err(fmt::Error),
+                Editor::default().edit_fmt("foo", &mut Failing)
+            );
+        }
+
+        struct Display<'a>(&'a Editor, &'a str);
+
+        impl fmt::Display for Display<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.0.edit_fmt(self.1, f)
+            }
+        }
+    }
+
+    mod cow {
+        use super::*;
+        use crate::factory;
+
+        #[test]
+        fn no_newlines_borrowed() {
+            assert_borrowed(&factory::replacer("-", 1), "foo bar");
+        }
+
+        #[test]
+        fn runs_shorter_than_trigger_borrowed() {
+            assert_borrowed(&factory::appender("-", 3), "foo\n\nbar\nbaz\n\n");
+            assert_borrowed(&factory::inserter_crlf("-", 2), "foo\r\nbar\r\nbaz\r\n");
+            assert_borrowed(
+                &Editor::new("-".to_string(), 2, NewlineType::ParagraphSeparator),
+                "foo\u{2029}bar\u{2029}",
+            );
+        }
+
+        #[test]
+        fn noop_borrowed() {
+            assert_borrowed(&Editor::default(), "foo\n\n\nbar");
+            assert_borrowed(&factory::appender("", 1), "foo\n\n\nbar");
+        }
+
+        #[test]
+        fn replacement_same_as_input_borrowed() {
+            assert_borrowed(&factory::replacer_crlf("\r\n", 1), "foo\r\n\r\nbar");
+
+            let editor = factory::replacer("-", 2).ensure_trailing_newline(true);
+            assert_borrowed(&editor, "foo\nbar\n");
+        }
+
+        #[test]
+        fn trigger_met_owned() {
+            assert_owned(&factory::appender("-", 2), "foo\n\nbar");
+            assert_owned(&factory::replacer_crlf("", 1), "foo\r\n");
+            assert_owned(&factory::replacer("-", 1), "\n");
+        }
+
+        #[test]
+        fn crlf_lone_carriage_return_borrowed() {
+            assert_borrowed(&factory::inserter_crlf("-", 2), "foo\rbar\r\n");
+        }
+
+        #[test]
+        fn crlf_bare_newline_owned() {
+            assert_owned(&factory::inserter_crlf("-", 2), "foo\nbar");
+        }
+
+        #[test]
+        fn trailing_newline_owned() {
+            let editor = Editor::default().ensure_trailing_newline(true);
+            assert_owned(&editor, "foo");
+            assert_owned(&editor, "foo\n\n");
+        }
+
+        #[test]
+        fn strip_cr_owned_only_with_crlf() {
+            let editor = factory::replacer("-", 3).strip_cr(true);
+            assert_owned(&editor, "foo\r\nbar");
+            assert_borrowed(&editor, "foo\nbar\rbaz");
+        }
+
+        fn assert_borrowed(editor: &Editor, input: &str) {
+            match editor.edit_cow(input) {
+                Cow::Borrowed(output) => assert_eq!(input, output),
+                Cow::Owned(output) => panic!("owned: {:?}", output),
+            }
+            assert_eq!(input, editor.edit(input));
+        }
+
+        fn assert_owned(editor: &Editor, input: &str) {
+            match editor.edit_cow(input) {
+                Cow::Owned(output) => assert_eq!(editor.edit(input), output),
+                Cow::Borrowed(output) => panic!("borrowed: {:?}", output),
+            }
         }
     }
 
-    /// Edit the input buffer's newlines into the output writer
-    ///
-    /// Input types must implement [`BufRead`].
-    /// Output types must implement [`Write`].
-    ///
-    /// Text is edited according to how this editor was constructed. Can be
-    /// used multiple times. The `replace` string is used to replace newlines
-    /// when the `newlines` trigger is met.
-    ///
-    /// # Examples
-    ///
-    /// ```rust
-    /// # use std::error::Error;
-    /// # use std::io::Cursor;
-    /// # use std::str::from_utf8;
-    /// # use linurgy::{Editor, NewlineType};
-    /// # fn main() -> Result<(), Box<dyn Error>> {
-    /// let editor = Editor::new("-".to_string(), 1, NewlineType::Lf);
-    /// // Cursor implements BufRead over a string
-    /// let mut input = Cursor::new("foo\nbar");
-    /// let mut output = Vec::new();
-    /// editor.edit_buffered(&mut input, &mut output)?;
-    /// assert_eq!("foo-bar", from_utf8(&output)?);
-    /// # Ok(())
-    /// # }
-    /// ```
-    #[inline]
-    pub fn edit_buffered<I, O>(&self, input: &mut I, output: &mut O) -> Result<(), io::Error>
-    where
-        I: BufRead,
-        O: Write,
-    {
-        let mut newlines = 0;
-        let mut buf = String::with_capacity(BUFSIZE);
+    mod edit_if_changed {
+        use super::*;
 
-        let (newline_len, newline_str) = match self.line_ending {
-            NewlineType::Lf => (1, "\n"),
-            NewlineType::Crlf => (2, "\r\n"),
-        };
+        #[test]
+        fn changed() {
+            let editor = factory::replacer(" ", 2);
+            let expected = Some("foo bar\nbaz".to_string());
+            assert_eq!(expected, editor.edit_if_changed("foo\n\nbar\nbaz"));
 
-        loop {
-            buf.clear();
-
-            match input.read_line(&mut buf)? {
-                // EOF
-                0 => break,
-                // newline by itself
-                len if len == newline_len => {
-                    newlines += 1;
-                }
-                // single newline
-                len => {
-                    while newlines > 0 {
-                        output.write_all(newline_str.as_bytes())?;
-                        newlines -= 1;
-                    }
-                    if buf.ends_with('\n') {
-                        newlines += 1;
-                        buf.truncate(len - newline_len);
-                    }
-                    output.write_all(buf.as_bytes())?;
-                }
-            }
+            let editor = Editor::default().ensure_trailing_newline(true);
+            assert_eq!(Some("foo\n".to_string()), editor.edit_if_changed("foo"));
+        }
 
-            if newlines == self.newlines {
-                output.write_all(self.replace.as_bytes())?;
-                newlines = 0;
-            }
+        #[test]
+        fn unchanged() {
+            assert_eq!(None, factory::replacer(" ", 2).edit_if_changed("foo\nbar"));
+            assert_eq!(None, Editor::default().edit_if_changed("foo\n\nbar"));
+            assert_eq!(None, factory::replacer("-", 1).edit_if_changed(""));
         }
 
-        // trailing newlines
-        while newlines > 0 {
-            output.write_all(newline_str.as_bytes())?;
-            newlines -= 1;
+        #[test]
+        fn edit_to_same_text_unchanged() {
+            let editor = factory::replacer("\n", 1);
+            assert_eq!(None, editor.edit_if_changed("foo\nbar\n"));
+
+            let editor = Editor::default().ensure_trailing_newline(true);
+            assert_eq!(None, editor.edit_if_changed("foo\n"));
         }
 
-        Ok(())
+        #[test]
+        fn auto_line_ending() {
+            let editor = factory::replacer_with("-", 1, NewlineType::Auto);
+            let expected = Some("foo-bar".to_string());
+            assert_eq!(expected, editor.edit_if_changed("foo\r\nbar"));
+            assert_eq!(None, editor.edit_if_changed("foo bar"));
+        }
     }
 
-    #[inline]
-    fn edit_lf(&self, input: &str) -> String {
-        let mut output = String::with_capacity(input.len() + self.replace.len());
-        let mut newlines = 0;
+    mod separators {
+        use super::*;
+        use std::io::BufReader;
 
-        for c in input.chars() {
-            newlines = match c {
-                '\n' => self.handle_newline(&mut output, newlines),
-                c => self.handle_char_lf(&mut output, c, newlines),
-            }
+        const LS: NewlineType = NewlineType::LineSeparator;
+        const PS: NewlineType = NewlineType::ParagraphSeparator;
+
+        #[test]
+        fn replace_line_separator() {
+            let editor = Editor::new(" ".to_string(), 1, LS);
+            assert_edit("foo bar baz", &editor, "foo\u{2028}bar\u{2028}baz");
         }
 
-        for _ in 0..newlines {
-            output.push('\n');
+        #[test]
+        fn insert_before_double_paragraph_separator() {
+            let editor = Editor::new("--\u{2029}\u{2029}".to_string(), 2, PS);
+            let input = "foo\u{2029}bar\u{2029}\u{2029}baz\u{2029}\u{2029}\u{2029}";
+            let expected = "foo\u{2029}bar--\u{2029}\u{2029}baz--\u{2029}\u{2029}\u{2029}";
+            assert_edit(expected, &editor, input);
         }
 
-        output
-    }
+        #[test]
+        fn other_newlines_are_text() {
+            let editor = Editor::new("-".to_string(), 1, PS);
+            let input = "foo\nbar\r\n\u{2028}baz\u{2029}";
+            assert_edit("foo\nbar\r\n\u{2028}baz-", &editor, input);
+        }
 
-    #[inline]
-    fn edit_crlf(&self, input: &str) -> String {
-        let mut output = String::with_capacity(input.len() + self.replace.len());
-        let mut nl_count = 0;
+        #[test]
+        fn one_char_lines() {
+            let editor = Editor::new("-".to_string(), 2, LS);
+            let input = "a\u{2028}b\u{2028}\u{2028}c";
+            assert_edit("a\u{2028}b-c", &editor, input);
+        }
+
+        #[test]
+        fn trailing_newline() {
+            let editor = Editor::new("-".to_string(), 3, PS).ensure_trailing_newline(true);
+            let input = "foo\u{2029}bar\u{2029}\u{2029}";
+            assert_edit("foo\u{2029}bar\u{2029}", &editor, input);
+        }
+
+        #[test]
+        fn zero_newlines_is_noop() {
+            let editor = Editor::new("-".to_string(), 0, LS);
+            assert!(editor.is_noop());
+            assert_edit(
+                "foo\u{2028}\u{2028}bar\n",
+                &editor,
+                "foo\u{2028}\u{2028}bar\n",
+            );
+        }
+
+        #[test]
+        fn edit_lines_splits_on_lf() {
+            let editor = Editor::new("\n".to_string(), 1, LS);
+            let actual: Vec<String> = editor.edit_lines("foo\u{2028}bar").collect();
+            assert_eq!(vec!["foo", "bar"], actual);
+        }
 
-        for c in input.chars() {
-            nl_count = match c {
-                '\r' => nl_count,
-                '\n' => self.handle_newline(&mut output, nl_count),
-                c => self.handle_char_crlf(&mut output, c, nl_count),
+        #[test]
+        fn buffered_invalid_utf8_reports_line() {
+            let editor = Editor::new("-".to_string(), 1, LS);
+            let mut input: &[u8] = b"foo\xe2\x80\xa8b\xffr\xe2\x80\xa8";
+            match editor.edit_buffered(&mut input, &mut Vec::new()) {
+                Err(EditError::InvalidUtf8 { line }) => assert_eq!(2, line),
+                res => panic!("unexpected result: {:?}", res),
             }
         }
 
-        for _ in 0..nl_count {
-            output.push_str("\r\n");
+        #[test]
+        fn buffered_separator_split_across_reads() {
+            let editor = Editor::new("-".to_string(), 1, PS);
+            let input = "foo\u{2029}ba\u{a9}\u{2029}baz";
+            let mut buf_input = BufReader::with_capacity(4, input.as_bytes());
+            let mut output = Vec::new();
+            editor.edit_buffered(&mut buf_input, &mut output).unwrap();
+            assert_eq!("foo-ba\u{a9}-baz", String::from_utf8(output).unwrap());
         }
 
-        output
-    }
+        fn assert_edit(expected: &str, editor: &Editor, input: &str) {
+            assert_eq!(expected, editor.edit(input), "standard");
 
-    #[inline(always)]
-    fn handle_newline(&self, output: &mut String, mut nl_count: u8) -> u8 {
-        nl_count += 1;
+            let mut in_place = input.to_string();
+            editor.edit_in_place(&mut in_place);
+            assert_eq!(expected, in_place, "in place");
 
-        if nl_count == self.newlines {
-            output.push_str(&self.replace);
-            0
-        } else {
-            nl_count
+            let mut buf_input = BufReader::new(input.as_bytes());
+            let mut output = Vec::new();
+            editor.edit_buffered(&mut buf_input, &mut output).unwrap();
+            assert_eq!(expected, String::from_utf8(output).unwrap(), "buffered");
+
+            let lines: Vec<String> = editor.edit_lines(input).collect();
+            let expected_lines: Vec<&str> = expected.lines().collect();
+            assert_eq!(expected_lines, lines, "lines");
         }
     }
 
-    #[inline(always)]
-    fn handle_char_lf(&self, output: &mut String, c: char, nl_count: u8) -> u8 {
-        for _ in 0..nl_count {
-            output.push('\n');
+    mod capacity {
+        use super::*;
+        use std::io::BufReader;
+
+        #[test]
+        fn lines_longer_than_capacity() {
+            let line = "0123456789".repeat(10);
+            let input = format!("{}\n\n{}\n{}", line, line, line);
+            let editor = Editor::new("\n--\n".to_string(), 2, NewlineType::Lf);
+
+            for &capacity in &[0, 1, 8, 64] {
+                let mut buf_input = BufReader::with_capacity(4, input.as_bytes());
+                let mut output = Vec::new();
+                editor
+                    .edit_buffered_with_capacity(&mut buf_input, &mut output, capacity)
+                    .unwrap();
+
+                let actual = String::from_utf8_lossy(&output);
+                assert_eq!(editor.edit(&input), actual, "\ncapacity: {}\n", capacity);
+            }
         }
-        output.push(c);
-        0
     }
 
-    #[inline(always)]
-    fn handle_char_crlf(&self, output: &mut String, c: char, nl_count: u8) -> u8 {
-        for _ in 0..nl_count {
-            output.push_str("\r\n");
+    mod written {
+        use super::*;
+        use std::io::BufReader;
+
+        #[test]
+        fn empty_input_writes_nothing() {
+            assert_written(&Editor::default(), "");
         }
-        output.push(c);
-        0
-    }
-}
 
-impl Default for Editor {
-    /// Will do nothing on `edit`
-    fn default() -> Self {
-        Editor {
-            replace: String::new(),
-            newlines: 0,
-            line_ending: NewlineType::Lf,
+        #[test]
+        fn default_editor_writes_input() {
+            assert_written(&Editor::default(), "foo\nbar\n\nbaz");
         }
-    }
-}
 
-impl NewlineType {
-    #[inline]
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            NewlineType::Lf => "\n",
-            NewlineType::Crlf => "\r\n",
+        #[test]
+        fn growing_replacement() {
+            let editor = Editor::new("\n---\n".to_string(), 1, NewlineType::Lf);
+            assert_written(&editor, "foo\nbar\n\nbaz\n");
         }
-    }
-}
 
-impl fmt::Display for NewlineType {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.as_str())
-    }
-}
+        #[test]
+        fn shrinking_replacement_crlf() {
+            let editor = Editor::new("".to_string(), 2, NewlineType::Crlf);
+            assert_written(&editor, "foo\r\n\r\nbar\r\nbaz\r\n\r\n\r\n");
+        }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        #[test]
+        fn trailing_newline() {
+            let editor =
+                Editor::new("-".to_string(), 3, NewlineType::Lf).ensure_trailing_newline(true);
+            assert_written(&editor, "foo\n\nbar\n\n\n\n");
+        }
 
-    mod standard {
+        fn assert_written(editor: &Editor, input: &str) {
+            let mut buf_input = BufReader::new(input.as_bytes());
+            let mut buf_output = Vec::new();
+            let written = editor
+                .edit_buffered_written(&mut buf_input, &mut buf_output)
+                .unwrap();
+            assert_eq!(buf_output.len(), written);
+            assert_eq!(editor.edit(input).as_bytes(), &buf_output[..]);
+        }
+    }
+
+    mod counted {
         use super::*;
+        use std::io::BufReader;
 
-        editor_tests!(assert_edit);
+        #[test]
+        fn default_editor_counts_zero() {
+            let input = "foo\nbar\n\nbaz";
+            assert_counted(0, &Editor::default(), input);
+        }
 
-        fn assert_edit(test: EditTest) {
-            let replace = test.replace.to_string();
-            let editor = Editor::new(replace, test.newlines, test.line_ending);
+        #[test]
+        fn zero_newlines_counts_zero() {
+            let input = "foo\nbar\n\nbaz\n\n\n";
+            let editor = Editor::new("x".to_string(), 0, NewlineType::Lf);
+            assert_counted(0, &editor, input);
+        }
 
-            assert_eq!(
-                test.expected,
-                editor.edit(test.input),
-                "\ntest: {}\n",
-                test.name
-            );
+        #[test]
+        fn identity_editors_count_zero() {
+            let input = "a\nb\nc\n\nd\n\n\n";
+            assert_counted(0, &factory::inserter("", 1), input);
+            assert_counted(0, &factory::inserter("", 2), input);
+            assert_counted(0, &factory::appender("", 1), input);
+            assert_counted(0, &factory::appender("", 3), input);
+        }
+
+        #[test]
+        fn counts_each_trigger() {
+            let input = "foo\n\nbar\nbaz\n\n\n\n";
+            let editor = Editor::new("-".to_string(), 2, NewlineType::Lf);
+            assert_counted(3, &editor, input);
+        }
+
+        #[test]
+        fn counts_each_trigger_crlf() {
+            let input = "foo\r\nbar\r\n\r\nbaz\r\n";
+            let editor = Editor::new("-".to_string(), 1, NewlineType::Crlf);
+            assert_counted(4, &editor, input);
+        }
+
+        fn assert_counted(expected: usize, editor: &Editor, input: &str) {
+            let (output, edits) = editor.edit_counted(input);
+            assert_eq!(editor.edit(input), output);
+            assert_eq!(expected, edits, "standard");
+
+            let mut buf_input = BufReader::new(input.as_bytes());
+            let mut buf_output = Vec::new();
+            let edits = editor
+                .edit_buffered_counted(&mut buf_input, &mut buf_output)
+                .unwrap();
+            assert_eq!(expected, edits, "buffered");
+
+            assert_eq!(expected, editor.preview(input).len(), "preview");
         }
     }
 
-    mod buffered {
+    mod flushing {
         use super::*;
         use std::io::BufReader;
 
-        editor_tests!(assert_edit_buffered);
+        /// Records how much had been written at each flush.
+        #[derive(Default)]
+        struct FlushRecorder {
+            written: Vec<u8>,
+            flushes: Vec<usize>,
+        }
 
-        fn assert_edit_buffered(test: EditTest) {
-            let mut input = BufReader::new(test.input.as_bytes());
+        impl Write for FlushRecorder {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.written.extend_from_slice(buf);
+                Ok(buf.len())
+            }
 
-            let mut output: Vec<u8> = Vec::new();
+            fn flush(&mut self) -> io::Result<()> {
+                self.flushes.push(self.written.len());
+                Ok(())
+            }
+        }
 
-            let replace = test.replace.to_string();
-            let editor = Editor::new(replace, test.newlines, test.line_ending);
+        #[test]
+        fn flushes_each_line() {
+            let editor = factory::appender("-", 1);
+            let output = assert_flushing(&editor, "foo\nbar\nbaz");
 
-            editor.edit_buffered(&mut input, &mut output).unwrap();
+            assert_eq!(vec![5, 10, 13, 13], output.flushes);
+        }
 
-            let actual = String::from_utf8_lossy(&output);
+        #[test]
+        fn held_newlines_flushed_at_end() {
+            let editor = factory::replacer("-", 3).ensure_trailing_newline(true);
+            let output = assert_flushing(&editor, "foo\n\nbar\n\n");
 
-            assert_eq!(test.expected, actual, "\ntest: {}\n", test.name);
+            assert_eq!(vec![3, 3, 8, 8, 9], output.flushes);
+        }
+
+        #[test]
+        fn noop_flushes_each_read() {
+            let editor = Editor::default();
+            let mut input = BufReader::with_capacity(4, "foo\nbar\n".as_bytes());
+            let mut output = FlushRecorder::default();
+            editor
+                .edit_buffered_flushing(&mut input, &mut output)
+                .unwrap();
+
+            assert_eq!(vec![4, 8], output.flushes);
+        }
+
+        fn assert_flushing(editor: &Editor, input: &str) -> FlushRecorder {
+            let mut output = FlushRecorder::default();
+            editor
+                .edit_buffered_flushing(&mut input.as_bytes(), &mut output)
+                .unwrap();
+
+            assert_eq!(editor.edit(input).as_bytes(), &output.written[..]);
+            output
         }
     }
 
@@ -287,7 +7872,7 @@ mod tests {
         pub name: &'static str,
         pub expected: &'static str,
         pub input: &'static str,
-        pub newlines: u8,
+        pub newlines: usize,
         pub replace: &'static str,
         pub line_ending: NewlineType,
     }
@@ -486,6 +8071,126 @@ mod tests {
                 });
             }
 
+            #[test]
+            fn lone_carriage_return_preserved_crlf() {
+                $assert_fn(EditTest {
+                    name: "lone carriage return preserved crlf",
+                    expected: "foo\rbar-baz",
+                    input: "foo\rbar\r\nbaz",
+                    newlines: 1,
+                    replace: "-",
+                    line_ending: NewlineType::Crlf,
+                });
+            }
+
+            #[test]
+            fn carriage_return_between_chars_crlf() {
+                $assert_fn(EditTest {
+                    name: "carriage return between chars crlf",
+                    expected: "a\rb",
+                    input: "a\rb",
+                    newlines: 1,
+                    replace: "-",
+                    line_ending: NewlineType::Crlf,
+                });
+            }
+
+            #[test]
+            fn carriage_return_before_crlf() {
+                $assert_fn(EditTest {
+                    name: "carriage return before crlf",
+                    expected: "a\r-b",
+                    input: "a\r\r\nb",
+                    newlines: 1,
+                    replace: "-",
+                    line_ending: NewlineType::Crlf,
+                });
+            }
+
+            #[test]
+            fn carriage_return_at_end_crlf() {
+                $assert_fn(EditTest {
+                    name: "carriage return at end crlf",
+                    expected: "a--b\r",
+                    input: "a\r\n\r\nb\r",
+                    newlines: 1,
+                    replace: "-",
+                    line_ending: NewlineType::Crlf,
+                });
+            }
+
+            #[test]
+            fn one_char_lines() {
+                $assert_fn(EditTest {
+                    name: "one char lines",
+                    expected: "a-b--c",
+                    input: "a\nb\n\nc",
+                    newlines: 1,
+                    replace: "-",
+                    line_ending: NewlineType::Lf,
+                });
+            }
+
+            #[test]
+            fn one_char_lines_crlf() {
+                $assert_fn(EditTest {
+                    name: "one char lines crlf",
+                    expected: "a\r\nb-c\r\nd",
+                    input: "a\r\nb\r\n\r\nc\r\nd",
+                    newlines: 2,
+                    replace: "-",
+                    line_ending: NewlineType::Crlf,
+                });
+            }
+
+            #[test]
+            fn one_char_last_line() {
+                $assert_fn(EditTest {
+                    name: "one char last line",
+                    expected: "foo-a",
+                    input: "foo\n\na",
+                    newlines: 2,
+                    replace: "-",
+                    line_ending: NewlineType::Lf,
+                });
+            }
+
+            #[test]
+            fn one_char_last_line_crlf() {
+                $assert_fn(EditTest {
+                    name: "one char last line crlf",
+                    expected: "foo-a",
+                    input: "foo\r\n\r\na",
+                    newlines: 2,
+                    replace: "-",
+                    line_ending: NewlineType::Crlf,
+                });
+            }
+
+            #[test]
+            fn one_char_bare_lf_lines_crlf() {
+                $assert_fn(EditTest {
+                    name: "one char bare lf lines crlf",
+                    expected: "a-b-c",
+                    input: "a\nb\r\nc",
+                    newlines: 1,
+                    replace: "-",
+                    line_ending: NewlineType::Crlf,
+                });
+            }
+
+            #[test]
+            fn zero_newlines_no_trailing_newline_does_nothing() {
+                $assert_fn(EditTest {
+                    name: "zero newlines no trailing newline does nothing",
+                    expected: "foo\nbar\n\nbaz",
+                    input: "foo\nbar\n\nbaz",
+                    newlines: 0,
+                    replace: "should not be used",
+                    line_ending: NewlineType::Lf,
+                });
+            }
+
             #[test]
             fn insert_dash_every_5_lines() {
                 $assert_fn(EditTest {
@@ -521,6 +8226,42 @@ mod tests {
                     line_ending: NewlineType::Lf,
                 });
             }
+
+            #[test]
+            fn append_ends_on_trigger() {
+                $assert_fn(EditTest {
+                    name: "append ends on trigger",
+                    expected: "foo\n\n-bar\n\n-",
+                    input: "foo\n\nbar\n\n",
+                    newlines: 2,
+                    replace: "\n\n-",
+                    line_ending: NewlineType::Lf,
+                });
+            }
+
+            #[test]
+            fn append_ends_on_trigger_crlf() {
+                $assert_fn(EditTest {
+                    name: "append ends on trigger crlf",
+                    expected: "foo\r\n\r\n-bar\r\n\r\n-",
+                    input: "foo\r\n\r\nbar\r\n\r\n",
+                    newlines: 2,
+                    replace: "\r\n\r\n-",
+                    line_ending: NewlineType::Crlf,
+                });
+            }
+
+            #[test]
+            fn append_ends_past_trigger() {
+                $assert_fn(EditTest {
+                    name: "append ends past trigger",
+                    expected: "foo\n\n-\n",
+                    input: "foo\n\n\n",
+                    newlines: 2,
+                    replace: "\n\n-",
+                    line_ending: NewlineType::Lf,
+                });
+            }
         };
     }
 
@@ -0,0 +1,223 @@
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+#[cfg(feature = "std")]
+use std::io;
+
+use crate::{BuildError, ParseEditorError};
+#[cfg(feature = "std")]
+use crate::{ConfigError, EditError};
+
+#[cfg(feature = "std")]
+impl EditError {
+    /// Classify an error from reading line number `line` of the input.
+    ///
+    /// [`BufRead::read_line`](std::io::BufRead::read_line) reports invalid
+    /// UTF-8 with [`io::ErrorKind::InvalidData`].
+    #[inline]
+    pub(crate) fn read(error: io::Error, line: usize) -> Self {
+        match error.kind() {
+            io::ErrorKind::InvalidData => EditError::InvalidUtf8 { line },
+            _ => EditError::Io(error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EditError::Io(error) => write!(f, "failed to read input: {}", error),
+            EditError::InvalidUtf8 { line } => {
+                write!(f, "input contains invalid UTF-8 on line {}", line)
+            }
+            EditError::WriteFailed(error) => write!(f, "failed to write output: {}", error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for EditError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            EditError::Io(error) | EditError::WriteFailed(error) => Some(error),
+            EditError::InvalidUtf8 { .. } => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for EditError {
+    #[inline]
+    fn from(error: io::Error) -> Self {
+        EditError::Io(error)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<EditError> for io::Error {
+    fn from(error: EditError) -> Self {
+        match error {
+            EditError::Io(error) | EditError::WriteFailed(error) => error,
+            EditError::InvalidUtf8 { .. } => io::Error::new(io::ErrorKind::InvalidData, error),
+        }
+    }
+}
+
+impl fmt::Display for ParseEditorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseEditorError::InvalidFormat => {
+                write!(f, "expected an editor in the form `edit_type:trigger:text`")
+            }
+            ParseEditorError::InvalidEditType(edit_type) => write!(
+                f,
+                "invalid edit type {:?}, expected append, insert, or replace",
+                edit_type
+            ),
+            ParseEditorError::InvalidTrigger(error) => write!(f, "invalid trigger: {}", error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for ParseEditorError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseEditorError::InvalidTrigger(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ConfigError {
+    /// Line of the config the error is on, starting at 1
+    ///
+    /// `None` if reading the config failed.
+    #[inline]
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            ConfigError::Io(_) => None,
+            ConfigError::InvalidEditor { line, .. } => Some(*line),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(error) => write!(f, "failed to read config: {}", error),
+            ConfigError::InvalidEditor { line, error } => {
+                write!(f, "invalid editor on line {}: {}", line, error)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::Io(error) => Some(error),
+            ConfigError::InvalidEditor { error, .. } => Some(error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for ConfigError {
+    #[inline]
+    fn from(error: io::Error) -> Self {
+        ConfigError::Io(error)
+    }
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildError::ZeroTrigger => write!(f, "newline trigger must be at least 1"),
+            BuildError::EmptyRange => {
+                write!(f, "newline trigger range must not start after it ends")
+            }
+            BuildError::LoneCarriageReturn => {
+                write!(
+                    f,
+                    "text contains a carriage return outside of a CRLF newline"
+                )
+            }
+            BuildError::MixedNewlines => {
+                write!(
+                    f,
+                    "text contains newlines of a different type to the editor"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for BuildError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_data_is_invalid_utf8() {
+        let error = io::Error::new(io::ErrorKind::InvalidData, "bad");
+        match EditError::read(error, 3) {
+            EditError::InvalidUtf8 { line } => assert_eq!(3, line),
+            error => panic!("unexpected error: {:?}", error),
+        }
+    }
+
+    #[test]
+    fn other_read_errors_are_io() {
+        let error = io::Error::new(io::ErrorKind::UnexpectedEof, "eof");
+        match EditError::read(error, 1) {
+            EditError::Io(error) => assert_eq!(io::ErrorKind::UnexpectedEof, error.kind()),
+            error => panic!("unexpected error: {:?}", error),
+        }
+    }
+
+    #[test]
+    fn into_io_error_keeps_kind() {
+        let error = EditError::WriteFailed(io::Error::new(io::ErrorKind::BrokenPipe, "pipe"));
+        assert_eq!(io::ErrorKind::BrokenPipe, io::Error::from(error).kind());
+
+        let error = EditError::InvalidUtf8 { line: 1 };
+        assert_eq!(io::ErrorKind::InvalidData, io::Error::from(error).kind());
+    }
+
+    #[test]
+    fn display_invalid_utf8() {
+        let error = EditError::InvalidUtf8 { line: 7 };
+        assert_eq!("input contains invalid UTF-8 on line 7", error.to_string());
+    }
+
+    #[test]
+    fn display_invalid_edit_type() {
+        let error = ParseEditorError::InvalidEditType("prepend".to_string());
+        assert_eq!(
+            r#"invalid edit type "prepend", expected append, insert, or replace"#,
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn display_zero_trigger() {
+        let error = BuildError::ZeroTrigger;
+        assert_eq!("newline trigger must be at least 1", error.to_string());
+    }
+
+    #[test]
+    fn display_empty_range() {
+        let error = BuildError::EmptyRange;
+        assert_eq!(
+            "newline trigger range must not start after it ends",
+            error.to_string()
+        );
+    }
+}
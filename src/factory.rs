@@ -1,6 +1,11 @@
 /*!
 Convenience functions for creating a configuired [`Editor`]. Variations are based on the desired
-type of edit: append, insert, or replace. Each has a [`CRLF`](NewlineType#variant.Crlf) version.
+type of edit: append, insert, or replace. Each has a [`CRLF`](NewlineType#variant.Crlf) version,
+and a `_with` version taking the [`NewlineType`] as a parameter for when it is chosen at runtime.
+Replace whole runs of at least or at most the trigger with [`replacer_at_least`] and
+[`replacer_at_most`], or number each match with [`template_replacer`]. Put text on both sides
+of newlines with [`surrounder`]. Convert between newline types with [`normalizer`], or add text
+to the start or end of every line with [`line_prefixer`] and [`line_suffixer`].
 
 # Examples
 
@@ -22,46 +27,385 @@ let output = editor.edit("foo\n\nbar");
 assert_eq!("foo\n\n---bar", output);
 ```
 */
-use crate::{Editor, NewlineType};
+use crate::prelude::*;
+use crate::{EditType, Editor, NewlineType, TriggerMode};
 
 /// Create an [`Editor`] that appends text *after* newlines.
 #[inline]
-pub fn appender(text: &str, newlines: u8) -> Editor {
-    Factory::build(text, newlines, EditType::Append, NewlineType::Lf)
+pub fn appender(text: &str, newlines: usize) -> Editor {
+    appender_with(text, newlines, NewlineType::Lf)
 }
 
 /// Create an [`Editor`] that inserts text *before* newlines.
 #[inline]
-pub fn inserter(text: &str, newlines: u8) -> Editor {
-    Factory::build(text, newlines, EditType::Insert, NewlineType::Lf)
+pub fn inserter(text: &str, newlines: usize) -> Editor {
+    inserter_with(text, newlines, NewlineType::Lf)
 }
 
 /// Create an [`Editor`] that replaces newlines with given text.
 #[inline]
-pub fn replacer(text: &str, newlines: u8) -> Editor {
-    Factory::build(text, newlines, EditType::Replace, NewlineType::Lf)
+pub fn replacer(text: &str, newlines: usize) -> Editor {
+    replacer_with(text, newlines, NewlineType::Lf)
 }
 
 /// Create an [`Editor`] that appends text *after* CRLF newlines.
 #[inline]
-pub fn appender_crlf(text: &str, newlines: u8) -> Editor {
-    Factory::build(text, newlines, EditType::Append, NewlineType::Crlf)
+pub fn appender_crlf(text: &str, newlines: usize) -> Editor {
+    appender_with(text, newlines, NewlineType::Crlf)
 }
 
 /// Create an [`Editor`] that inserts text *before* CRLF newlines.
 #[inline]
-pub fn inserter_crlf(text: &str, newlines: u8) -> Editor {
-    Factory::build(text, newlines, EditType::Insert, NewlineType::Crlf)
+pub fn inserter_crlf(text: &str, newlines: usize) -> Editor {
+    inserter_with(text, newlines, NewlineType::Crlf)
 }
 
 /// Create an [`Editor`] that replaces CRLF newlines with given text.
 #[inline]
-pub fn replacer_crlf(text: &str, newlines: u8) -> Editor {
-    Factory::build(text, newlines, EditType::Replace, NewlineType::Crlf)
+pub fn replacer_crlf(text: &str, newlines: usize) -> Editor {
+    replacer_with(text, newlines, NewlineType::Crlf)
+}
+
+/// Create an [`Editor`] that appends text *after* `newline` newlines.
+///
+/// # Examples
+///
+/// ```rust
+/// # use linurgy::{factory, NewlineType};
+/// let editor = factory::appender_with("---", 2, NewlineType::Crlf);
+/// assert_eq!(factory::appender_crlf("---", 2), editor);
+/// ```
+#[inline]
+pub fn appender_with(text: &str, newlines: usize, newline: NewlineType) -> Editor {
+    Factory::build(text, newlines, EditType::Append, newline)
+}
+
+/// Create an [`Editor`] that inserts text *before* `newline` newlines.
+#[inline]
+pub fn inserter_with(text: &str, newlines: usize, newline: NewlineType) -> Editor {
+    Factory::build(text, newlines, EditType::Insert, newline)
+}
+
+/// Create an [`Editor`] that replaces `newline` newlines with given text.
+#[inline]
+pub fn replacer_with(text: &str, newlines: usize, newline: NewlineType) -> Editor {
+    Factory::build(text, newlines, EditType::Replace, newline)
+}
+
+/// Create an [`Editor`] that replaces runs of at least `newlines` newlines
+/// with given text.
+///
+/// The whole run is replaced, however long it is. Shorter runs are kept.
+/// See [`TriggerMode::AtLeast`].
+///
+/// # Examples
+///
+/// Collapse any run of blank lines into one.
+///
+/// ```rust
+/// # use linurgy::factory;
+/// let editor = factory::replacer_at_least("\n\n", 3);
+/// assert_eq!("foo\n\nbar\n\nbaz", editor.edit("foo\n\nbar\n\n\n\n\nbaz"));
+/// ```
+#[inline]
+pub fn replacer_at_least(text: &str, newlines: usize) -> Editor {
+    replacer_at_least_with(text, newlines, NewlineType::Lf)
+}
+
+/// Create an [`Editor`] that replaces runs of at least `newlines` CRLF
+/// newlines with given text.
+#[inline]
+pub fn replacer_at_least_crlf(text: &str, newlines: usize) -> Editor {
+    replacer_at_least_with(text, newlines, NewlineType::Crlf)
+}
+
+/// Create an [`Editor`] that replaces runs of at least `newlines` `newline`
+/// newlines with given text.
+#[inline]
+pub fn replacer_at_least_with(text: &str, newlines: usize, newline: NewlineType) -> Editor {
+    replacer_with(text, newlines, newline).trigger_mode(TriggerMode::AtLeast)
+}
+
+/// Create an [`Editor`] that replaces runs of at most `newlines` newlines
+/// with given text.
+///
+/// The whole run is replaced. Longer runs are kept. See
+/// [`TriggerMode::AtMost`].
+///
+/// # Examples
+///
+/// Join wrapped lines, keeping paragraph breaks.
+///
+/// ```rust
+/// # use linurgy::factory;
+/// let editor = factory::replacer_at_most(" ", 1);
+/// assert_eq!("foo bar\n\nbaz", editor.edit("foo\nbar\n\nbaz"));
+/// ```
+#[inline]
+pub fn replacer_at_most(text: &str, newlines: usize) -> Editor {
+    replacer_at_most_with(text, newlines, NewlineType::Lf)
+}
+
+/// Create an [`Editor`] that replaces runs of at most `newlines` CRLF
+/// newlines with given text.
+#[inline]
+pub fn replacer_at_most_crlf(text: &str, newlines: usize) -> Editor {
+    replacer_at_most_with(text, newlines, NewlineType::Crlf)
+}
+
+/// Create an [`Editor`] that replaces runs of at most `newlines` `newline`
+/// newlines with given text.
+#[inline]
+pub fn replacer_at_most_with(text: &str, newlines: usize, newline: NewlineType) -> Editor {
+    replacer_with(text, newlines, newline).trigger_mode(TriggerMode::AtMost)
+}
+
+/// Create an [`Editor`] that replaces newlines with a template, numbering
+/// each match.
+///
+/// Placeholders in `template` are expanded for every run of `newlines`
+/// newlines replaced:
+///
+/// - `{n}`: the number of the match, starting at 1.
+/// - `{count}`: the number of newlines in the run. This is `newlines`,
+///   unless the editor's [`TriggerMode`] is changed to one which replaces
+///   whole runs, such as [`AtLeast`](TriggerMode::AtLeast).
+///
+/// Write `{{` and `}}` for literal braces. Any other brace is kept as it is.
+/// For replacements which need more than a number, use
+/// [`Editor::edit_with`].
+///
+/// # Examples
+///
+/// Number every section break.
+///
+/// ```rust
+/// # use linurgy::factory;
+/// let editor = factory::template_replacer("\n-- {n} --\n", 2);
+/// let output = editor.edit("foo\n\nbar\n\nbaz");
+/// assert_eq!("foo\n-- 1 --\nbar\n-- 2 --\nbaz", output);
+/// ```
+#[inline]
+pub fn template_replacer(template: &str, newlines: usize) -> Editor {
+    template_replacer_with(template, newlines, NewlineType::Lf)
+}
+
+/// Create an [`Editor`] that replaces CRLF newlines with a template,
+/// numbering each match.
+#[inline]
+pub fn template_replacer_crlf(template: &str, newlines: usize) -> Editor {
+    template_replacer_with(template, newlines, NewlineType::Crlf)
+}
+
+/// Create an [`Editor`] that replaces `newline` newlines with a template,
+/// numbering each match.
+#[inline]
+pub fn template_replacer_with(template: &str, newlines: usize, newline: NewlineType) -> Editor {
+    let mut editor = replacer_with(template, newlines, newline);
+    editor.template = true;
+    editor
+}
+
+/// Create an [`Editor`] that surrounds newlines with text *before* and
+/// *after* them.
+///
+/// Each run of `newlines` newlines is kept, with `before` inserted in front
+/// of it and `after` appended behind it, in a single edit.
+///
+/// # Examples
+///
+/// Mark paragraph breaks.
+///
+/// ```rust
+/// # use linurgy::factory;
+/// let editor = factory::surrounder("[", "]", 2);
+/// assert_eq!("foo[\n\n]bar", editor.edit("foo\n\nbar"));
+/// ```
+#[inline]
+pub fn surrounder(before: &str, after: &str, newlines: usize) -> Editor {
+    surrounder_with(before, after, newlines, NewlineType::Lf)
+}
+
+/// Create an [`Editor`] that surrounds CRLF newlines with text *before* and
+/// *after* them.
+#[inline]
+pub fn surrounder_crlf(before: &str, after: &str, newlines: usize) -> Editor {
+    surrounder_with(before, after, newlines, NewlineType::Crlf)
+}
+
+/// Create an [`Editor`] that surrounds `newline` newlines with text *before*
+/// and *after* them.
+///
+/// The editor replaces newlines with `before`, the newlines, then `after`.
+/// As with other replace edits, [`Editor::with_line_ending`] keeps this
+/// `replace` string as it is.
+#[inline]
+pub fn surrounder_with(before: &str, after: &str, newlines: usize, newline: NewlineType) -> Editor {
+    let replace = Factory::wrap_replace(after, before, newlines, newline);
+    Factory::build(&replace, newlines, EditType::Replace, newline)
+}
+
+/// Create an [`Editor`] that wraps each segment of text between `open` and
+/// `close`.
+///
+/// Segments are separated by runs of `newlines` newlines, which are kept.
+/// `open` is written at the start of the text and after each separator.
+/// `close` is written before each separator and at the end of the text.
+/// Empty input produces empty output.
+///
+/// # Examples
+///
+/// Wrap paragraphs in html `<p>` tags.
+///
+/// ```rust
+/// # use linurgy::factory;
+/// let editor = factory::wrap("<p>", "</p>", 2);
+/// let output = editor.edit("foo\nbar\n\nbaz");
+/// assert_eq!("<p>foo\nbar</p>\n\n<p>baz</p>", output);
+/// ```
+#[inline]
+pub fn wrap(open: &str, close: &str, newlines: usize) -> Editor {
+    wrap_with(open, close, newlines, NewlineType::Lf)
+}
+
+/// Create an [`Editor`] that wraps each segment of text, separated by CRLF
+/// newlines, between `open` and `close`.
+#[inline]
+pub fn wrap_crlf(open: &str, close: &str, newlines: usize) -> Editor {
+    wrap_with(open, close, newlines, NewlineType::Crlf)
+}
+
+/// Create an [`Editor`] that wraps each segment of text, separated by
+/// `newline` newlines, between `open` and `close`.
+#[inline]
+pub fn wrap_with(open: &str, close: &str, newlines: usize, newline: NewlineType) -> Editor {
+    Factory::wrap(open, close, newlines, newline)
+}
+
+/// Create an [`Editor`] that adds text to the start of every line.
+///
+/// Unlike [`appender`] with a trigger of `1`, the first line and blank lines
+/// are also prefixed. A newline at the end of the input ends the last line,
+/// so nothing is added after it. Empty input produces empty output.
+///
+/// # Examples
+///
+/// Quote text as in an email reply.
+///
+/// ```rust
+/// # use linurgy::factory;
+/// let editor = factory::line_prefixer("> ");
+/// let output = editor.edit("foo\n\nbar\n");
+/// assert_eq!("> foo\n> \n> bar\n", output);
+/// ```
+#[inline]
+pub fn line_prefixer(text: &str) -> Editor {
+    line_prefixer_with(text, NewlineType::Lf)
+}
+
+/// Create an [`Editor`] that adds text to the start of every CRLF line.
+#[inline]
+pub fn line_prefixer_crlf(text: &str) -> Editor {
+    line_prefixer_with(text, NewlineType::Crlf)
+}
+
+/// Create an [`Editor`] that adds text to the start of every line ending with
+/// a `newline` newline.
+#[inline]
+pub fn line_prefixer_with(text: &str, newline: NewlineType) -> Editor {
+    Factory::wrap(text, "", 1, newline).skip_empty_last_segment(true)
+}
+
+/// Create an [`Editor`] that adds text to the end of every line, before its
+/// newline.
+///
+/// Unlike [`inserter`] with a trigger of `1`, a last line without a newline
+/// is also suffixed. Blank lines are suffixed too, but a newline at the end
+/// of the input ends the last line, so nothing is added after it. Empty input
+/// produces empty output.
+///
+/// # Examples
+///
+/// ```rust
+/// # use linurgy::factory;
+/// let editor = factory::line_suffixer(";");
+/// assert_eq!("foo;\n;\nbar;", editor.edit("foo\n\nbar"));
+/// assert_eq!("foo;\n", editor.edit("foo\n"));
+/// ```
+#[inline]
+pub fn line_suffixer(text: &str) -> Editor {
+    line_suffixer_with(text, NewlineType::Lf)
+}
+
+/// Create an [`Editor`] that adds text to the end of every CRLF line, before
+/// its newline.
+#[inline]
+pub fn line_suffixer_crlf(text: &str) -> Editor {
+    line_suffixer_with(text, NewlineType::Crlf)
+}
+
+/// Create an [`Editor`] that adds text to the end of every line, before its
+/// `newline` newline.
+#[inline]
+pub fn line_suffixer_with(text: &str, newline: NewlineType) -> Editor {
+    Factory::wrap("", text, 1, newline).skip_empty_last_segment(true)
+}
+
+/// Create an [`Editor`] that joins every line into one, with `separator`
+/// between them.
+///
+/// Each run of newlines, of any length, becomes exactly one `separator`, so
+/// blank lines don't add extra separators as they would with
+/// `replacer(separator, 1)`. A run at the end of the input is replaced too.
+/// The same as [`replacer_at_least`] with a trigger of `1`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use linurgy::factory;
+/// let editor = factory::joiner(" ");
+/// assert_eq!("foo bar baz ", editor.edit("foo\nbar\n\n\nbaz\n"));
+/// ```
+#[inline]
+pub fn joiner(separator: &str) -> Editor {
+    joiner_with(separator, NewlineType::Lf)
+}
+
+/// Create an [`Editor`] that joins every CRLF line into one, with
+/// `separator` between them.
+#[inline]
+pub fn joiner_crlf(separator: &str) -> Editor {
+    joiner_with(separator, NewlineType::Crlf)
+}
+
+/// Create an [`Editor`] that joins every line ending in a `newline` newline
+/// into one, with `separator` between them.
+#[inline]
+pub fn joiner_with(separator: &str, newline: NewlineType) -> Editor {
+    replacer_at_least_with(separator, 1, newline)
+}
+
+/// Create an [`Editor`] that converts every `from` newline into a `to`
+/// newline.
+///
+/// Each newline is converted on its own, so runs of any length are
+/// preserved. Converting a newline type to itself makes no changes.
+///
+/// # Examples
+///
+/// ```rust
+/// # use linurgy::{factory, NewlineType};
+/// let editor = factory::normalizer(NewlineType::Crlf, NewlineType::Lf);
+/// let output = editor.edit("foo\r\n\r\nbar\r\n");
+/// assert_eq!("foo\n\nbar\n", output);
+/// ```
+#[inline]
+pub fn normalizer(from: NewlineType, to: NewlineType) -> Editor {
+    Factory::build(to.as_str(), 1, EditType::Replace, from)
 }
 
 #[derive(Debug)]
-struct Factory<'a> {
+pub(crate) struct Factory<'a> {
     /// Text to replace/insert/append.
     text: &'a str,
 
@@ -72,76 +416,86 @@ struct Factory<'a> {
     newline: NewlineType,
 
     /// Number of newlines to trigger replacement.
-    trigger: u8,
-}
-
-/// Which action to implement when editing
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum EditType {
-    /// New edits will appear after newlines
-    Append,
-
-    /// New edits will appear before newlines
-    Insert,
-
-    /// New edits will appear instead of newlines
-    Replace,
+    trigger: usize,
 }
 
 impl<'a> Factory<'a> {
     #[inline]
-    fn build(text: &'a str, trigger: u8, edit_type: EditType, newline: NewlineType) -> Editor {
-        let factory = Self {
+    pub(crate) fn new(
+        text: &'a str,
+        trigger: usize,
+        edit_type: EditType,
+        newline: NewlineType,
+    ) -> Self {
+        Self {
             text,
             trigger,
             edit_type,
             newline,
-        };
-
-        factory.create_editor()
+        }
     }
 
     #[inline]
-    fn create_editor(&self) -> Editor {
-        let replace = match self.edit_type {
-            EditType::Append => self.append_string(),
-            EditType::Insert => self.insert_string(),
-            EditType::Replace => String::from(self.text),
-        };
-
-        Editor::new(replace, self.trigger, self.newline)
+    fn build(text: &'a str, trigger: usize, edit_type: EditType, newline: NewlineType) -> Editor {
+        Self::new(text, trigger, edit_type, newline).create_editor()
     }
 
     #[inline]
-    fn append_string(&self) -> String {
-        let mut replace = self.string_with_replace_capacity();
-
-        for _ in 0..self.trigger {
-            replace.push_str(self.newline.as_str());
-        }
-
-        replace.push_str(self.text);
+    fn wrap(open: &str, close: &str, trigger: usize, newline: NewlineType) -> Editor {
+        let replace = Factory::wrap_replace(open, close, trigger, newline);
+        Factory::build(&replace, trigger, EditType::Replace, newline).wrap(open, close)
+    }
 
+    /// Wrapping editors replace newlines with `close`, the newlines, then
+    /// `open`.
+    #[inline]
+    pub(crate) fn wrap_replace(
+        open: &str,
+        close: &str,
+        trigger: usize,
+        newline: NewlineType,
+    ) -> String {
+        let mut replace = String::from(close);
+        Factory::new(open, trigger, EditType::Append, newline).push_replace(&mut replace);
         replace
     }
 
     #[inline]
-    fn insert_string(&self) -> String {
-        let mut replace = self.string_with_replace_capacity();
-
-        replace.push_str(self.text);
+    fn create_editor(&self) -> Editor {
+        let capacity = self.text.len() + self.trigger * self.newline.len();
+        let mut replace = String::with_capacity(capacity);
+        self.push_replace(&mut replace);
+
+        Editor::with_edit_type(
+            replace,
+            String::from(self.text),
+            self.edit_type,
+            self.trigger,
+            self.newline,
+        )
+    }
 
-        for _ in 0..self.trigger {
-            replace.push_str(self.newline.as_str());
+    /// Push the `replace` string for this edit onto the end of `replace`.
+    #[inline]
+    pub(crate) fn push_replace(&self, replace: &mut String) {
+        match self.edit_type {
+            EditType::Append => {
+                self.push_newlines(replace);
+                replace.push_str(self.text);
+            }
+            EditType::Insert => {
+                replace.push_str(self.text);
+                self.push_newlines(replace);
+            }
+            EditType::Replace => replace.push_str(self.text),
         }
-
-        replace
     }
 
     #[inline]
-    fn string_with_replace_capacity(&self) -> String {
-        let capacity = self.text.len() + self.trigger as usize * self.newline.as_str().len();
-        String::with_capacity(capacity)
+    fn push_newlines(&self, replace: &mut String) {
+        for _ in 0..self.trigger {
+            replace.push_str(self.newline.as_str());
+        }
     }
 }
 
@@ -152,134 +506,350 @@ mod tests {
     #[test]
     fn appender_blank() {
         let editor = appender("", 0);
-        let expected = blank_editor();
+        let expected = expected_editor("", "", EditType::Append, 0, NewlineType::Lf);
         assert_eq!(expected, editor);
     }
 
     #[test]
     fn inserter_blank() {
         let editor = inserter("", 0);
-        let expected = blank_editor();
+        let expected = expected_editor("", "", EditType::Insert, 0, NewlineType::Lf);
         assert_eq!(expected, editor);
     }
 
     #[test]
     fn replacer_blank() {
         let editor = replacer("", 0);
-        let expected = blank_editor();
+        let expected = expected_editor("", "", EditType::Replace, 0, NewlineType::Lf);
         assert_eq!(expected, editor);
     }
 
     #[test]
     fn appender_crlf_blank() {
         let editor = appender_crlf("", 0);
-        let expected = blank_editor_crlf();
+        let expected = expected_editor("", "", EditType::Append, 0, NewlineType::Crlf);
         assert_eq!(expected, editor);
     }
 
     #[test]
     fn inserter_crlf_blank() {
         let editor = inserter_crlf("", 0);
-        let expected = blank_editor_crlf();
+        let expected = expected_editor("", "", EditType::Insert, 0, NewlineType::Crlf);
         assert_eq!(expected, editor);
     }
 
     #[test]
     fn replacer_crlf_blank() {
         let editor = replacer_crlf("", 0);
-        let expected = blank_editor_crlf();
+        let expected = expected_editor("", "", EditType::Replace, 0, NewlineType::Crlf);
         assert_eq!(expected, editor);
     }
 
     #[test]
     fn appender_dash_one_line() {
         let editor = appender("-", 1);
-        let expected = Editor::new(String::from("\n-"), 1, NewlineType::Lf);
+        let expected = expected_editor("\n-", "-", EditType::Append, 1, NewlineType::Lf);
         assert_eq!(expected, editor);
     }
 
     #[test]
     fn inserter_dash_one_line() {
         let editor = inserter("-", 1);
-        let expected = Editor::new(String::from("-\n"), 1, NewlineType::Lf);
+        let expected = expected_editor("-\n", "-", EditType::Insert, 1, NewlineType::Lf);
         assert_eq!(expected, editor);
     }
 
     #[test]
     fn replacer_dash_one_line() {
         let editor = replacer("-", 1);
-        let expected = Editor::new(String::from("-"), 1, NewlineType::Lf);
+        let expected = expected_editor("-", "-", EditType::Replace, 1, NewlineType::Lf);
         assert_eq!(expected, editor);
     }
 
     #[test]
     fn appender_crlf_dash_one_line() {
         let editor = appender_crlf("-", 1);
-        let expected = Editor::new(String::from("\r\n-"), 1, NewlineType::Crlf);
+        let expected = expected_editor("\r\n-", "-", EditType::Append, 1, NewlineType::Crlf);
         assert_eq!(expected, editor);
     }
 
     #[test]
     fn inserter_crlf_dash_one_line() {
         let editor = inserter_crlf("-", 1);
-        let expected = Editor::new(String::from("-\r\n"), 1, NewlineType::Crlf);
+        let expected = expected_editor("-\r\n", "-", EditType::Insert, 1, NewlineType::Crlf);
         assert_eq!(expected, editor);
     }
 
     #[test]
     fn replacer_crlf_dash_one_line() {
         let editor = replacer_crlf("-", 1);
-        let expected = Editor::new(String::from("-"), 1, NewlineType::Crlf);
+        let expected = expected_editor("-", "-", EditType::Replace, 1, NewlineType::Crlf);
         assert_eq!(expected, editor);
     }
 
     #[test]
     fn appender_dash_two_lines() {
         let editor = appender("-", 2);
-        let expected = Editor::new(String::from("\n\n-"), 2, NewlineType::Lf);
+        let expected = expected_editor("\n\n-", "-", EditType::Append, 2, NewlineType::Lf);
         assert_eq!(expected, editor);
     }
 
     #[test]
     fn inserter_dash_two_lines() {
         let editor = inserter("-", 2);
-        let expected = Editor::new(String::from("-\n\n"), 2, NewlineType::Lf);
+        let expected = expected_editor("-\n\n", "-", EditType::Insert, 2, NewlineType::Lf);
         assert_eq!(expected, editor);
     }
 
     #[test]
     fn replacer_dash_two_lines() {
         let editor = replacer("-", 2);
-        let expected = Editor::new(String::from("-"), 2, NewlineType::Lf);
+        let expected = expected_editor("-", "-", EditType::Replace, 2, NewlineType::Lf);
         assert_eq!(expected, editor);
     }
 
     #[test]
     fn appender_crlf_dash_two_lines() {
         let editor = appender_crlf("-", 2);
-        let expected = Editor::new(String::from("\r\n\r\n-"), 2, NewlineType::Crlf);
+        let expected = expected_editor("\r\n\r\n-", "-", EditType::Append, 2, NewlineType::Crlf);
         assert_eq!(expected, editor);
     }
 
     #[test]
     fn inserter_crlf_dash_two_lines() {
         let editor = inserter_crlf("-", 2);
-        let expected = Editor::new(String::from("-\r\n\r\n"), 2, NewlineType::Crlf);
+        let expected = expected_editor("-\r\n\r\n", "-", EditType::Insert, 2, NewlineType::Crlf);
         assert_eq!(expected, editor);
     }
 
     #[test]
     fn replacer_crlf_dash_two_lines() {
         let editor = replacer_crlf("-", 2);
-        let expected = Editor::new(String::from("-"), 2, NewlineType::Crlf);
+        let expected = expected_editor("-", "-", EditType::Replace, 2, NewlineType::Crlf);
         assert_eq!(expected, editor);
     }
 
-    fn blank_editor() -> Editor {
-        Editor::new(String::from(""), 0, NewlineType::Lf)
+    #[test]
+    fn normalizer_crlf_to_lf() {
+        let editor = normalizer(NewlineType::Crlf, NewlineType::Lf);
+        assert_eq!(
+            "foo\nbar\n\n\nbaz\n",
+            editor.edit("foo\r\nbar\r\n\r\n\r\nbaz\r\n")
+        );
+    }
+
+    #[test]
+    fn normalizer_lf_to_crlf() {
+        let editor = normalizer(NewlineType::Lf, NewlineType::Crlf);
+        assert_eq!("foo\r\nbar\r\n\r\nbaz", editor.edit("foo\nbar\n\nbaz"));
+    }
+
+    #[test]
+    fn normalizer_same_type_does_nothing() {
+        let input = "foo\nbar\n\n\nbaz\n";
+        let editor = normalizer(NewlineType::Lf, NewlineType::Lf);
+        assert_eq!(input, editor.edit(input));
+
+        let input = "foo\r\nbar\r\n\r\nbaz";
+        let editor = normalizer(NewlineType::Crlf, NewlineType::Crlf);
+        assert_eq!(input, editor.edit(input));
+    }
+
+    #[test]
+    fn normalizer_editor() {
+        let editor = normalizer(NewlineType::Crlf, NewlineType::Lf);
+        let expected = expected_editor("\n", "\n", EditType::Replace, 1, NewlineType::Crlf);
+        assert_eq!(expected, editor);
     }
 
-    fn blank_editor_crlf() -> Editor {
-        Editor::new(String::from(""), 0, NewlineType::Crlf)
+    #[test]
+    fn wrap_paragraphs() {
+        let editor = wrap("<p>", "</p>", 2);
+        let output = editor.edit("foo\n\nbar\nbaz\n\nqux");
+        assert_eq!("<p>foo</p>\n\n<p>bar\nbaz</p>\n\n<p>qux</p>", output);
+    }
+
+    #[test]
+    fn wrap_crlf_paragraphs() {
+        let editor = wrap_crlf("<p>", "</p>", 2);
+        let output = editor.edit("foo\r\n\r\nbar");
+        assert_eq!("<p>foo</p>\r\n\r\n<p>bar</p>", output);
+    }
+
+    #[test]
+    fn wrap_empty_input() {
+        assert_eq!("", wrap("<p>", "</p>", 2).edit(""));
+    }
+
+    #[test]
+    fn wrap_editor() {
+        let editor = wrap("(", ")", 1);
+        let expected = expected_editor(")\n(", ")\n(", EditType::Replace, 1, NewlineType::Lf);
+        assert_eq!(expected.wrap("(", ")"), editor);
+    }
+
+    #[test]
+    fn appender_with_crlf_is_appender_crlf() {
+        assert_eq!(
+            appender_crlf("-", 1),
+            appender_with("-", 1, NewlineType::Crlf)
+        );
+    }
+
+    #[test]
+    fn inserter_with_crlf_is_inserter_crlf() {
+        assert_eq!(
+            inserter_crlf("-", 1),
+            inserter_with("-", 1, NewlineType::Crlf)
+        );
+    }
+
+    #[test]
+    fn replacer_with_crlf_is_replacer_crlf() {
+        assert_eq!(
+            replacer_crlf("-", 1),
+            replacer_with("-", 1, NewlineType::Crlf)
+        );
+    }
+
+    #[test]
+    fn wrap_with_crlf_is_wrap_crlf() {
+        assert_eq!(
+            wrap_crlf("(", ")", 1),
+            wrap_with("(", ")", 1, NewlineType::Crlf)
+        );
+    }
+
+    #[test]
+    fn appender_with_lf_is_appender() {
+        assert_eq!(appender("-", 2), appender_with("-", 2, NewlineType::Lf));
+    }
+
+    #[test]
+    fn replacer_with_line_separator() {
+        let editor = replacer_with("-", 1, NewlineType::LineSeparator);
+        assert_eq!("foo-bar\n", editor.edit("foo\u{2028}bar\n"));
+    }
+
+    #[test]
+    fn surrounder_paragraph_break() {
+        let editor = surrounder("[", "]", 2);
+        assert_eq!("foo[\n\n]bar", editor.edit("foo\n\nbar"));
+        assert_eq!("foo\nbar[\n\n]", editor.edit("foo\nbar\n\n"));
+    }
+
+    #[test]
+    fn surrounder_crlf_keeps_newlines() {
+        let editor = surrounder_crlf("<", ">", 1);
+        assert_eq!("a<\r\n><\r\n>b", editor.edit("a\r\n\r\nb"));
+        assert_eq!(surrounder_with("<", ">", 1, NewlineType::Crlf), editor);
+    }
+
+    #[test]
+    fn surrounder_is_appender_and_inserter() {
+        let input = "foo\n\nbar\nbaz\n\n";
+        let both = inserter("-", 2).then(appender("+", 2));
+        assert_eq!(both.edit(input), surrounder("-", "+", 2).edit(input));
+    }
+
+    #[test]
+    fn line_prefixer_leading_blank_lines() {
+        assert_lines("> \n> \n> foo\n", &line_prefixer("> "), "\n\nfoo\n");
+    }
+
+    #[test]
+    fn line_prefixer_no_trailing_newline() {
+        assert_lines("> foo\n> \n> bar", &line_prefixer("> "), "foo\n\nbar");
+    }
+
+    #[test]
+    fn line_prefixer_trailing_blank_line() {
+        assert_lines("# foo\n# \n", &line_prefixer("# "), "foo\n\n");
+        assert_lines("# \n", &line_prefixer("# "), "\n");
+        assert_lines("", &line_prefixer("# "), "");
+    }
+
+    #[test]
+    fn line_prefixer_crlf_lines() {
+        let editor = line_prefixer_crlf("// ");
+        assert_lines("// foo\r\n// \r\n// bar\r\n", &editor, "foo\r\n\r\nbar\r\n");
+        assert_eq!(line_prefixer_with("// ", NewlineType::Crlf), editor);
+    }
+
+    #[test]
+    fn line_suffixer_last_line_without_newline() {
+        assert_lines("foo;\nbar;", &line_suffixer(";"), "foo\nbar");
+        assert_lines("foo;\nbar;\n", &line_suffixer(";"), "foo\nbar\n");
+        assert_lines("", &line_suffixer(";"), "");
+    }
+
+    #[test]
+    fn line_suffixer_blank_lines() {
+        assert_lines(";\nfoo;\n;\n;\nbar;", &line_suffixer(";"), "\nfoo\n\n\nbar");
+        assert_lines(";\n;\n", &line_suffixer(";"), "\n\n");
+    }
+
+    #[test]
+    fn line_suffixer_crlf_lines() {
+        let editor = line_suffixer_crlf(" \\");
+        assert_lines("foo \\\r\n \\\r\nbar \\", &editor, "foo\r\n\r\nbar");
+        assert_lines("foo \\\r\n", &editor, "foo\r\n");
+        assert_eq!(line_suffixer_with(" \\", NewlineType::Crlf), editor);
+    }
+
+    #[test]
+    fn joiner_one_separator_per_run() {
+        assert_lines("a b", &joiner(" "), "a\n\n\nb");
+        assert_lines("a b c ", &joiner(" "), "a\nb\n\nc\n\n");
+        assert_lines("abc", &joiner(""), "\n\na\nb\n\n\n\nc");
+        assert_lines("", &joiner(" "), "");
+    }
+
+    #[test]
+    fn joiner_crlf_lines() {
+        let editor = joiner_crlf(", ");
+        assert_lines("a, b, c\r", &editor, "a\r\n\r\nb\nc\r");
+        assert_eq!(joiner_with(", ", NewlineType::Crlf), editor);
+        assert_eq!(replacer_at_least(" ", 1), joiner(" "));
+    }
+
+    #[test]
+    fn template_replacer_numbers_matches() {
+        let editor = template_replacer("\n{n}. ", 2);
+        assert_lines("foo\n1. bar\n2. baz", &editor, "foo\n\nbar\n\nbaz");
+        assert_eq!(
+            template_replacer_with("\n{n}. ", 2, NewlineType::Lf),
+            editor
+        );
+        assert_eq!(
+            template_replacer_with("{n}", 2, NewlineType::Crlf),
+            template_replacer_crlf("{n}", 2)
+        );
+    }
+
+    fn assert_lines(expected: &str, editor: &Editor, input: &str) {
+        assert_eq!(expected, editor.edit(input), "standard");
+
+        let mut output = Vec::new();
+        editor
+            .edit_buffered(&mut input.as_bytes(), &mut output)
+            .unwrap();
+        assert_eq!(expected, String::from_utf8(output).unwrap(), "buffered");
+    }
+
+    fn expected_editor(
+        replace: &str,
+        text: &str,
+        edit_type: EditType,
+        newlines: usize,
+        line_ending: NewlineType,
+    ) -> Editor {
+        Editor::with_edit_type(
+            String::from(replace),
+            String::from(text),
+            edit_type,
+            newlines,
+            line_ending,
+        )
     }
 }
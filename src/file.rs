@@ -0,0 +1,250 @@
+use std::ffi::{OsStr, OsString};
+use std::fs::{self, File, OpenOptions, Permissions};
+use std::io::{self, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{EditError, Editor};
+
+/// Number of temporary files named by this process, so each edit in place
+/// gets its own, even for the same file.
+static TEMP_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// Names tried for a temporary file before giving up.
+const TEMP_ATTEMPTS: usize = 16;
+
+impl Editor {
+    /// Edit the file at `input`, writing the result to the file at `output`
+    ///
+    /// The input is read with a [`BufReader`] and edited with
+    /// [`Editor::edit_buffered`]. The output file is created, or truncated
+    /// if it already exists.
+    ///
+    /// If `input` and `output` are the same file, it is edited as with
+    /// [`Editor::edit_file_in_place`].
+    ///
+    /// # Errors
+    ///
+    /// Failing to open the input is an [`EditError::Io`], and failing to
+    /// create the output is an [`EditError::WriteFailed`]. Otherwise errors
+    /// are the same as [`Editor::edit_buffered`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::error::Error;
+    /// # use std::{env, fs};
+    /// # use linurgy::factory;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let input = env::temp_dir().join("linurgy-edit-file-doc-input.txt");
+    /// # let output = env::temp_dir().join("linurgy-edit-file-doc-output.txt");
+    /// fs::write(&input, "foo\nbar")?;
+    ///
+    /// factory::replacer("-", 1).edit_file(&input, &output)?;
+    ///
+    /// assert_eq!("foo-bar", fs::read_to_string(&output)?);
+    /// # fs::remove_file(&input)?;
+    /// # fs::remove_file(&output)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn edit_file<P, Q>(&self, input: P, output: Q) -> Result<(), EditError>
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        let input = input.as_ref();
+        let output = output.as_ref();
+
+        if let Some(path) = same_file(input, output) {
+            return self.replace_file(&path);
+        }
+
+        let input = File::open(input)?;
+        let output = File::create(output).map_err(EditError::WriteFailed)?;
+        self.edit_file_into(input, output)?;
+
+        Ok(())
+    }
+
+    /// Edit the file at `path`, atomically replacing it with the result
+    ///
+    /// The edited text is written to a temporary file in the same directory,
+    /// which is then renamed over the original. If editing fails, or the
+    /// process is interrupted, the original file is left untouched. The
+    /// replacement keeps the original file's permissions. Each call uses its
+    /// own temporary file, so concurrent edits of the same file don't
+    /// interfere: the last one to finish replaces it.
+    ///
+    /// Symbolic links are followed, so the file they point to is replaced.
+    ///
+    /// # Errors
+    ///
+    /// Failing to open `path` is an [`EditError::Io`], and failing to create
+    /// or rename the temporary file is an [`EditError::WriteFailed`].
+    /// Otherwise errors are the same as [`Editor::edit_buffered`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::error::Error;
+    /// # use std::{env, fs};
+    /// # use linurgy::factory;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let path = env::temp_dir().join("linurgy-edit-file-in-place-doc.txt");
+    /// fs::write(&path, "foo\n\n\nbar")?;
+    ///
+    /// factory::replacer("\n", 3).edit_file_in_place(&path)?;
+    ///
+    /// assert_eq!("foo\nbar", fs::read_to_string(&path)?);
+    /// # fs::remove_file(&path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn edit_file_in_place<P>(&self, path: P) -> Result<(), EditError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = fs::canonicalize(path)?;
+        self.replace_file(&path)
+    }
+
+    /// Edit the file at the canonical `path`, replacing it with a temporary
+    /// file holding the result.
+    fn replace_file(&self, path: &Path) -> Result<(), EditError> {
+        let input = File::open(path)?;
+        let permissions = input.metadata()?.permissions();
+        let (temp, temp_path) = create_temp(path).map_err(EditError::WriteFailed)?;
+
+        let result = self.edit_file_into(input, temp).and_then(|temp| {
+            swap_file(temp, permissions, &temp_path, path).map_err(EditError::WriteFailed)
+        });
+
+        if result.is_err() {
+            // only the file created above is removed, never one belonging to
+            // another edit
+            let _ = fs::remove_file(&temp_path);
+        }
+
+        result
+    }
+
+    /// Edit `input` into `output`, returning the flushed `output`.
+    #[inline]
+    fn edit_file_into(&self, input: File, output: File) -> Result<File, EditError> {
+        let mut input = BufReader::new(input);
+        let mut output = BufWriter::new(output);
+
+        self.edit_buffered(&mut input, &mut output)?;
+
+        output
+            .into_inner()
+            .map_err(|error| EditError::WriteFailed(error.into()))
+    }
+}
+
+/// Rename the edited `temp` file over the original at `path`.
+fn swap_file(
+    temp: File,
+    permissions: Permissions,
+    temp_path: &Path,
+    path: &Path,
+) -> io::Result<()> {
+    temp.set_permissions(permissions)?;
+    // make sure the edit is on disk before it replaces the original
+    temp.sync_all()?;
+    drop(temp);
+
+    fs::rename(temp_path, path)
+}
+
+/// The canonical path of `input`, if `output` refers to the same file.
+fn same_file(input: &Path, output: &Path) -> Option<PathBuf> {
+    let input = fs::canonicalize(input).ok()?;
+    let output = fs::canonicalize(output).ok()?;
+
+    if input == output {
+        Some(input)
+    } else {
+        None
+    }
+}
+
+/// Create a new temporary file to replace `path`, returning it with its
+/// path.
+///
+/// A name which is already taken, such as by a file left behind by another
+/// process, is never opened, and the next one is tried instead.
+fn create_temp(path: &Path) -> io::Result<(File, PathBuf)> {
+    let mut attempts = 0;
+
+    loop {
+        let temp_path = temp_path(path, TEMP_FILES.fetch_add(1, Ordering::Relaxed));
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temp_path)
+        {
+            Ok(temp) => return Ok((temp, temp_path)),
+            Err(ref error)
+                if error.kind() == io::ErrorKind::AlreadyExists && attempts < TEMP_ATTEMPTS =>
+            {
+                attempts += 1;
+            }
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// A hidden sibling of `path`, so renaming it over `path` stays on the same
+/// filesystem, unique to this process and `id`.
+fn temp_path(path: &Path, id: usize) -> PathBuf {
+    let mut name = OsString::from(".");
+    name.push(path.file_name().unwrap_or_else(|| OsStr::new("linurgy")));
+    name.push(format!(".linurgy-{}-{}.tmp", process::id(), id));
+
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn temp_path_is_hidden_sibling() {
+        let path = Path::new("dir").join("file.txt");
+        let expected = format!(".file.txt.linurgy-{}-7.tmp", process::id());
+
+        let temp = temp_path(&path, 7);
+
+        assert_eq!(Some(Path::new("dir")), temp.parent());
+        assert_eq!(Some(OsStr::new(&expected)), temp.file_name());
+    }
+
+    #[test]
+    fn create_temp_skips_taken_names() -> io::Result<()> {
+        let path = env::temp_dir().join("linurgy-create-temp.txt");
+        let taken = temp_path(&path, TEMP_FILES.load(Ordering::Relaxed));
+        fs::write(&taken, "taken")?;
+
+        let (_, temp_path) = create_temp(&path)?;
+
+        assert_ne!(taken, temp_path);
+        assert_eq!("taken", fs::read_to_string(&taken)?);
+
+        fs::remove_file(&taken)?;
+        fs::remove_file(&temp_path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn missing_output_is_not_same_file() {
+        let input = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        let output = Path::new(env!("CARGO_MANIFEST_DIR")).join("linurgy-missing.txt");
+
+        assert_eq!(None, same_file(&input, &output));
+    }
+}
@@ -1,8 +1,12 @@
 /*!
 An easy to use library for manipulating multiple newlines.
 
-Create an [`Editor`] with one of the six [`factory`] functions to suit your line editing needs.
-Or create one directly with [`Editor::new`].
+Create an [`Editor`] with one of the [`factory`] functions to suit your line editing needs.
+Configure one step by step with an [`EditorBuilder`], or create one directly with
+[`Editor::new`]. Chain editors together into a [`Pipeline`] with
+[`Editor::then`], or read a list of them from a file with [`config::parse`]. Bind an editor to
+an input and output stream with [`BufEditor`]. For a one-off edit from strings alone, such as in
+a WebAssembly build, use [`edit`] with a spec like `"replace:2: "`.
 
 # Examples
 
@@ -83,12 +87,95 @@ assert_eq!("foo\n\n---\nbar", from_utf8(&output)?);
 # Ok(())
 # }
 ```
+
+# Features
+
+- `std` (default): edit streams and files with [`Editor::edit_buffered`],
+  [`EditReader`], and [`config`]. Without it linurgy is `no_std`, needs only
+  `alloc` and Rust 1.36, and edits in memory.
+- `tokio`: edit [`tokio`](https://docs.rs/tokio) async streams with
+  [`Editor::edit_buffered_async`].
+- `rayon`: edit large inputs on multiple threads with [`Editor::edit_parallel`].
+- `bytes`: edit [`bytes`](https://docs.rs/bytes) buffers into a `BytesMut`
+  with [`Editor::edit_bytes_buf`].
+- `serde`: implement `Serialize` and `Deserialize` for [`Editor`], [`NewlineType`],
+  [`EditType`], and [`Pipeline`].
 */
+#![cfg_attr(docsrs, feature(doc_cfg))]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std as alloc;
+
+#[cfg(all(feature = "tokio", not(feature = "std")))]
+compile_error!("the `tokio` feature requires the `std` feature");
+#[cfg(all(feature = "rayon", not(feature = "std")))]
+compile_error!("the `rayon` feature requires the `std` feature");
 
+#[cfg(feature = "tokio")]
+mod async_io;
+#[cfg(feature = "std")]
+mod buffer;
+mod builder;
+mod bytes;
+mod const_editor;
 mod editor;
+mod error;
+#[cfg(feature = "std")]
+mod file;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod pipeline;
+mod preview;
+#[cfg(feature = "std")]
+mod reader;
+mod rules;
+mod stats;
+#[cfg(feature = "std")]
+mod stdio;
+#[cfg(feature = "std")]
+mod writer;
 
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod config;
 pub mod factory;
 
+use crate::prelude::*;
+
+/// Items of the standard prelude, which are imported from `alloc` when built
+/// without `std`.
+mod prelude {
+    pub(crate) use alloc::string::{String, ToString};
+    pub(crate) use alloc::vec::Vec;
+    pub(crate) use alloc::{format, vec};
+}
+
+/// Edit the input's newlines with an editor parsed from `spec`
+///
+/// The spec has the form `edit_type:trigger:text`, as parsed by
+/// [`Editor::from_str`](core::str::FromStr::from_str). Useful where only
+/// strings can be passed in, such as from JavaScript to a WebAssembly build,
+/// and available without `std`. To edit more than one input with the same
+/// spec, parse an [`Editor`] once instead.
+///
+/// # Errors
+///
+/// Returns a [`ParseEditorError`] if `spec` is not a valid editor.
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(Ok("foo bar".to_string()), linurgy::edit("foo\n\nbar", "replace:2: "));
+/// assert!(linurgy::edit("foo", "remove:2:").is_err());
+/// ```
+#[inline]
+pub fn edit(input: &str, spec: &str) -> Result<String, ParseEditorError> {
+    spec.parse::<Editor>().map(|editor| editor.edit(input))
+}
+
 /// Line-ending text editor
 ///
 /// This is a text editor that replaces line-endings with a specified string.
@@ -110,7 +197,7 @@ pub mod factory;
 /// # Newline type
 ///
 /// When constructing an editor, you need to specify the type of newline to use.
-/// This can be either [`NewlineType::Lf`] (`\n`) or [`NewlineType::Crlf`]
+/// This is usually either [`NewlineType::Lf`] (`\n`) or [`NewlineType::Crlf`]
 /// (`\r\n`).
 ///
 /// # Factory
@@ -208,20 +295,646 @@ pub mod factory;
 /// # Default
 ///
 /// [`Editor::default`] returns an editor which makes no changes to input text.
+///
+/// # Serde
+///
+/// With the `serde` feature enabled, editors can be serialized and
+/// deserialized. A deserialized editor produces identical output to the
+/// original.
+///
+/// # Ordering
+///
+/// Editors are ordered by their `newlines` trigger, then [`NewlineType`],
+/// then `replace` string. Any remaining ties are broken by the rest of the
+/// editor's options, so editors are only equal when they are identical. This
+/// ordering is stable across versions, so editors can be used as keys in
+/// ordered collections such as [`BTreeSet`](std::collections::BTreeSet).
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Editor {
     replace: String,
-    newlines: u8,
+    newlines: usize,
     line_ending: NewlineType,
+    edit_type: EditType,
+    text: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    trailing_newline: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    open: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    close: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    max_edits: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    skip_edits: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    strip_cr: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    blank_whitespace: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    output_ending: Option<NewlineType>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    skip_empty_end: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    trigger_mode: TriggerMode,
+    #[cfg_attr(feature = "serde", serde(default))]
+    template: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    fallback: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    reversed_crlf: bool,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    lines: LineOptions,
 }
 
-/// The two types of
-/// [newline](https://en.wikipedia.org/wiki/Newline#Issues_with_different_newline_formats).
+/// [`Editor`] options which trim lines and the ends of the input, or append
+/// after its last line.
+///
+/// Serialized as fields of the editor itself.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+struct LineOptions {
+    trim_trailing: bool,
+    trim_leading: bool,
+    trim_leading_newlines: bool,
+    trim_trailing_newlines: bool,
+    append_eof: bool,
+}
+
+/// Line-ending editor with a `'static` replacement, built at compile time
+///
+/// Works like an [`Editor`] made with [`Editor::new`], replacing runs of
+/// exactly `newlines` newlines with the `replace` string. The replacement is
+/// borrowed rather than owned, so [`ConstEditor::new`] is a `const fn` and
+/// editors fixed at compile time can be declared as a `static` or `const`,
+/// without allocating.
+///
+/// In exchange, none of the [`Editor`] options such as
+/// [`wrap`](Editor::wrap) or [`max_edits`](Editor::max_edits) are available.
+/// Convert to an owned editor with [`ConstEditor::to_editor`] to use them,
+/// or for buffered editing.
+///
+/// # Examples
+///
+/// ```rust
+/// # use linurgy::{ConstEditor, NewlineType};
+/// static PARAGRAPHS: ConstEditor = ConstEditor::new("\n---\n", 2, NewlineType::Lf);
+///
+/// assert_eq!("foo\n---\nbar", PARAGRAPHS.edit("foo\n\nbar"));
+/// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConstEditor {
+    replace: &'static str,
+    newlines: usize,
+    line_ending: NewlineType,
+}
+
+/// The types of
+/// [newline](https://en.wikipedia.org/wiki/Newline#Issues_with_different_newline_formats).
+///
+/// As well as the common LF and CRLF line endings, the Unicode line and
+/// paragraph separators can be used. These are found in text from JavaScript
+/// sources and some PDFs. They are multi-byte characters, so editors using
+/// them only work on valid UTF-8 input.
+///
+/// With the `serde` feature enabled, variants are serialized as the strings
+/// `"lf"`, `"crlf"`, `"lineseparator"`, `"paragraphseparator"`, and
+/// `"auto"`.
+///
+/// Newline types are ordered as they are declared: `Lf`, `Crlf`,
+/// `LineSeparator`, `ParagraphSeparator`, then `Auto`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum NewlineType {
     /// Line ending: `\n`
     Lf,
 
     /// Line ending: `\r\n`
     Crlf,
+
+    /// Unicode line separator: `\u{2028}`
+    LineSeparator,
+
+    /// Unicode paragraph separator: `\u{2029}`
+    ParagraphSeparator,
+
+    /// Either `\n` or `\r\n`, matching the style of each input
+    ///
+    /// Every time an editor is used, its input is checked and the editor
+    /// is used as if it were created for [`NewlineType::Lf`] or
+    /// [`NewlineType::Crlf`], as with [`Editor::with_line_ending`]. The
+    /// output follows the predominant style of the input:
+    ///
+    /// - Methods given the whole input, such as [`Editor::edit`], pick CRLF
+    ///   when `\r\n` newlines outnumber bare `\n`, and LF otherwise
+    /// - Streaming methods, such as [`Editor::edit_buffered`], pick the
+    ///   style of the first line
+    ///
+    /// Outside of an edit, such as from [`NewlineType::as_str`], it is the
+    /// same as `\n`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::{factory, NewlineType};
+    /// let editor = factory::appender_with("-", 2, NewlineType::Auto);
+    /// assert_eq!("foo\n\n-bar", editor.edit("foo\n\nbar"));
+    /// assert_eq!("foo\r\n\r\n-bar", editor.edit("foo\r\n\r\nbar"));
+    /// ```
+    Auto,
+}
+
+/// Fluent configuration for an [`Editor`]
+///
+/// Set the text, type of edit, newline trigger and newline type in any order,
+/// then call [`EditorBuilder::build`]. Unset options keep their defaults: an
+/// empty replacement, a trigger of `0`, and [`NewlineType::Lf`], so a new
+/// builder produces [`Editor::default`].
+///
+/// The builder can be reused. The `replace` string is only rebuilt after an
+/// option changes, so [`EditorBuilder::prepare`] it once and use
+/// [`EditorBuilder::build_prepared`] to create many editors cheaply.
+///
+/// # Examples
+///
+/// Insert dashes before every double CRLF newline.
+///
+/// ```rust
+/// # use linurgy::{EditType, EditorBuilder};
+/// let editor = EditorBuilder::new()
+///     .text("---")
+///     .edit_type(EditType::Insert)
+///     .newline_trigger(2)
+///     .newline_crlf()
+///     .build();
+///
+/// assert_eq!("foo---\r\n\r\nbar", editor.edit("foo\r\n\r\nbar"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct EditorBuilder {
+    text: String,
+    edit_type: EditType,
+    newlines: usize,
+    line_ending: NewlineType,
+    trailing_newline: bool,
+    max_edits: Option<usize>,
+    skip_edits: usize,
+    strip_cr: bool,
+    blank_whitespace: bool,
+    trigger_mode: TriggerMode,
+    lines: LineOptions,
+    replace: String,
+    dirty: bool,
+}
+
+/// Streaming editor bound to an input reader and output writer
+///
+/// Holds an [`Editor`] along with borrowed input and output streams, so the
+/// editing state can be owned and passed around as one value. Calling
+/// [`BufEditor::edit`] reads the input to the end, writing edited text to the
+/// output, exactly like [`Editor::edit_buffered`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::error::Error;
+/// # use std::io::Cursor;
+/// # use std::str::from_utf8;
+/// # use linurgy::{BufEditor, NewlineType};
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let mut input = Cursor::new("foo\nbar");
+/// let mut output = Vec::new();
+///
+/// let mut editor = BufEditor::new("-".to_string(), 1, NewlineType::Lf, &mut input, &mut output);
+/// editor.edit()?;
+///
+/// assert_eq!("foo-bar", from_utf8(&output)?);
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Debug)]
+pub struct BufEditor<'a, I, O> {
+    editor: Editor,
+    input: &'a mut I,
+    output: &'a mut O,
+}
+
+/// Reader which edits the newlines of text read through it
+///
+/// Wraps an inner [`BufRead`](std::io::BufRead), so edited text can be
+/// passed to anything expecting a reader. The inner reader is read one line
+/// at a time, as edited text is needed, and produces the same output as
+/// [`Editor::edit_buffered`].
+///
+/// # EOF
+///
+/// A run of newlines which could still meet the trigger is held until the
+/// text following it is read. Newlines at the end of the input are only
+/// produced once the inner reader reaches EOF, along with any trailing
+/// newline from [`Editor::ensure_trailing_newline`] or closing text from
+/// [`Editor::wrap`].
+///
+/// Reading input which is not valid UTF-8 returns an
+/// [`io::ErrorKind::InvalidData`](std::io::ErrorKind::InvalidData) error.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::io::{self, Cursor, Read};
+/// # use linurgy::{factory, EditReader};
+/// # fn main() -> io::Result<()> {
+/// let editor = factory::replacer(",", 1);
+/// let mut reader = EditReader::new(&editor, Cursor::new("foo\nbar\nbaz"));
+///
+/// let mut output = String::new();
+/// reader.read_to_string(&mut output)?;
+///
+/// assert_eq!("foo,bar,baz", output);
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Debug)]
+pub struct EditReader<'a, R> {
+    inner: R,
+    state: editor::BufState<'a>,
+    line_ending: NewlineType,
+
+    /// Last line read from `inner`.
+    line: String,
+
+    /// Edited text, read up to `pos`.
+    edited: String,
+    pos: usize,
+
+    /// Number of lines read from `inner`.
+    lines: usize,
+
+    /// Whether `inner` has reached EOF.
+    finished: bool,
+}
+
+/// Writer which edits the newlines of text written through it
+///
+/// Wraps an inner [`Write`](std::io::Write), so edited text can be written
+/// with the [`write!`] and [`writeln!`] macros. Text is edited one line at a
+/// time and the result written to the inner writer. Newline runs may be
+/// split across any number of writes, and produce the same output as
+/// [`Editor::edit`].
+///
+/// Only complete lines are edited as they are written. A partial line, and
+/// any run of newlines which could still meet the trigger, is held until
+/// more text is written. [`EditWriter::finish`] writes what is held and
+/// returns the inner writer. Dropping the writer also finishes it, ignoring
+/// any errors.
+///
+/// Written text must be valid UTF-8, otherwise an
+/// [`io::ErrorKind::InvalidData`](std::io::ErrorKind::InvalidData) error is
+/// returned.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::io::{self, Write};
+/// # use linurgy::{factory, EditWriter};
+/// # fn main() -> io::Result<()> {
+/// let editor = factory::replacer("\n", 2);
+/// let mut writer = EditWriter::new(&editor, Vec::new());
+///
+/// writeln!(writer, "foo")?;
+/// writeln!(writer)?;
+/// write!(writer, "bar")?;
+///
+/// let output = writer.finish()?;
+/// assert_eq!(b"foo\nbar", &output[..]);
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Debug)]
+pub struct EditWriter<'a, W: std::io::Write> {
+    inner: Option<W>,
+    state: editor::BufState<'a>,
+
+    /// Written bytes which don't yet form a complete line.
+    partial: Vec<u8>,
+
+    /// Edited text waiting to be written to `inner`.
+    edited: String,
+
+    /// Number of complete lines written.
+    lines: usize,
+}
+
+/// Error returned by the buffered editing methods
+///
+/// Separates failures reading the input from failures writing the output,
+/// and reports input which is not valid UTF-8 along with the line it was
+/// found on.
+///
+/// Converts to and from [`io::Error`](std::io::Error), so `?` still works in
+/// functions returning [`io::Result`](std::io::Result).
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::io::Cursor;
+/// # use linurgy::{factory, EditError};
+/// let editor = factory::replacer(" ", 1);
+/// let mut input = Cursor::new(b"foo\nb\xffr\n".to_vec());
+/// let mut output = Vec::new();
+///
+/// match editor.edit_buffered(&mut input, &mut output) {
+///     Err(EditError::InvalidUtf8 { line }) => assert_eq!(2, line),
+///     _ => unreachable!(),
+/// }
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Debug)]
+pub enum EditError {
+    /// Reading the input failed
+    Io(std::io::Error),
+
+    /// The input contained invalid UTF-8
+    InvalidUtf8 {
+        /// Line the invalid UTF-8 was found on, starting at 1
+        line: usize,
+    },
+
+    /// Writing the output failed
+    WriteFailed(std::io::Error),
+}
+
+/// Error parsing an [`Editor`] from a string.
+///
+/// See [`Editor::from_str`](Editor#method.from_str) for the format.
+///
+/// # Examples
+///
+/// ```rust
+/// # use linurgy::{Editor, ParseEditorError};
+/// let res = "prepend:1:-".parse::<Editor>();
+/// assert_eq!(Err(ParseEditorError::InvalidEditType("prepend".to_string())), res);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseEditorError {
+    /// The string is not in the form `edit_type:trigger:text`
+    InvalidFormat,
+
+    /// The edit type is not `append`, `insert`, or `replace`
+    InvalidEditType(String),
+
+    /// The trigger is not a non-negative whole number
+    InvalidTrigger(core::num::ParseIntError),
+}
+
+/// Error reading editors from a config with [`config::parse`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use linurgy::{config, ConfigError, ParseEditorError};
+/// match config::parse("# editors\nappend:2\n".as_bytes()) {
+///     Err(ConfigError::InvalidEditor { line, error }) => {
+///         assert_eq!(2, line);
+///         assert_eq!(ParseEditorError::InvalidFormat, error);
+///     }
+///     res => panic!("unexpected result: {:?}", res),
+/// }
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Debug)]
+pub enum ConfigError {
+    /// Reading the config failed
+    Io(std::io::Error),
+
+    /// A line of the config is not a valid editor
+    InvalidEditor {
+        /// Line the editor is on, starting at 1
+        line: usize,
+
+        /// Why the editor is invalid
+        error: ParseEditorError,
+    },
+}
+
+/// Error validating the options of an [`EditorBuilder`].
+///
+/// See [`EditorBuilder::try_build`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use linurgy::{BuildError, EditorBuilder};
+/// let res = EditorBuilder::new().text("-").try_build();
+/// assert_eq!(Err(BuildError::ZeroTrigger), res);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildError {
+    /// The newline trigger is `0`, so it can never be met
+    ZeroTrigger,
+
+    /// The text contains a `\r` which is not part of a `\r\n`
+    LoneCarriageReturn,
+
+    /// The text contains newlines of a different type to the editor's
+    ///
+    /// A [`NewlineType::Crlf`] editor's text contains a bare `\n`, or a
+    /// [`NewlineType::Lf`] editor's text contains a `\r\n`.
+    MixedNewlines,
+
+    /// The range of newlines which trigger an edit is empty, as its start is
+    /// after its end
+    EmptyRange,
+}
+
+/// Which action an [`Editor`] takes when its trigger is met.
+///
+/// Editors built by the [`factory`] functions record the type of edit they
+/// make. Editors created with [`Editor::new`] are [`EditType::Replace`].
+///
+/// With the `serde` feature enabled, variants are serialized as the strings
+/// `"append"`, `"insert"`, and `"replace"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum EditType {
+    /// New text appears after newlines
+    Append,
+
+    /// New text appears before newlines
+    Insert,
+
+    /// New text appears instead of newlines
+    Replace,
+}
+
+/// How the length of a run of newlines is compared to an [`Editor`]'s
+/// trigger.
+///
+/// Set with [`Editor::trigger_mode`]. Editors built by the [`factory`]
+/// functions are [`TriggerMode::Exactly`], except
+/// [`factory::replacer_at_least`] and [`factory::replacer_at_most`]. A
+/// trigger of `0` is never met in any mode.
+///
+/// With the `serde` feature enabled, variants are serialized as the strings
+/// `"exactly"`, `"at_least"`, and `"at_most"`, and
+/// [`TriggerMode::Between`] as a map such as `{"between": 3}`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use linurgy::{factory, TriggerMode};
+/// let input = "a\nb\n\nc\n\n\nd";
+///
+/// let editor = factory::replacer("-", 2);
+/// assert_eq!("a\nb-c-\nd", editor.edit(input));
+///
+/// let editor = editor.trigger_mode(TriggerMode::AtLeast);
+/// assert_eq!("a\nb-c-d", editor.edit(input));
+///
+/// let editor = editor.trigger_mode(TriggerMode::AtMost);
+/// assert_eq!("a-b-c\n\n\nd", editor.edit(input));
+///
+/// let editor = editor.trigger_mode(TriggerMode::Between(2));
+/// assert_eq!("a\nb-c\n\n\nd", editor.edit(input));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TriggerMode {
+    /// Every `trigger` newlines in a run are replaced, and any newlines left
+    /// over are kept
+    Exactly,
+
+    /// A run of at least `trigger` newlines is replaced as a whole
+    AtLeast,
+
+    /// A run of at most `trigger` newlines is replaced as a whole, and
+    /// longer runs are kept
+    AtMost,
+
+    /// A run of between the given number and `trigger` newlines is replaced
+    /// as a whole, and shorter or longer runs are kept
+    ///
+    /// No run is replaced if the given number is more than `trigger`.
+    Between(usize),
+}
+
+/// A sequence of [`Editor`]s applied one after another
+///
+/// Each editor runs on the output of the one before it, in the order they
+/// were added. The first editor sees the original input and the last editor
+/// produces the final output. An empty pipeline makes no changes.
+///
+/// Create a pipeline by chaining editors with [`Editor::then`], or from a
+/// [`Vec`] of editors with [`Pipeline::new`].
+///
+/// # Examples
+///
+/// Collapse triple newlines to double, then append a marker after each
+/// double newline.
+///
+/// ```rust
+/// # use linurgy::factory;
+/// let pipeline = factory::replacer("\n\n", 3).then(factory::appender("---", 2));
+/// let output = pipeline.edit("foo\n\n\nbar\n\nbaz");
+/// assert_eq!("foo\n\n---bar\n\n---baz", output);
+/// ```
+///
+/// # Buffered
+///
+/// [`Pipeline::edit_buffered`] streams each line through every stage in
+/// turn. Only text a stage cannot yet decide on (a partial line or a pending
+/// run of newlines) is held between reads.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pipeline {
+    editors: Vec<Editor>,
+}
+
+/// Editor with a replacement for each length of run of newlines
+///
+/// Where an [`Editor`] has one trigger, a rule editor has any number of
+/// rules, each mapping a run length to its replacement. Every run of
+/// newlines is matched as a whole: once it ends, its length is looked up,
+/// and the run is replaced if there is a rule for it. Runs of any other
+/// length are kept as they are. A run at the end of the input is matched
+/// the same way.
+///
+/// Unlike an [`Editor`], a run longer than a rule is never split into
+/// several matches, so a rule for `1` only replaces single newlines.
+///
+/// # Examples
+///
+/// Single newlines become spaces and double newlines paragraph breaks.
+///
+/// ```rust
+/// # use linurgy::{NewlineType, RuleEditor};
+/// let editor = RuleEditor::new(vec![(1, " "), (2, "\n\n")], NewlineType::Lf);
+/// let output = editor.edit("foo\nbar\n\nbaz\n\n\nqux\n");
+/// assert_eq!("foo bar\n\nbaz\n\n\nqux ", output);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RuleEditor {
+    rules: alloc::collections::BTreeMap<usize, String>,
+    line_ending: NewlineType,
+}
+
+/// Statistics about the newlines in some text, from [`Editor::analyze`].
+///
+/// Consecutive newlines form a run. Counting how many runs of each length a
+/// text contains helps choose an editor's trigger.
+///
+/// # Examples
+///
+/// ```rust
+/// # use linurgy::factory;
+/// let stats = factory::replacer("", 1).analyze("foo\nbar\n\nbaz\n\n\n");
+/// assert_eq!(1, stats.runs_of(1));
+/// assert_eq!(1, stats.runs_of(2));
+/// assert_eq!(1, stats.runs_of(3));
+/// assert_eq!(6, stats.newlines());
+/// assert!(stats.ends_with_newline());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NewlineStats {
+    runs: alloc::collections::BTreeMap<usize, usize>,
+    newlines: usize,
+    trailing_newline: bool,
+}
+
+/// A replacement an [`Editor`] would make, from [`Editor::preview`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use linurgy::{factory, EditSpan};
+/// let spans = factory::replacer(" ", 2).preview("foo\n\nbar");
+/// let span = EditSpan {
+///     byte_offset: 3,
+///     matched_len: 2,
+///     replacement: " ".to_string(),
+/// };
+/// assert_eq!(vec![span], spans);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EditSpan {
+    /// Byte offset in the input of the first matched newline
+    pub byte_offset: usize,
+
+    /// Length in bytes of the matched newlines in the input
+    pub matched_len: usize,
+
+    /// Text the matched newlines are replaced with
+    pub replacement: String,
 }
@@ -0,0 +1,207 @@
+use std::cmp;
+
+use rayon::prelude::*;
+
+use crate::editor::Scan;
+use crate::{Editor, NewlineType};
+
+/// Smallest chunk of input edited on its own thread.
+const MIN_CHUNK: usize = 64 * 1024;
+
+impl Editor {
+    /// Edit the input's newlines using multiple threads
+    ///
+    /// Produces the same output as [`Editor::edit`]. The input is split into
+    /// chunks which are edited in parallel on the [`rayon`] thread pool, then
+    /// joined. Chunks are only split on characters which cannot be part of a
+    /// newline, so runs of newlines are never divided between threads.
+    ///
+    /// Splitting and joining the chunks has a cost of its own, so this is
+    /// only faster for large inputs of at least a few megabytes. Inputs
+    /// smaller than one chunk per thread (64 KiB each) are edited on the
+    /// current thread. Editors with [`Editor::max_edits`] or
+    /// [`Editor::skip_edits`], or made by
+    /// [`template_replacer`](crate::factory::template_replacer),
+    /// count every edit in order, so they are also edited on the current
+    /// thread.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let editor = factory::replacer("\n", 2);
+    /// let input = "foo\n\nbar\n".repeat(100_000);
+    /// assert_eq!(editor.edit(&input), editor.edit_parallel(&input));
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+    pub fn edit_parallel(&self, input: &str) -> String {
+        let size = cmp::max(MIN_CHUNK, input.len() / rayon::current_num_threads());
+        self.edit_chunked(input, size)
+    }
+
+    /// Edit the input in parallel chunks of at least `size` bytes.
+    pub(crate) fn edit_chunked(&self, input: &str, size: usize) -> String {
+        if self.line_ending == NewlineType::Auto {
+            return self.resolve(input).edit_chunked(input, size);
+        }
+
+        if self.max_edits.is_some()
+            || self.skip_edits > 0
+            || self.template
+            || self.reversed_crlf
+            || self.lines_affect_scan()
+        {
+            return self.edit(input);
+        }
+
+        let chunks = self.chunks(input, size);
+        if chunks.len() < 2 {
+            return self.edit(input);
+        }
+
+        let last = chunks.len() - 1;
+        let edited: Vec<String> = chunks
+            .par_iter()
+            .enumerate()
+            .map(|(index, chunk)| {
+                let mut output = String::with_capacity(chunk.len() + self.replace.len());
+                let mut replace = self.replace.as_str();
+                let mut scan = Scan::new(self);
+                if index > 0 {
+                    scan.resume();
+                }
+
+                scan.push_str(self, chunk, &mut output, &mut replace);
+
+                // only the last chunk ends the input
+                if index == last {
+                    scan.finish(self, &mut output, &mut replace);
+                } else {
+                    scan.end_chunk(self, &mut output, &mut replace);
+                }
+                output
+            })
+            .collect();
+
+        let len = edited.iter().map(String::len).sum::<usize>() + self.line_ending.len();
+        let mut output = String::with_capacity(len);
+        for chunk in &edited {
+            output.push_str(chunk);
+        }
+
+        if self.trailing_newline {
+            self.end_with_newline(&mut output, 0);
+        }
+
+        output
+    }
+
+    /// Split the input into chunks of at least `size` bytes.
+    ///
+    /// Every chunk after the first starts with a character which is not part
+    /// of a newline or blank line, which ends any run of newlines before it.
+    fn chunks<'a>(&self, input: &'a str, size: usize) -> Vec<&'a str> {
+        let end_char = self.line_ending.end_char();
+        let mut chunks = Vec::new();
+        let mut rest = input;
+
+        while rest.len() > size {
+            let mut start = size;
+            while !rest.is_char_boundary(start) {
+                start += 1;
+            }
+
+            // a `\r` may start a CRLF newline, and spaces or tabs a blank line
+            let split = rest[start..]
+                .char_indices()
+                .find(|&(_, c)| c != end_char && c != '\r' && c != ' ' && c != '\t')
+                .map(|(index, _)| start + index);
+
+            match split {
+                Some(split) => {
+                    let (chunk, next) = rest.split_at(split);
+                    chunks.push(chunk);
+                    rest = next;
+                }
+                None => break,
+            }
+        }
+
+        chunks.push(rest);
+        chunks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{factory, NewlineType};
+
+    const INPUT: &str = "foo\n\nbar\n \n\nbaz\r\n\t\r\n\r\nqux\r\n\rquux\u{2028}  \u{2028}é\n";
+
+    fn assert_same_as_edit(editor: &Editor) {
+        let input = INPUT.repeat(50);
+        for size in 1..INPUT.len() * 2 {
+            assert_eq!(
+                editor.edit(&input),
+                editor.edit_chunked(&input, size),
+                "\neditor: {}\nsize: {}\n",
+                editor,
+                size
+            );
+        }
+    }
+
+    #[test]
+    fn same_as_edit_lf() {
+        assert_same_as_edit(&factory::replacer("-", 1));
+        assert_same_as_edit(&factory::appender("---", 2));
+        assert_same_as_edit(&factory::inserter("\n", 3));
+    }
+
+    #[test]
+    fn same_as_edit_crlf() {
+        assert_same_as_edit(&factory::replacer_crlf("-", 1));
+        assert_same_as_edit(&factory::appender_crlf("---", 2));
+        assert_same_as_edit(&factory::replacer("-", 2).strip_cr(true));
+    }
+
+    #[test]
+    fn same_as_edit_separators() {
+        assert_same_as_edit(&factory::replacer_with("\n", 2, NewlineType::LineSeparator));
+    }
+
+    #[test]
+    fn same_as_edit_with_options() {
+        assert_same_as_edit(&factory::wrap("<p>", "</p>", 2).ensure_trailing_newline(true));
+        assert_same_as_edit(&factory::replacer("-", 1).max_edits(Some(3)));
+        assert_same_as_edit(&factory::replacer("-", 1).skip_edits(3));
+        assert_same_as_edit(&factory::replacer("-", 3).treat_whitespace_lines_as_blank(true));
+        assert_same_as_edit(&factory::line_prefixer("> "));
+        assert_same_as_edit(&factory::line_suffixer(";"));
+        assert_same_as_edit(&factory::replacer_at_least("-", 2));
+        assert_same_as_edit(&factory::replacer_at_most("-", 1).wrap("<", ">"));
+    }
+
+    #[test]
+    fn chunks_start_outside_newlines() {
+        let editor = factory::replacer_crlf("-", 1);
+        let input = "a\r\n\r\nb\r\nc";
+
+        let chunks = editor.chunks(input, 1);
+
+        assert_eq!(vec!["a\r\n\r\n", "b\r\n", "c"], chunks);
+    }
+
+    #[test]
+    fn all_newlines_one_chunk() {
+        let editor = factory::replacer("-", 2);
+        assert_eq!(vec!["a\n\n\n\n"], editor.chunks("a\n\n\n\n", 1));
+    }
+
+    #[test]
+    fn empty_input() {
+        let editor = factory::wrap("(", ")", 1);
+        assert_eq!("", editor.edit_parallel(""));
+    }
+}
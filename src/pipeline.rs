@@ -0,0 +1,292 @@
+use core::mem;
+#[cfg(feature = "std")]
+use std::io::{BufRead, Write};
+
+#[cfg(feature = "std")]
+use crate::editor::{read_line, write_str, BufState, BUFSIZE};
+use crate::prelude::*;
+#[cfg(feature = "std")]
+use crate::{EditError, NewlineType};
+use crate::{Editor, Pipeline};
+
+impl Pipeline {
+    /// Create a new pipeline
+    ///
+    /// `editors` are applied in order: the first editor receives the input,
+    /// each following editor receives the output of the one before.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::{factory, Pipeline};
+    /// let pipeline = Pipeline::new(vec![
+    ///     factory::replacer("\n", 2),
+    ///     factory::appender("-", 1),
+    /// ]);
+    /// let output = pipeline.edit("foo\n\nbar");
+    /// assert_eq!("foo\n-bar", output);
+    /// ```
+    #[inline]
+    pub fn new(editors: Vec<Editor>) -> Self {
+        Pipeline { editors }
+    }
+
+    /// Add another editor to the end of the pipeline
+    #[inline]
+    pub fn then(mut self, next: Editor) -> Self {
+        self.editors.push(next);
+        self
+    }
+
+    /// The editors in this pipeline, in the order they are applied
+    #[inline]
+    pub fn editors(&self) -> &[Editor] {
+        &self.editors
+    }
+
+    /// Edit the input's newlines with each editor in turn
+    ///
+    /// Produces the same [`String`] as calling [`Editor::edit`] on each
+    /// editor's output, but only allocates for the final output and a single
+    /// intermediate buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let pipeline = factory::replacer("\n\n", 3).then(factory::appender("---", 2));
+    /// let output = pipeline.edit("foo\n\n\nbar");
+    /// assert_eq!("foo\n\n---bar", output);
+    /// ```
+    pub fn edit(&self, input: &str) -> String {
+        let (first, rest) = match self.editors.split_first() {
+            Some(editors) => editors,
+            None => return String::from(input),
+        };
+
+        let mut output = first.edit(input);
+        let mut scratch = String::new();
+
+        for editor in rest {
+            scratch.clear();
+            editor.edit_into(&output, &mut scratch);
+            mem::swap(&mut output, &mut scratch);
+        }
+
+        output
+    }
+
+    /// Edit the input buffer's newlines with each editor in turn, into the
+    /// output writer
+    ///
+    /// Input types must implement [`BufRead`].
+    /// Output types must implement [`Write`].
+    ///
+    /// Each line read from `input` is passed through every stage before the
+    /// next line is read, so the whole document is never held in memory.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::error::Error;
+    /// # use std::io::Cursor;
+    /// # use std::str::from_utf8;
+    /// # use linurgy::factory;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let pipeline = factory::replacer("\n\n", 3).then(factory::appender("---", 2));
+    /// let mut input = Cursor::new("foo\n\n\nbar");
+    /// let mut output = Vec::new();
+    /// pipeline.edit_buffered(&mut input, &mut output)?;
+    /// assert_eq!("foo\n\n---bar", from_utf8(&output)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn edit_buffered<I, O>(&self, input: &mut I, output: &mut O) -> Result<(), EditError>
+    where
+        I: BufRead,
+        O: Write,
+    {
+        let mut stages: Vec<Stage> = self.editors.iter().map(Stage::new).collect();
+        let mut chunk = String::with_capacity(BUFSIZE);
+        let mut scratch = String::with_capacity(BUFSIZE);
+
+        let mut lines = 0;
+
+        let mut edited = String::with_capacity(BUFSIZE);
+
+        // each stage splits chunks into its own lines
+        while read_line(input, &mut chunk, &mut lines, NewlineType::Lf)? {
+            for stage in stages.iter_mut() {
+                scratch.clear();
+                stage.push(&chunk, &mut scratch);
+                mem::swap(&mut chunk, &mut scratch);
+            }
+
+            // edited chunks are collected to make fewer, larger writes
+            edited.push_str(&chunk);
+            if edited.len() >= BUFSIZE {
+                write_str(output, &edited)?;
+                edited.clear();
+            }
+        }
+
+        write_str(output, &edited)?;
+
+        // each stage flushes into the next, in order
+        chunk.clear();
+        for stage in stages.iter_mut() {
+            scratch.clear();
+            stage.push(&chunk, &mut scratch);
+            stage.finish(&mut scratch);
+            mem::swap(&mut chunk, &mut scratch);
+        }
+
+        write_str(output, &chunk)?;
+
+        Ok(())
+    }
+}
+
+impl From<Vec<Editor>> for Pipeline {
+    #[inline]
+    fn from(editors: Vec<Editor>) -> Self {
+        Pipeline::new(editors)
+    }
+}
+
+/// A single editor in a buffered pipeline.
+///
+/// Output from the previous stage can end part way through a line, so it is
+/// held until the rest of the line arrives.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+struct Stage<'a> {
+    state: BufState<'a>,
+    partial: String,
+}
+
+#[cfg(feature = "std")]
+impl<'a> Stage<'a> {
+    #[inline]
+    fn new(editor: &'a Editor) -> Self {
+        Stage {
+            state: BufState::new(editor),
+            partial: String::new(),
+        }
+    }
+
+    /// Edit every complete line in `chunk`, pushing the result onto `output`.
+    #[inline]
+    fn push(&mut self, chunk: &str, output: &mut String) {
+        self.partial.push_str(chunk);
+
+        let line_end = self.state.line_end();
+        let end = match self.partial.rfind(line_end) {
+            Some(index) => index + line_end.len_utf8(),
+            None => return,
+        };
+
+        let mut lines = &self.partial[..end];
+        while let Some(index) = lines.find(line_end) {
+            let (line, rest) = lines.split_at(index + line_end.len_utf8());
+            self.state.push_line(line, output);
+            lines = rest;
+        }
+
+        self.partial.drain(..end);
+    }
+
+    /// Edit any remaining partial line and trailing newlines.
+    #[inline]
+    fn finish(&mut self, output: &mut String) {
+        if !self.partial.is_empty() {
+            self.state.push_line(&self.partial, output);
+            self.partial.clear();
+        }
+        self.state.finish(output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::factory;
+    use std::io::BufReader;
+
+    #[test]
+    fn empty_pipeline_does_nothing() {
+        let input = "foo\n\nbar\r\nbaz\n";
+        assert_eq!(input, Pipeline::default().edit(input));
+        assert_eq!(input, edit_buffered(&Pipeline::default(), input));
+    }
+
+    #[test]
+    fn single_editor_same_as_editor() {
+        let input = "foo\nbar\n\nbaz\n";
+        let editor = factory::inserter("-", 1);
+        let pipeline = Pipeline::new(vec![editor.clone()]);
+        assert_eq!(editor.edit(input), pipeline.edit(input));
+        assert_eq!(editor.edit(input), edit_buffered(&pipeline, input));
+    }
+
+    #[test]
+    fn stages_run_in_order() {
+        let input = "foo\n\n\nbar\n\nbaz\n\n\n";
+        let first = factory::replacer("\n\n", 3);
+        let second = factory::appender("---", 2);
+        let expected = second.edit(first.edit(input));
+
+        let pipeline = first.then(second);
+
+        assert_eq!(expected, pipeline.edit(input));
+        assert_eq!(expected, edit_buffered(&pipeline, input));
+    }
+
+    #[test]
+    fn mixed_newline_types() {
+        let input = "foo\u{2029}bar\nbaz\u{2029}\u{2029}qux\n";
+        let first = Editor::new("\n".to_string(), 1, NewlineType::ParagraphSeparator);
+        let second = factory::replacer(" ", 1);
+        let expected = second.edit(first.edit(input));
+        assert_eq!("foo bar baz  qux ", expected);
+
+        let pipeline = first.then(second);
+
+        assert_eq!(expected, pipeline.edit(input));
+        assert_eq!(expected, edit_buffered(&pipeline, input));
+    }
+
+    #[test]
+    fn buffered_partial_lines_between_stages() {
+        let input = "foo\nbar\nbaz\n\nqux";
+        let first = factory::replacer("-", 1);
+        let second = factory::replacer("+", 1);
+        let third = factory::appender("\n", 1);
+        let expected = third.edit(second.edit(first.edit(input)));
+
+        let pipeline = first.then(second).then(third);
+
+        assert_eq!(expected, edit_buffered(&pipeline, input));
+    }
+
+    #[test]
+    fn buffered_trailing_newlines_flow_through_stages() {
+        let input = "foo\r\n\r\n\r\n";
+        let first = factory::replacer_crlf("\r\n\r\n", 3);
+        let second = factory::inserter_crlf("*", 2);
+        let expected = second.edit(first.edit(input));
+
+        let pipeline = first.then(second);
+
+        assert_eq!(expected, edit_buffered(&pipeline, input));
+    }
+
+    fn edit_buffered(pipeline: &Pipeline, input: &str) -> String {
+        let mut input = BufReader::new(input.as_bytes());
+        let mut output = Vec::new();
+        pipeline.edit_buffered(&mut input, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+}
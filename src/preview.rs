@@ -0,0 +1,193 @@
+use core::ops::Range;
+
+use crate::editor::{Output, Replacement};
+use crate::prelude::*;
+use crate::{EditSpan, Editor};
+
+impl Editor {
+    /// Find where the trigger is met in the input, without editing it
+    ///
+    /// Returns a span for each run of newlines [`Editor::edit`] would
+    /// replace, in order, found by the same scan of the input. Runs which are skipped or past the
+    /// [`max_edits`](Editor::max_edits) limit are left out, so the number of
+    /// spans is the number of edits. A no-op editor returns no spans.
+    ///
+    /// Only replacements are reported. Other changes an editor can make are
+    /// not: [wrapping](Editor::wrap) the text, converting newlines to another
+    /// type, removing whitespace lines or
+    /// [leading](Editor::trim_leading_newlines) and
+    /// [trailing](Editor::trim_trailing_newlines) newlines, or replacing
+    /// shorter runs with a [`fallback`](Editor::fallback). With
+    /// [`ensure_trailing_newline`](Editor::ensure_trailing_newline), newlines
+    /// at the end of a span's replacement may also be trimmed if it ends the
+    /// output.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let input = "foo\n\nbar\r\n\r\nbaz";
+    /// let spans = factory::replacer_crlf(" ", 2).preview(input);
+    ///
+    /// assert_eq!(2, spans.len());
+    /// assert_eq!("\r\n\r\n", &input[spans[1].byte_offset..][..spans[1].matched_len]);
+    /// ```
+    pub fn preview(&self, input: &str) -> Vec<EditSpan> {
+        let mut spans = Vec::new();
+        let mut output = String::with_capacity(input.len());
+        self.edit_into_spans(input, &mut output, Some(&mut spans));
+
+        spans
+    }
+}
+
+/// Replacement which records the span of each match it replaces.
+pub(crate) struct Spans<'s, R> {
+    replace: R,
+    spans: &'s mut Vec<EditSpan>,
+}
+
+impl<'s, R> Spans<'s, R> {
+    #[inline]
+    pub(crate) fn new(replace: R, spans: &'s mut Vec<EditSpan>) -> Self {
+        Spans { replace, spans }
+    }
+}
+
+impl<'s, R> Replacement for Spans<'s, R>
+where
+    R: Replacement,
+{
+    #[inline]
+    fn push_to<O>(&mut self, output: &mut O, index: usize, newlines: usize, matched: Range<usize>)
+    where
+        O: Output,
+    {
+        let start = output.len();
+        self.replace
+            .push_to(output, index, newlines, matched.clone());
+
+        self.spans.push(EditSpan {
+            byte_offset: matched.start,
+            matched_len: matched.end - matched.start,
+            replacement: String::from(&output.as_str()[start..]),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{factory, NewlineType};
+
+    fn span(byte_offset: usize, matched_len: usize, replacement: &str) -> EditSpan {
+        EditSpan {
+            byte_offset,
+            matched_len,
+            replacement: replacement.to_string(),
+        }
+    }
+
+    #[test]
+    fn offsets() {
+        let spans = factory::replacer("-", 2).preview("a\n\nbb\nccc\n\n\n\nd\n\n");
+
+        assert_eq!(
+            vec![
+                span(1, 2, "-"),
+                span(9, 2, "-"),
+                span(11, 2, "-"),
+                span(14, 2, "-"),
+            ],
+            spans
+        );
+    }
+
+    #[test]
+    fn run_longer_than_trigger() {
+        let spans = factory::appender("-", 2).preview("a\n\n\nb");
+        assert_eq!(vec![span(1, 2, "\n\n-")], spans);
+    }
+
+    #[test]
+    fn crlf_includes_cr() {
+        let spans = factory::inserter_crlf("-", 1).preview("a\r\nb\nc\rd");
+        assert_eq!(vec![span(1, 2, "-\r\n"), span(4, 1, "-\r\n")], spans);
+    }
+
+    #[test]
+    fn reversed_crlf_includes_cr() {
+        let editor = factory::replacer("-", 1).reversed_crlf(true);
+        assert_eq!(vec![span(1, 2, "-")], editor.preview("a\n\rb"));
+    }
+
+    #[test]
+    fn trailing_newlines_trimmed() {
+        let editor = factory::replacer("-", 1).trim_trailing_newlines(true);
+        let spans = editor.preview("a\n\nb\n\n");
+        assert_eq!(vec![span(1, 1, "-"), span(2, 1, "-")], spans);
+    }
+
+    #[test]
+    fn separators() {
+        let editor = factory::replacer_with(" ", 2, NewlineType::ParagraphSeparator);
+        let spans = editor.preview("é\u{2029}\u{2029}x");
+        assert_eq!(vec![span(2, 6, " ")], spans);
+    }
+
+    #[test]
+    fn count_matches_edit() {
+        let input = "a\n\nb\n\nc\n\nd\n\ne\n\n";
+        let editor = factory::replacer("-", 2).skip_edits(1).max_edits(Some(2));
+
+        let spans = editor.preview(input);
+
+        assert_eq!(vec![span(4, 2, "-"), span(7, 2, "-")], spans);
+        assert_eq!(editor.edit_counted(input).1, spans.len());
+    }
+
+    #[test]
+    fn trailing_newlines() {
+        let editor = factory::replacer("\n\n", 2).ensure_trailing_newline(true);
+        assert_eq!(vec![span(3, 2, "\n\n")], editor.preview("foo\n\n"));
+    }
+
+    #[test]
+    fn whitespace_lines() {
+        let editor = factory::replacer("-", 2).treat_whitespace_lines_as_blank(true);
+        assert_eq!(vec![span(1, 4, "-")], editor.preview("a\n \t\nb"));
+    }
+
+    #[test]
+    fn at_least_covers_whole_run() {
+        let editor = factory::replacer_at_least("-", 2).treat_whitespace_lines_as_blank(true);
+        let spans = editor.preview("a\nb\n \n\nc\n\n");
+        assert_eq!(vec![span(3, 4, "-"), span(8, 2, "-")], spans);
+    }
+
+    #[test]
+    fn at_most_skips_long_runs() {
+        let spans = factory::replacer_at_most("-", 2).preview("a\nb\n\n\nc\n");
+        assert_eq!(vec![span(1, 1, "-"), span(7, 1, "-")], spans);
+    }
+
+    #[test]
+    fn auto() {
+        let editor = factory::replacer_with("-", 1, NewlineType::Auto);
+        assert_eq!(vec![span(1, 2, "-")], editor.preview("a\r\nb"));
+    }
+
+    #[test]
+    fn noop_editor_is_empty() {
+        assert!(Editor::default().preview("a\n\n\nb").is_empty());
+        assert!(factory::appender("", 1).preview("a\n\n\nb").is_empty());
+        assert!(factory::replacer("-", 3).preview("a\n\nb").is_empty());
+    }
+
+    #[test]
+    fn zero_trigger_is_empty() {
+        let editor = factory::replacer_at_least("-", 0).trim_trailing_whitespace(true);
+        assert!(editor.preview("\n\r \t").is_empty());
+        assert_eq!(0, editor.edit_counted("\n\r \t").1);
+    }
+}
@@ -0,0 +1,199 @@
+use std::cmp;
+use std::io::{self, BufRead, Read};
+
+use crate::editor::{read_line, BufState};
+use crate::{EditReader, Editor};
+
+impl<'a, R> EditReader<'a, R>
+where
+    R: BufRead,
+{
+    /// Create a reader which edits text read from `inner` with `editor`
+    #[inline]
+    pub fn new(editor: &'a Editor, inner: R) -> Self {
+        EditReader {
+            inner,
+            state: BufState::new(editor),
+            line_ending: editor.line_ending,
+            line: String::new(),
+            edited: String::new(),
+            pos: 0,
+            lines: 0,
+            finished: false,
+        }
+    }
+
+    /// The inner reader
+    #[inline]
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// The inner reader
+    ///
+    /// Reading from it directly skips editing that text, and may split a
+    /// line which has been partly read.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwrap the inner reader
+    ///
+    /// Edited text which has not been read yet is lost.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<'a, R> Read for EditReader<'a, R>
+where
+    R: BufRead,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = {
+            let available = self.fill_buf()?;
+            let len = cmp::min(buf.len(), available.len());
+            buf[..len].copy_from_slice(&available[..len]);
+            len
+        };
+
+        self.consume(len);
+
+        Ok(len)
+    }
+}
+
+impl<'a, R> BufRead for EditReader<'a, R>
+where
+    R: BufRead,
+{
+    /// Edited text which has not been read yet
+    ///
+    /// Lines are read from the inner reader until some edited text is
+    /// available. Empty only at EOF.
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        while self.pos == self.edited.len() && !self.finished {
+            self.edited.clear();
+            self.pos = 0;
+
+            if read_line(
+                &mut self.inner,
+                &mut self.line,
+                &mut self.lines,
+                self.line_ending,
+            )? {
+                self.state.push_line(&self.line, &mut self.edited);
+            } else {
+                self.state.finish(&mut self.edited);
+                self.finished = true;
+            }
+        }
+
+        Ok(&self.edited.as_bytes()[self.pos..])
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.pos = cmp::min(self.pos + amt, self.edited.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{factory, NewlineType};
+    use std::io::Cursor;
+
+    const INPUT: &str = "foo\n\nbar\n\n\nbaz\nqux\n\n";
+
+    /// Read all of `input` through an `EditReader`, `size` bytes at a time.
+    fn read_chunked(editor: &Editor, input: &str, size: usize) -> String {
+        let mut reader = EditReader::new(editor, Cursor::new(input));
+        let mut output = Vec::new();
+        let mut buf = vec![0; size];
+
+        loop {
+            match reader.read(&mut buf).unwrap() {
+                0 => break,
+                len => output.extend_from_slice(&buf[..len]),
+            }
+        }
+
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn same_output_as_edit() {
+        let editor = factory::appender("---", 2);
+        assert_eq!(editor.edit(INPUT), read_chunked(&editor, INPUT, 1024));
+    }
+
+    #[test]
+    fn one_byte_at_a_time() {
+        let editor = factory::replacer("-", 2);
+        assert_eq!(editor.edit(INPUT), read_chunked(&editor, INPUT, 1));
+    }
+
+    #[test]
+    fn one_byte_at_a_time_crlf() {
+        let input = "foo\r\n\r\nbar\r\n\r\n\r\nbaz\rqux\r";
+        let editor = factory::inserter_crlf(";", 2);
+        assert_eq!(editor.edit(input), read_chunked(&editor, input, 1));
+    }
+
+    #[test]
+    fn one_byte_at_a_time_separators() {
+        let input = "foo\u{2028}\u{2028}bar\u{2028}baz";
+        let editor = factory::replacer_with("\n", 2, NewlineType::LineSeparator);
+        assert_eq!(editor.edit(input), read_chunked(&editor, input, 1));
+    }
+
+    #[test]
+    fn trailing_newlines_at_eof() {
+        let editor = factory::wrap("<p>", "</p>", 2).ensure_trailing_newline(true);
+        let input = "foo\n\nbar\n";
+        assert_eq!(editor.edit(input), read_chunked(&editor, input, 1));
+        assert_eq!(
+            "<p>foo</p>\n\n<p>bar\n</p>\n",
+            read_chunked(&editor, input, 1)
+        );
+    }
+
+    #[test]
+    fn lines_are_edited() {
+        let editor = factory::inserter(";", 1);
+        let reader = EditReader::new(&editor, Cursor::new("foo\nbar\nbaz"));
+
+        let lines: Vec<String> = reader.lines().map(Result::unwrap).collect();
+
+        assert_eq!(vec!["foo;", "bar;", "baz"], lines);
+    }
+
+    #[test]
+    fn reads_lazily() {
+        let editor = factory::replacer("-", 1);
+        let mut reader = EditReader::new(&editor, Cursor::new("foo\nbar\nbaz"));
+
+        assert_eq!(b"foo-", reader.fill_buf().unwrap());
+        assert_eq!(4, reader.get_ref().position());
+    }
+
+    #[test]
+    fn invalid_utf8_is_invalid_data() {
+        let editor = factory::replacer(" ", 1);
+        let mut reader = EditReader::new(&editor, Cursor::new(b"foo\nb\xffr\n".to_vec()));
+
+        let error = reader.read_to_end(&mut Vec::new()).unwrap_err();
+
+        assert_eq!(io::ErrorKind::InvalidData, error.kind());
+        assert!(error.to_string().contains("line 2"), "{}", error);
+    }
+
+    #[test]
+    fn empty_input() {
+        let editor = factory::wrap("<p>", "</p>", 2);
+        assert_eq!("", read_chunked(&editor, "", 1));
+    }
+}
@@ -0,0 +1,174 @@
+use crate::prelude::*;
+use crate::{NewlineType, RuleEditor};
+
+impl RuleEditor {
+    /// Create a new editor from `(run length, replacement)` rules
+    ///
+    /// Any collection of pairs can be used, such as a [`Vec`] or a
+    /// [`HashMap`](std::collections::HashMap). A later rule for the same
+    /// length replaces an earlier one. A rule for `0` never matches, as
+    /// every run has at least one newline.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::collections::HashMap;
+    /// # use linurgy::{NewlineType, RuleEditor};
+    /// let mut rules = HashMap::new();
+    /// rules.insert(2, "<br>".to_string());
+    ///
+    /// let editor = RuleEditor::new(rules, NewlineType::Crlf);
+    /// assert_eq!("foo<br>bar\r\nbaz", editor.edit("foo\r\n\r\nbar\r\nbaz"));
+    /// ```
+    pub fn new<I, S>(rules: I, line_ending: NewlineType) -> Self
+    where
+        I: IntoIterator<Item = (usize, S)>,
+        S: Into<String>,
+    {
+        RuleEditor {
+            rules: rules
+                .into_iter()
+                .map(|(len, replace)| (len, replace.into()))
+                .collect(),
+            line_ending,
+        }
+    }
+
+    /// The replacement for runs of `len` newlines, if there is a rule for
+    /// them
+    #[inline]
+    pub fn rule(&self, len: usize) -> Option<&str> {
+        self.rules.get(&len).map(String::as_str)
+    }
+
+    /// Iterate over `(run length, replacement)` rules, from shortest to
+    /// longest run
+    #[inline]
+    pub fn rules<'a>(&'a self) -> impl Iterator<Item = (usize, &'a str)> + 'a {
+        self.rules
+            .iter()
+            .map(|(&len, replace)| (len, replace.as_str()))
+    }
+
+    /// The type of newline this editor matches
+    #[inline]
+    pub fn line_ending(&self) -> NewlineType {
+        self.line_ending
+    }
+
+    /// Edit the input's runs of newlines
+    ///
+    /// As with an [`Editor`](crate::Editor), a [`NewlineType::Crlf`] editor
+    /// also counts a bare `\n` as a newline, and a [`NewlineType::Auto`]
+    /// editor matches the newlines used most in the input. Runs without a
+    /// rule are copied exactly as they are.
+    pub fn edit<S>(&self, input: S) -> String
+    where
+        S: AsRef<str>,
+    {
+        let input = input.as_ref();
+        let line_ending = match self.line_ending {
+            NewlineType::Auto => NewlineType::detect(input),
+            line_ending => line_ending,
+        };
+        let newline = line_ending.end_char();
+        let skip_cr = line_ending == NewlineType::Crlf;
+
+        let mut output = String::with_capacity(input.len());
+        let mut run = 0;
+        let mut run_start = 0;
+        let mut offset = 0;
+
+        while let Some(index) = input[offset..].find(newline) {
+            let mut text_end = offset + index;
+
+            // a `\r` is only part of a newline if `\n` comes next
+            if skip_cr && input[offset..text_end].ends_with('\r') {
+                text_end -= 1;
+            }
+
+            if text_end > offset {
+                self.push_run(&mut output, &input[run_start..offset], run);
+                output.push_str(&input[offset..text_end]);
+                run = 0;
+                run_start = text_end;
+            }
+
+            run += 1;
+            offset += index + newline.len_utf8();
+        }
+
+        self.push_run(&mut output, &input[run_start..offset], run);
+        output.push_str(&input[offset..]);
+
+        output
+    }
+
+    /// Push the replacement for a run of `len` newlines, or the run itself
+    /// if there is no rule for it.
+    #[inline]
+    fn push_run(&self, output: &mut String, run: &str, len: usize) {
+        match self.rules.get(&len) {
+            Some(replace) if len > 0 => output.push_str(replace),
+            _ => output.push_str(run),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn abc(line_ending: NewlineType) -> RuleEditor {
+        RuleEditor::new(vec![(1, "A"), (2, "B"), (3, "C")], line_ending)
+    }
+
+    #[test]
+    fn three_rules_mixed_runs() {
+        let editor = abc(NewlineType::Lf);
+        let output = editor.edit("\na\n\nb\nc\n\n\nd\n\n\n\ne\n\n");
+        assert_eq!("AaBbAcCd\n\n\n\neB", output);
+    }
+
+    #[test]
+    fn three_rules_mixed_runs_crlf() {
+        let editor = abc(NewlineType::Crlf);
+        let output = editor.edit("a\r\n\nb\rc\r\n\r\n\r\n\r\nd\r\n");
+        assert_eq!("aBb\rc\r\n\r\n\r\n\r\ndA", output);
+    }
+
+    #[test]
+    fn auto_detects_line_ending() {
+        let editor = abc(NewlineType::Auto);
+        assert_eq!("aBb", editor.edit("a\r\n\r\nb"));
+        assert_eq!("a\rAbAcA", editor.edit("a\r\nb\nc\n"));
+    }
+
+    #[test]
+    fn separators() {
+        let editor = abc(NewlineType::ParagraphSeparator);
+        assert_eq!("aB\nb", editor.edit("a\u{2029}\u{2029}\nb"));
+    }
+
+    #[test]
+    fn no_rules() {
+        let editor = RuleEditor::new(Vec::<(usize, String)>::new(), NewlineType::Lf);
+        assert_eq!("a\n\nb\n", editor.edit("a\n\nb\n"));
+        assert_eq!("", editor.edit(""));
+    }
+
+    #[test]
+    fn zero_rule_never_matches() {
+        let editor = RuleEditor::new(vec![(0, "-")], NewlineType::Lf);
+        assert_eq!("ab\n", editor.edit("ab\n"));
+        assert_eq!("", editor.edit(""));
+    }
+
+    #[test]
+    fn later_rule_replaces_earlier() {
+        let editor = RuleEditor::new(vec![(1, "A"), (1, "B")], NewlineType::Lf);
+        assert_eq!(Some("B"), editor.rule(1));
+        assert_eq!(None, editor.rule(2));
+        assert_eq!(vec![(1, "B")], editor.rules().collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,226 @@
+use core::iter::Peekable;
+use core::str::Chars;
+
+use crate::{Editor, NewlineStats, NewlineType};
+
+impl Editor {
+    /// Count the runs of newlines in the input, without editing it
+    ///
+    /// Newlines are matched the same way as [`Editor::edit`], using this
+    /// editor's [`NewlineType`]. As with editing, a CRLF editor also counts a
+    /// bare `\n` as a newline, an LF editor which
+    /// [`strip_cr`](Editor::strip_cr) counts a `\r\n`, and an editor which
+    /// reads [`reversed_crlf`](Editor::reversed_crlf) counts a `\n\r`. Lines of only
+    /// whitespace count as blank with
+    /// [`treat_whitespace_lines_as_blank`](Editor::treat_whitespace_lines_as_blank).
+    /// The editor's trigger and replacement are not used.
+    ///
+    /// # Examples
+    ///
+    /// Find the longest run of blank lines.
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let stats = factory::replacer_crlf("", 1).analyze("foo\r\n\r\nbar\r\nbaz");
+    /// assert_eq!(Some(2), stats.longest_run());
+    /// assert!(!stats.ends_with_newline());
+    /// ```
+    pub fn analyze(&self, input: &str) -> NewlineStats {
+        if self.line_ending == NewlineType::Auto {
+            return self.resolve(input).analyze(input);
+        }
+
+        let mut stats = NewlineStats::default();
+        let mut chars = input.chars().peekable();
+        let mut run = 0;
+        let mut after_cr = false;
+        let mut ends_with_newline = false;
+
+        while let Some(c) = chars.next() {
+            ends_with_newline = self.is_newline(c, after_cr, &mut chars);
+            after_cr = c == '\r' && !ends_with_newline;
+
+            if ends_with_newline {
+                run += 1;
+            } else if (self.blank_whitespace || self.lines.trim_trailing || self.lines.trim_leading)
+                && (c == ' ' || c == '\t')
+            {
+                // only a blank line if a newline comes before other text
+            } else if run > 0 {
+                stats.push_run(run);
+                run = 0;
+            }
+        }
+
+        if run > 0 {
+            stats.push_run(run);
+        }
+
+        stats.trailing_newline = ends_with_newline;
+
+        stats
+    }
+
+    /// Whether `c` is a newline, consuming the `\n` of a CRLF newline or
+    /// the `\r` of a reversed one.
+    #[inline]
+    fn is_newline(&self, c: char, after_cr: bool, chars: &mut Peekable<Chars>) -> bool {
+        let end_char = self.line_ending.end_char();
+
+        if self.skips_cr() && c == '\r' && chars.peek() == Some(&end_char) {
+            chars.next();
+            return true;
+        }
+
+        if self.joins_reversed_crlf() && c == '\n' && !after_cr && chars.peek() == Some(&'\r') {
+            chars.next();
+        }
+
+        c == end_char
+    }
+}
+
+impl NewlineStats {
+    /// Number of runs of exactly `len` newlines
+    #[inline]
+    pub fn runs_of(&self, len: usize) -> usize {
+        self.runs.get(&len).cloned().unwrap_or(0)
+    }
+
+    /// Number of runs of newlines of any length
+    #[inline]
+    pub fn total_runs(&self) -> usize {
+        self.runs.values().sum()
+    }
+
+    /// Total number of newlines
+    #[inline]
+    pub fn newlines(&self) -> usize {
+        self.newlines
+    }
+
+    /// Length of the longest run of newlines, or `None` if there are none
+    #[inline]
+    pub fn longest_run(&self) -> Option<usize> {
+        self.runs.keys().next_back().cloned()
+    }
+
+    /// Whether the text ends with a newline
+    #[inline]
+    pub fn ends_with_newline(&self) -> bool {
+        self.trailing_newline
+    }
+
+    /// Iterate over `(length, count)` pairs for each run length found, from
+    /// shortest to longest
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use linurgy::factory;
+    /// let stats = factory::replacer("", 1).analyze("a\n\nb\nc\n\nd");
+    /// let runs: Vec<_> = stats.runs().collect();
+    /// assert_eq!(vec![(1, 1), (2, 2)], runs);
+    /// ```
+    #[inline]
+    pub fn runs<'a>(&'a self) -> impl Iterator<Item = (usize, usize)> + 'a {
+        self.runs.iter().map(|(&len, &count)| (len, count))
+    }
+
+    #[inline]
+    fn push_run(&mut self, len: usize) {
+        *self.runs.entry(len).or_insert(0) += 1;
+        self.newlines += len;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{factory, NewlineType};
+
+    const DOCUMENT: &str = "title\n\n\nintro\nmore\n\npara\n\npara\n\n\n\nend\n";
+
+    #[test]
+    fn mixed_run_lengths() {
+        let stats = factory::replacer("", 1).analyze(DOCUMENT);
+
+        assert_eq!(2, stats.runs_of(1));
+        assert_eq!(2, stats.runs_of(2));
+        assert_eq!(1, stats.runs_of(3));
+        assert_eq!(1, stats.runs_of(4));
+        assert_eq!(0, stats.runs_of(5));
+        assert_eq!(6, stats.total_runs());
+        assert_eq!(13, stats.newlines());
+        assert_eq!(Some(4), stats.longest_run());
+        assert!(stats.ends_with_newline());
+        assert_eq!(
+            vec![(1, 2), (2, 2), (3, 1), (4, 1)],
+            stats.runs().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn mixed_run_lengths_crlf() {
+        let input = DOCUMENT.replace('\n', "\r\n");
+        let stats = factory::replacer_crlf("", 1).analyze(&input);
+
+        assert_eq!(factory::replacer("", 1).analyze(DOCUMENT), stats);
+    }
+
+    #[test]
+    fn crlf_counts_bare_lf_but_not_lone_cr() {
+        let stats = factory::replacer_crlf("", 1).analyze("a\r\n\nb\rc\r");
+
+        assert_eq!(1, stats.runs_of(2));
+        assert_eq!(1, stats.total_runs());
+        assert!(!stats.ends_with_newline());
+    }
+
+    #[test]
+    fn strip_cr_counts_crlf() {
+        let input = DOCUMENT.replace('\n', "\r\n");
+        let stats = factory::replacer("", 1).strip_cr(true).analyze(&input);
+
+        assert_eq!(factory::replacer("", 1).analyze(DOCUMENT), stats);
+    }
+
+    #[test]
+    fn trigger_is_ignored() {
+        let lf = factory::appender("-", 3).analyze(DOCUMENT);
+        assert_eq!(factory::replacer("", 1).analyze(DOCUMENT), lf);
+    }
+
+    #[test]
+    fn separators() {
+        let editor = factory::replacer_with("", 1, NewlineType::ParagraphSeparator);
+        let stats = editor.analyze("a\u{2029}\u{2029}b\nc\u{2029}");
+
+        assert_eq!(1, stats.runs_of(1));
+        assert_eq!(1, stats.runs_of(2));
+        assert_eq!(3, stats.newlines());
+        assert!(stats.ends_with_newline());
+    }
+
+    #[test]
+    fn whitespace_lines() {
+        let input = "a\n \t\n\nb  \nc\n  d";
+
+        let stats = factory::replacer("", 1).analyze(input);
+        assert_eq!(vec![(1, 3), (2, 1)], stats.runs().collect::<Vec<_>>());
+
+        let stats = factory::replacer("", 1)
+            .treat_whitespace_lines_as_blank(true)
+            .analyze(input);
+        assert_eq!(vec![(1, 2), (3, 1)], stats.runs().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn no_newlines() {
+        let stats = factory::replacer("", 1).analyze("foo");
+
+        assert_eq!(NewlineStats::default(), stats);
+        assert_eq!(None, stats.longest_run());
+        assert_eq!(0, stats.total_runs());
+    }
+}
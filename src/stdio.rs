@@ -0,0 +1,73 @@
+use std::io::{self, Write};
+
+use crate::{EditError, Editor};
+
+impl Editor {
+    /// Edit stdin, writing the result to stdout
+    ///
+    /// The input is edited with [`Editor::edit_buffered`] as it is read, and
+    /// stdout is flushed once it ends. This is the usual way to use an
+    /// editor in a command line tool.
+    ///
+    /// Both stdin and stdout are locked until the input ends, which saves
+    /// locking them for every read and write. Other threads printing to
+    /// stdout will wait until editing is finished.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Editor::edit_buffered`]. [`EditError`] converts into
+    /// [`io::Error`], so `?` works in a `main` returning [`io::Result`].
+    ///
+    /// # Examples
+    ///
+    /// Double space every line.
+    ///
+    /// ```rust,no_run
+    /// # use std::io;
+    /// # use linurgy::factory;
+    /// fn main() -> io::Result<()> {
+    ///     factory::appender("\n", 1).edit_stdin_to_stdout()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn edit_stdin_to_stdout(&self) -> Result<(), EditError> {
+        let stdin = io::stdin();
+        let stdout = io::stdout();
+        let mut output = stdout.lock();
+
+        self.edit_buffered(&mut stdin.lock(), &mut output)?;
+        output.flush().map_err(EditError::WriteFailed)
+    }
+
+    /// Edit stdin, returning the result
+    ///
+    /// Reads until the input ends, editing with [`Editor::edit_buffered`]
+    /// as it goes. Stdin is locked until then.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Editor::edit_buffered`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use linurgy::{factory, EditError};
+    /// # fn main() -> Result<(), EditError> {
+    /// let paragraphs = factory::replacer(" ", 1).edit_stdin_to_string()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn edit_stdin_to_string(&self) -> Result<String, EditError> {
+        let stdin = io::stdin();
+        let mut output = Vec::new();
+
+        self.edit_buffered(&mut stdin.lock(), &mut output)?;
+
+        // no-op editors copy the input without checking it
+        String::from_utf8(output).map_err(|error| {
+            let valid = &error.as_bytes()[..error.utf8_error().valid_up_to()];
+            let line = valid.iter().filter(|&&b| b == b'\n').count() + 1;
+            EditError::InvalidUtf8 { line }
+        })
+    }
+}
@@ -0,0 +1,297 @@
+use std::io::{self, Write};
+use std::str;
+
+use crate::editor::BufState;
+use crate::{EditError, EditWriter, Editor};
+
+impl<'a, W> EditWriter<'a, W>
+where
+    W: Write,
+{
+    /// Create a writer which edits text with `editor` before writing it to
+    /// `inner`
+    #[inline]
+    pub fn new(editor: &'a Editor, inner: W) -> Self {
+        EditWriter {
+            inner: Some(inner),
+            state: BufState::new(editor),
+            partial: Vec::new(),
+            edited: String::new(),
+            lines: 0,
+        }
+    }
+
+    /// Write any held text, then flush and return the inner writer
+    ///
+    /// A partial line at the end is edited as the last line of the text, so
+    /// the output matches [`Editor::edit`] for everything written.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.finish_edit()?;
+        Ok(self.inner.take().expect("inner writer is only taken once"))
+    }
+
+    /// The inner writer
+    #[inline]
+    pub fn get_ref(&self) -> &W {
+        self.inner
+            .as_ref()
+            .expect("inner writer is only taken once")
+    }
+
+    /// The inner writer
+    ///
+    /// Writing to it directly may interleave with edited text which is yet
+    /// to be written.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut W {
+        self.inner
+            .as_mut()
+            .expect("inner writer is only taken once")
+    }
+
+    /// Edit the complete lines in `partial`, removing them.
+    ///
+    /// `searched` bytes at the start of `partial` are known not to contain a
+    /// line ending.
+    fn edit_lines(&mut self, searched: usize) -> io::Result<()> {
+        let mut end_buf = [0; 4];
+        let end = self.state.line_end().encode_utf8(&mut end_buf).as_bytes();
+
+        // a line ending may have started in the searched bytes
+        let from = searched.saturating_sub(end.len() - 1);
+        let len = match self.partial[from..]
+            .windows(end.len())
+            .rposition(|window| window == end)
+        {
+            Some(index) => from + index + end.len(),
+            None => return Ok(()),
+        };
+
+        let text = str::from_utf8(&self.partial[..len]).map_err(|error| {
+            let before = &self.partial[..error.valid_up_to()];
+            let line = self.lines + count(before, end) + 1;
+            io::Error::from(EditError::InvalidUtf8 { line })
+        })?;
+
+        let mut rest = text;
+        while let Some(index) = rest.find(self.state.line_end()) {
+            let (line, next) = rest.split_at(index + end.len());
+            self.state.push_line(line, &mut self.edited);
+            self.lines += 1;
+            rest = next;
+        }
+
+        self.partial.drain(..len);
+
+        Ok(())
+    }
+
+    /// Write the edited text to the inner writer.
+    #[inline]
+    fn write_edited(&mut self) -> io::Result<()> {
+        if let Some(inner) = self.inner.as_mut() {
+            inner.write_all(self.edited.as_bytes())?;
+            self.edited.clear();
+        }
+
+        Ok(())
+    }
+
+    /// Edit the partial line as the last line, then write and flush
+    /// everything held.
+    fn finish_edit(&mut self) -> io::Result<()> {
+        self.write_edited()?;
+
+        if !self.partial.is_empty() {
+            let line = str::from_utf8(&self.partial).map_err(|_| {
+                io::Error::from(EditError::InvalidUtf8 {
+                    line: self.lines + 1,
+                })
+            })?;
+            self.state.push_line(line, &mut self.edited);
+            self.partial.clear();
+        }
+
+        self.state.finish(&mut self.edited);
+        self.write_edited()?;
+
+        self.get_mut().flush()
+    }
+}
+
+impl<'a, W> Write for EditWriter<'a, W>
+where
+    W: Write,
+{
+    /// Edit any complete lines and write them to the inner writer
+    ///
+    /// All of `buf` is always accepted. If writing edited text to the inner
+    /// writer fails, it is kept and written by the next call.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // text left over from a failed write goes first
+        self.write_edited()?;
+
+        let searched = self.partial.len();
+        self.partial.extend_from_slice(buf);
+
+        if let Err(error) = self.edit_lines(searched) {
+            self.partial.truncate(searched);
+            return Err(error);
+        }
+
+        // the text is edited, so `buf` has been written even if this fails
+        let _ = self.write_edited();
+
+        Ok(buf.len())
+    }
+
+    /// Write edited text and flush the inner writer
+    ///
+    /// Text which is held until more is written, such as a partial line, is
+    /// not flushed. Use [`EditWriter::finish`] to write it.
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_edited()?;
+        self.get_mut().flush()
+    }
+}
+
+impl<'a, W> Drop for EditWriter<'a, W>
+where
+    W: Write,
+{
+    fn drop(&mut self) {
+        if self.inner.is_some() {
+            // errors can't be reported from drop
+            let _ = self.finish_edit();
+        }
+    }
+}
+
+/// Number of times `pattern` occurs in `bytes`.
+#[inline]
+fn count(bytes: &[u8], pattern: &[u8]) -> usize {
+    bytes
+        .windows(pattern.len())
+        .filter(|window| *window == pattern)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{factory, NewlineType};
+
+    const INPUT: &str = "foo\n\nbar\n\n\nbaz\nqux\n\n";
+
+    /// Write `input` through an `EditWriter` in chunks of `size` bytes.
+    fn write_chunked(editor: &Editor, input: &str, size: usize) -> String {
+        let mut writer = EditWriter::new(editor, Vec::new());
+        for chunk in input.as_bytes().chunks(size) {
+            writer.write_all(chunk).unwrap();
+        }
+        String::from_utf8(writer.finish().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn same_output_as_edit() {
+        let editor = factory::appender("---", 2);
+        assert_eq!(
+            editor.edit(INPUT),
+            write_chunked(&editor, INPUT, INPUT.len())
+        );
+    }
+
+    #[test]
+    fn any_chunk_size() {
+        let editor = factory::replacer("-", 2);
+        for size in 1..INPUT.len() {
+            assert_eq!(editor.edit(INPUT), write_chunked(&editor, INPUT, size));
+        }
+    }
+
+    #[test]
+    fn crlf_split_between_writes() {
+        let input = "foo\r\n\r\nbar\r\n\r\n\r\nbaz\rqux\r";
+        let editor = factory::inserter_crlf(";", 2);
+        for size in 1..input.len() {
+            assert_eq!(editor.edit(input), write_chunked(&editor, input, size));
+        }
+    }
+
+    #[test]
+    fn separator_split_between_writes() {
+        let input = "foo\u{2029}\u{2029}bar\u{2029}baz";
+        let editor = factory::replacer_with("\n", 2, NewlineType::ParagraphSeparator);
+        for size in 1..input.len() {
+            assert_eq!(editor.edit(input), write_chunked(&editor, input, size));
+        }
+    }
+
+    #[test]
+    fn trailing_newline_held_until_finish() {
+        let editor = factory::replacer(" ", 2).ensure_trailing_newline(true);
+        let mut writer = EditWriter::new(&editor, Vec::new());
+
+        writer.write_all(b"foo\nbar\n\n\n").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(b"foo\nbar ", &writer.get_ref()[..]);
+
+        assert_eq!(b"foo\nbar \n", &writer.finish().unwrap()[..]);
+    }
+
+    #[test]
+    fn partial_line_held_until_finish() {
+        let editor = factory::replacer("-", 1);
+        let mut writer = EditWriter::new(&editor, Vec::new());
+
+        writer.write_all(b"foo\nbar").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(b"foo-", &writer.get_ref()[..]);
+
+        assert_eq!(b"foo-bar", &writer.finish().unwrap()[..]);
+    }
+
+    #[test]
+    fn drop_finishes() {
+        let editor = factory::wrap("<p>", "</p>", 2);
+        let mut output = Vec::new();
+
+        {
+            let mut writer = EditWriter::new(&editor, &mut output);
+            write!(writer, "foo\n\nbar").unwrap();
+        }
+
+        assert_eq!(b"<p>foo</p>\n\n<p>bar</p>", &output[..]);
+    }
+
+    #[test]
+    fn split_utf8_char() {
+        let input = "é\nü\n\nø";
+        let editor = factory::replacer(" ", 1);
+        assert_eq!(editor.edit(input), write_chunked(&editor, input, 1));
+    }
+
+    #[test]
+    fn invalid_utf8_reports_line() {
+        let editor = factory::replacer(" ", 1);
+        let mut writer = EditWriter::new(&editor, Vec::new());
+
+        writer.write_all(b"foo\n").unwrap();
+        let error = writer.write_all(b"bar\nb\xffz\n").unwrap_err();
+
+        assert_eq!(io::ErrorKind::InvalidData, error.kind());
+        assert!(error.to_string().contains("line 3"), "{}", error);
+    }
+
+    #[test]
+    fn invalid_utf8_is_not_written() {
+        let editor = factory::replacer(" ", 1);
+        let mut writer = EditWriter::new(&editor, Vec::new());
+
+        writer.write_all(b"foo").unwrap();
+        assert!(writer.write_all(b"\xff\n").is_err());
+        writer.write_all(b"\nbar").unwrap();
+
+        assert_eq!(b"foo bar", &writer.finish().unwrap()[..]);
+    }
+}
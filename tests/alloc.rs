@@ -0,0 +1,103 @@
+//! Editing which leaves the text unchanged must not allocate.
+//!
+//! Allocations are counted for each thread, so tests running at the same time
+//! don't count each other's.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::borrow::Cow;
+use std::cell::Cell;
+
+use linurgy::{factory, Editor};
+
+thread_local!(static ALLOCATIONS: Cell<usize> = Cell::new(0));
+
+struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// Number of allocations made by `f` on this thread.
+fn allocations<F>(f: F) -> usize
+where
+    F: FnOnce(),
+{
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+fn assert_unchanged_without_allocating(editor: &Editor, input: &str) {
+    let mut borrowed = false;
+    let count = allocations(|| {
+        if let Cow::Borrowed(_) = editor.edit_cow(input) {
+            borrowed = true;
+        }
+    });
+
+    assert!(borrowed, "editor: {:?}\ninput: {:?}", editor, input);
+    assert_eq!(
+        0, count,
+        "edit_cow\neditor: {:?}\ninput: {:?}",
+        editor, input
+    );
+
+    let mut changed = None;
+    let count = allocations(|| changed = editor.edit_if_changed(input));
+
+    assert_eq!(None, changed, "editor: {:?}\ninput: {:?}", editor, input);
+    assert_eq!(
+        0, count,
+        "edit_if_changed\neditor: {:?}\ninput: {:?}",
+        editor, input
+    );
+}
+
+#[test]
+fn unchanged_does_not_allocate() {
+    let input = "foo\nbar\r\nbaz\n".repeat(100);
+
+    assert_unchanged_without_allocating(&factory::replacer(" ", 2), &input);
+    assert_unchanged_without_allocating(&factory::appender("-", 3), &input);
+    assert_unchanged_without_allocating(&Editor::default(), &input);
+    assert_unchanged_without_allocating(&factory::inserter("", 1), &input);
+
+    let editor = factory::replacer("\n", 1).ensure_trailing_newline(true);
+    assert_unchanged_without_allocating(&editor, &input);
+}
+
+#[test]
+fn same_replacement_does_not_allocate() {
+    let input = "foo\r\n\r\nbar\r\n".repeat(100);
+    let editor = factory::replacer_crlf("\r\n", 1);
+
+    assert_unchanged_without_allocating(&editor, &input);
+}
+
+#[test]
+fn changed_allocates_once() {
+    let input = "foo\n".repeat(100) + "\nbar";
+    let editor = factory::replacer(" ", 2);
+
+    let count = allocations(|| {
+        let output = editor.edit_cow(&input);
+        assert!(output.ends_with("foo bar"));
+    });
+    assert_eq!(1, count, "edit_cow");
+
+    let count = allocations(|| {
+        let output = editor.edit_if_changed(&input);
+        assert!(output.unwrap().ends_with("foo bar"));
+    });
+    assert_eq!(1, count, "edit_if_changed");
+}
@@ -64,3 +64,33 @@ fn reuse_replacer() {
 
     assert_eq!(expected, actual);
 }
+
+#[test]
+fn pipeline_collapse_then_append() {
+    let input = "foo\n\n\nbar\n\nbaz";
+
+    let expected = "foo\n\n---bar\n\n---baz";
+
+    let pipeline = factory::replacer("\n\n", 3).then(factory::appender("---", 2));
+
+    let actual = pipeline.edit(input);
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn edit_with_spec() {
+    let input = "foo\r\nbar\r\n\r\nbaz";
+
+    let expected = "foo\r\nbar---\r\n\r\nbaz";
+
+    let actual = linurgy::edit(input, "insert:2:---:crlf").unwrap();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn edit_with_invalid_spec() {
+    assert!(linurgy::edit("foo\nbar", "replace:two:-").is_err());
+    assert!(linurgy::edit("foo\nbar", "replace").is_err());
+}
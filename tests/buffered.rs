@@ -1,11 +1,12 @@
 use std::{
     env::temp_dir,
     fs,
-    io::{BufReader, Result, Write},
+    io::{self, BufReader, Cursor, Result, Write},
     path::Path,
+    thread,
 };
 
-use linurgy::factory;
+use linurgy::{factory, BufEditor, EditError};
 
 #[test]
 fn extra_line() {
@@ -79,3 +80,247 @@ fn create_input_file(input: &str, path: &Path) -> Result<()> {
     input_file.write_all(input.as_bytes())?;
     Ok(())
 }
+
+#[test]
+fn buf_editor() -> Result<()> {
+    let input = "foo\n\nbar\n\nbaz\n";
+
+    let expected = "foo\n\n---bar\n\n---baz\n";
+
+    let mut input_buf = BufReader::new(input.as_bytes());
+    let mut output_buf = Vec::<u8>::with_capacity(input.len());
+
+    let editor = factory::appender("---", 2);
+    let mut buf_editor = BufEditor::with_editor(editor, &mut input_buf, &mut output_buf);
+
+    buf_editor.edit()?;
+
+    let actual = String::from_utf8_lossy(&output_buf);
+
+    assert_eq!(expected, actual);
+
+    Ok(())
+}
+
+#[test]
+fn invalid_utf8_reports_line() {
+    let mut input = Cursor::new(b"foo\nbar\nb\xffz\n".to_vec());
+    let mut output = Vec::new();
+
+    let res = factory::replacer(" ", 1).edit_buffered(&mut input, &mut output);
+
+    match res {
+        Err(EditError::InvalidUtf8 { line }) => assert_eq!(3, line),
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn failed_write_is_write_failed() {
+    struct Broken;
+
+    impl Write for Broken {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "broken"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut input = Cursor::new("foo\nbar");
+
+    let res = factory::replacer(" ", 1).edit_buffered(&mut input, &mut Broken);
+
+    match res {
+        Err(EditError::WriteFailed(error)) => assert_eq!(io::ErrorKind::BrokenPipe, error.kind()),
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn edit_file() -> Result<()> {
+    let input_path = temp_dir().join("linurgy-edit-file-input.txt");
+    let output_path = temp_dir().join("linurgy-edit-file-output.txt");
+
+    create_input_file("foo\n\nbar\n\nbaz\n", &input_path)?;
+
+    factory::inserter("--", 2).edit_file(&input_path, &output_path)?;
+
+    assert_eq!("foo--\n\nbar--\n\nbaz\n", fs::read_to_string(&output_path)?);
+
+    fs::remove_file(&input_path)?;
+    fs::remove_file(&output_path)?;
+
+    Ok(())
+}
+
+#[test]
+fn edit_file_same_path() -> Result<()> {
+    let path = temp_dir().join("linurgy-edit-file-same.txt");
+
+    create_input_file("foo\nbar\nbaz\n", &path)?;
+
+    factory::replacer("-", 1).edit_file(&path, &path)?;
+
+    assert_eq!("foo-bar-baz-", fs::read_to_string(&path)?);
+
+    fs::remove_file(&path)?;
+
+    Ok(())
+}
+
+#[test]
+fn edit_file_missing_input_is_io() {
+    let input_path = temp_dir().join("linurgy-edit-file-missing.txt");
+    let output_path = temp_dir().join("linurgy-edit-file-missing-output.txt");
+
+    let res = factory::replacer("-", 1).edit_file(&input_path, &output_path);
+
+    match res {
+        Err(EditError::Io(error)) => assert_eq!(io::ErrorKind::NotFound, error.kind()),
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    assert!(!output_path.exists());
+}
+
+#[test]
+fn edit_file_in_place() -> Result<()> {
+    let path = temp_dir().join("linurgy-edit-file-in-place.txt");
+
+    create_input_file("foo\n\n\nbar\n", &path)?;
+
+    factory::replacer("\n", 3).edit_file_in_place(&path)?;
+
+    assert_eq!("foo\nbar\n", fs::read_to_string(&path)?);
+
+    fs::remove_file(&path)?;
+
+    Ok(())
+}
+
+#[test]
+fn edit_file_in_place_concurrently() -> Result<()> {
+    let dir = temp_dir().join("linurgy-edit-file-in-place-concurrent");
+    let path = dir.join("input.txt");
+
+    // clear anything left by an earlier failure
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir)?;
+    create_input_file("foo\n\nbar\n", &path)?;
+
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let path = path.clone();
+            thread::spawn(move || {
+                for _ in 0..16 {
+                    factory::replacer("\n", 2).edit_file_in_place(&path)?;
+                }
+                Ok::<_, EditError>(())
+            })
+        })
+        .collect();
+
+    for thread in threads {
+        thread.join().unwrap().unwrap();
+    }
+
+    // no temporary files are left behind
+    assert_eq!(1, fs::read_dir(&dir)?.count());
+    assert_eq!("foo\nbar\n", fs::read_to_string(&path)?);
+
+    fs::remove_dir_all(&dir)?;
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn edit_file_in_place_swaps_file() -> Result<()> {
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+    let path = temp_dir().join("linurgy-edit-file-in-place-swap.txt");
+
+    create_input_file("foo\nbar\n", &path)?;
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o640))?;
+    let before = fs::metadata(&path)?;
+
+    factory::inserter(";", 1).edit_file_in_place(&path)?;
+
+    let after = fs::metadata(&path)?;
+    assert_eq!("foo;\nbar;\n", fs::read_to_string(&path)?);
+    assert_ne!(before.ino(), after.ino());
+    assert_eq!(0o640, after.permissions().mode() & 0o777);
+
+    fs::remove_file(&path)?;
+
+    Ok(())
+}
+
+#[test]
+fn edit_file_in_place_failure_keeps_original() -> Result<()> {
+    let path = temp_dir().join("linurgy-edit-file-in-place-invalid.txt");
+
+    fs::write(&path, b"foo\nb\xffr\n")?;
+
+    let res = factory::replacer("-", 1).edit_file_in_place(&path);
+
+    match res {
+        Err(EditError::InvalidUtf8 { line }) => assert_eq!(2, line),
+        res => panic!("unexpected result: {:?}", res),
+    }
+    assert_eq!(b"foo\nb\xffr\n".to_vec(), fs::read(&path)?);
+
+    fs::remove_file(&path)?;
+
+    Ok(())
+}
+
+/// Writer which counts the calls made to it.
+#[derive(Default)]
+struct CountWrites {
+    output: Vec<u8>,
+    writes: usize,
+}
+
+impl Write for CountWrites {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writes += 1;
+        self.output.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn long_newline_run_few_writes() {
+    let input = format!("foo{}bar{}", "\n".repeat(10_000), "\n".repeat(10_001));
+    let editor = factory::replacer("-", 3);
+
+    let mut output = CountWrites::default();
+    editor
+        .edit_buffered(&mut Cursor::new(&input), &mut output)
+        .unwrap();
+
+    assert_eq!(editor.edit(&input).as_bytes(), &output.output[..]);
+    assert!(output.writes < 20, "{} writes", output.writes);
+}
+
+#[test]
+fn many_lines_few_writes() {
+    let input = "foo\nbar\n\n".repeat(1000);
+    let editor = factory::inserter("---", 2);
+
+    let mut output = CountWrites::default();
+    editor
+        .edit_buffered(&mut Cursor::new(&input), &mut output)
+        .unwrap();
+
+    assert_eq!(editor.edit(&input).as_bytes(), &output.output[..]);
+    assert!(output.writes < 20, "{} writes", output.writes);
+}
@@ -0,0 +1,35 @@
+//! Uses linurgy from a `#![no_std]` crate, so CI can check it builds for a
+//! target without `std`.
+#![no_std]
+
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use linurgy::{factory, Editor, EditorBuilder, NewlineType};
+
+/// Collapse blank lines, then end each line with a semicolon.
+pub fn paragraphs(input: &str) -> String {
+    factory::replacer("\n", 2)
+        .then(factory::inserter(";", 1))
+        .edit(input)
+}
+
+/// Edit text which may not be valid UTF-8.
+pub fn bytes(input: &[u8]) -> Vec<u8> {
+    Editor::new(String::from(" "), 1, NewlineType::Crlf).edit_bytes(input)
+}
+
+/// Build an editor from parts chosen at runtime.
+pub fn build(text: &str, trigger: usize) -> Editor {
+    EditorBuilder::new()
+        .text(text)
+        .newline_trigger(trigger)
+        .build()
+}
+
+/// Edit with an editor given as a string, as from JavaScript.
+pub fn spec(input: &str, spec: &str) -> Option<String> {
+    linurgy::edit(input, spec).ok()
+}
@@ -0,0 +1,259 @@
+//! `Editor::edit_buffered` must write exactly what `Editor::edit` returns, and
+//! `Editor::output_len` must count its length. Every way of counting edits
+//! must agree with `Editor::edit_counted`. `Editor::edit_cow` and
+//! `Editor::edit_if_changed` may only skip the output when editing leaves the
+//! text unchanged.
+//!
+//! Inputs are generated from a fixed seed, so any failure is reproducible.
+
+use std::borrow::Cow;
+use std::io::BufReader;
+
+use linurgy::{factory, Editor, NewlineType, TriggerMode};
+
+const CASES: usize = 2000;
+
+/// Pieces of text inputs are built from, weighted towards newlines.
+const PIECES: &[&str] = &[
+    "a", "foo", "é", " ", "\t", "\n", "\n", "\n", "\r\n", "\r\n", "\r", "\n\r", "\u{2028}",
+    "\u{2029}",
+];
+
+/// A small xorshift generator, so the corpus needs no dependencies.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn input(&mut self) -> String {
+        let len = self.below(40);
+        let mut input: String = (0..len).map(|_| PIECES[self.below(PIECES.len())]).collect();
+
+        // runs of newlines at the end, trailing whitespace, or neither
+        match self.below(4) {
+            0 => input.push_str(&"\n".repeat(self.below(4) + 1)),
+            1 => input.push_str(&"\r\n".repeat(self.below(4) + 1)),
+            2 => input.push_str(" \t"),
+            _ => input.push('z'),
+        }
+
+        input
+    }
+
+    fn editor(&mut self) -> Editor {
+        let text = ["", "-", "\n", "\r\n", "<>"][self.below(5)];
+        let trigger = self.below(4);
+        // `Auto` only matches when the first line has the predominant style
+        let line_endings = [
+            NewlineType::Lf,
+            NewlineType::Crlf,
+            NewlineType::LineSeparator,
+            NewlineType::Auto,
+        ];
+        let line_ending = line_endings[self.below(3)];
+
+        // identity editors, which write back the newlines they replace
+        match self.below(10) {
+            0 => return factory::inserter_with("", trigger, line_ending),
+            1 => return factory::appender_with("", trigger, line_ending),
+            _ => {}
+        }
+
+        let editor = match self.below(8) {
+            0 => factory::appender_with(text, trigger, line_ending),
+            1 => factory::inserter_with(text, trigger, line_ending),
+            2 => factory::replacer_with(text, trigger, line_ending),
+            3 => factory::wrap_with("(", ")", trigger, line_ending),
+            4 => factory::line_prefixer_with("> ", line_ending),
+            5 => factory::line_suffixer_with(";", line_ending),
+            6 => factory::template_replacer_with("<{n}:{count}>", trigger, line_ending),
+            _ => {
+                let output_ending = line_endings[self.below(4)];
+                Editor::new_converting(text.to_string(), trigger, line_ending, output_ending)
+            }
+        };
+
+        let trigger_mode = match self.below(5) {
+            0 => TriggerMode::AtLeast,
+            1 => TriggerMode::AtMost,
+            2 => TriggerMode::Between(self.below(4)),
+            _ => TriggerMode::Exactly,
+        };
+
+        let fallback = ["", "+", "\n", "\r\n"].get(self.below(8)).cloned();
+
+        let max_edits = match self.below(4) {
+            0 => Some(self.below(3)),
+            _ => None,
+        };
+
+        editor
+            .ensure_trailing_newline(self.below(3) == 0)
+            .max_edits(max_edits)
+            .skip_edits(self.below(3) / 2)
+            .strip_cr(self.below(3) == 0)
+            .treat_whitespace_lines_as_blank(self.below(3) == 0)
+            .skip_empty_last_segment(self.below(4) == 0)
+            .trigger_mode(trigger_mode)
+            .fallback(fallback)
+            .reversed_crlf(self.below(3) == 0)
+            .trim_trailing_whitespace(self.below(3) == 0)
+            .strip_leading_whitespace(self.below(4) == 0)
+            .trim_leading_newlines(self.below(4) == 0)
+            .trim_trailing_newlines(self.below(4) == 0)
+            .append_at_eof(self.below(3) == 0)
+    }
+}
+
+fn edit_buffered(editor: &Editor, input: &str, read_capacity: usize, capacity: usize) -> String {
+    let mut input = BufReader::with_capacity(read_capacity, input.as_bytes());
+    let mut output = Vec::new();
+
+    editor
+        .edit_buffered_with_capacity(&mut input, &mut output, capacity)
+        .unwrap();
+
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn buffered_matches_edit() {
+    let mut rng = Rng(0x6c69_6e75_7267);
+
+    for _ in 0..CASES {
+        let editor = rng.editor();
+        let input = rng.input();
+        let read_capacity = rng.below(8) + 1;
+        let capacity = rng.below(16) + 1;
+
+        assert_eq!(
+            editor.edit(&input),
+            edit_buffered(&editor, &input, read_capacity, capacity),
+            "\neditor: {:?}\ninput: {:?}\n",
+            editor,
+            input
+        );
+    }
+}
+
+#[test]
+fn buffered_matches_edit_default_capacity() {
+    let mut rng = Rng(0x7061_7269_7479);
+
+    for _ in 0..CASES {
+        let editor = rng.editor();
+        let input = rng.input();
+
+        let mut output = Vec::new();
+        editor
+            .edit_buffered(&mut input.as_bytes(), &mut output)
+            .unwrap();
+
+        assert_eq!(
+            editor.edit(&input),
+            String::from_utf8(output).unwrap(),
+            "\neditor: {:?}\ninput: {:?}\n",
+            editor,
+            input
+        );
+    }
+}
+
+#[test]
+fn output_len_matches_edit() {
+    let mut rng = Rng(0x6c65_6e67_7468);
+
+    for _ in 0..CASES {
+        let editor = rng.editor();
+        let input = rng.input();
+
+        assert_eq!(
+            editor.edit(&input).len(),
+            editor.output_len(&input),
+            "\neditor: {:?}\ninput: {:?}\n",
+            editor,
+            input
+        );
+    }
+}
+
+#[test]
+fn counts_match_edit_counted() {
+    let mut rng = Rng(0x636f_756e_7473);
+
+    for _ in 0..CASES {
+        let editor = rng.editor();
+        let input = rng.input();
+        let (_, edits) = editor.edit_counted(&input);
+
+        let mut output = Vec::new();
+        let buffered = editor
+            .edit_buffered_counted(&mut input.as_bytes(), &mut output)
+            .unwrap();
+
+        assert_eq!(
+            (edits, edits),
+            (buffered, editor.preview(&input).len()),
+            "\neditor: {:?}\ninput: {:?}\n",
+            editor,
+            input
+        );
+    }
+}
+
+#[test]
+fn cr_before_inserted_newline_is_trimmed() {
+    let editor = factory::inserter_crlf("\n", 2).ensure_trailing_newline(true);
+    let input = "foo\n\r\r\n\r\n";
+
+    assert_eq!("foo\r\n", editor.edit(input));
+    assert_eq!("foo\r\n", edit_buffered(&editor, input, 1, 1));
+}
+
+#[test]
+fn lone_cr_at_end_is_kept() {
+    let editor = factory::replacer_crlf("-", 2).ensure_trailing_newline(true);
+    let input = "foo\r\n\r";
+
+    assert_eq!("foo\r\n\r\r\n", editor.edit(input));
+    assert_eq!("foo\r\n\r\r\n", edit_buffered(&editor, input, 1, 1));
+}
+
+#[test]
+fn edit_cow_and_edit_if_changed_skip_only_unchanged_text() {
+    let mut rng = Rng(0x636f_775f_6564);
+
+    for _ in 0..CASES {
+        let editor = rng.editor();
+        let input = rng.input();
+        let edited = editor.edit(&input);
+
+        let changed = editor.edit_if_changed(&input);
+
+        match editor.edit_cow(&input) {
+            Cow::Borrowed(borrowed) => assert_eq!(
+                (edited.as_str(), None),
+                (borrowed, changed),
+                "\neditor: {:?}\ninput: {:?}\n",
+                editor,
+                input
+            ),
+            Cow::Owned(owned) => assert_eq!(
+                (&edited, Some(&edited)),
+                (&owned, changed.as_ref()),
+                "\neditor: {:?}\ninput: {:?}\n",
+                editor,
+                input
+            ),
+        }
+    }
+}
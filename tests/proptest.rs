@@ -0,0 +1,182 @@
+//! Property tests of the newline editing state machine.
+//!
+//! Run with `cargo test --features proptest --test proptest`. The invariants
+//! checked for arbitrary inputs are:
+//!
+//! - A no-op editor returns its input unchanged.
+//! - Editing twice is the same as editing once, for editors which replace
+//!   every newline with text containing none, or only ensure a trailing
+//!   newline, and for any editor which reports [`Editor::is_idempotent`].
+//! - Each edit changes the number of newlines by the newlines in the
+//!   replacement minus the trigger, so the output's newline count follows
+//!   from the input's count and the number of edits.
+//! - [`Editor::edit_buffered`] writes exactly what [`Editor::edit`] returns.
+#![cfg(feature = "proptest")]
+
+use linurgy::{factory, Editor, NewlineType, TriggerMode};
+use proptest::prelude::*;
+
+/// Text made of short runs of every kind of newline, including lone `\r`.
+fn input() -> impl Strategy<Value = String> {
+    "(a|é| |\t|-|\r|\n|\r\n|\u{2028}|\u{2029}){0,40}"
+}
+
+fn line_ending() -> impl Strategy<Value = NewlineType> {
+    prop_oneof![
+        Just(NewlineType::Lf),
+        Just(NewlineType::Crlf),
+        Just(NewlineType::LineSeparator),
+        Just(NewlineType::ParagraphSeparator),
+    ]
+}
+
+/// Any editor built by the factory, without options.
+fn factory_editor() -> impl Strategy<Value = Editor> {
+    (
+        0..4u8,
+        "(-|\n|\r\n|\u{2028}){0,3}",
+        0..4usize,
+        line_ending(),
+    )
+        .prop_map(|(kind, text, trigger, line_ending)| match kind {
+            0 => factory::appender_with(&text, trigger, line_ending),
+            1 => factory::inserter_with(&text, trigger, line_ending),
+            2 => factory::replacer_with(&text, trigger, line_ending),
+            _ => factory::wrap_with("(", ")", trigger, line_ending),
+        })
+}
+
+/// Any editor, with any options except a trailing newline.
+fn editor() -> impl Strategy<Value = Editor> {
+    (
+        factory_editor(),
+        proptest::option::of(0..4usize),
+        0..3usize,
+        any::<bool>(),
+        any::<bool>(),
+        any::<bool>(),
+        any::<bool>(),
+        any::<bool>(),
+    )
+        .prop_map(
+            |(editor, max, skip, strip, blank, reversed, trim, dedent)| {
+                editor
+                    .max_edits(max)
+                    .skip_edits(skip)
+                    .strip_cr(strip)
+                    .treat_whitespace_lines_as_blank(blank)
+                    .reversed_crlf(reversed)
+                    .trim_trailing_whitespace(trim)
+                    .strip_leading_whitespace(dedent)
+            },
+        )
+}
+
+/// Editors which never change their input.
+fn noop_editor() -> impl Strategy<Value = Editor> {
+    let line_ending = prop_oneof![
+        Just(NewlineType::Lf),
+        Just(NewlineType::LineSeparator),
+        Just(NewlineType::ParagraphSeparator),
+    ];
+
+    (0..4u8, "-{0,2}", 1..4usize, line_ending).prop_map(|(kind, text, trigger, line_ending)| {
+        match kind {
+            0 => factory::replacer_with(&text, 0, line_ending),
+            1 => factory::appender_with("", trigger, line_ending),
+            2 => factory::inserter_with("", trigger, line_ending),
+            _ => factory::replacer_with(&text, trigger, line_ending).max_edits(Some(0)),
+        }
+    })
+}
+
+/// Editors for which editing twice is the same as editing once.
+fn idempotent_editor() -> impl Strategy<Value = Editor> {
+    (any::<bool>(), "[- a]{0,3}", line_ending()).prop_map(|(squash, text, line_ending)| {
+        if squash {
+            factory::replacer_with(&text, 1, line_ending)
+        } else {
+            factory::replacer_with("", 0, line_ending).ensure_trailing_newline(true)
+        }
+    })
+}
+
+/// Replacers of text without newlines, in any trigger mode, most of which
+/// are idempotent.
+fn replacer_editor() -> impl Strategy<Value = Editor> {
+    let trigger_mode = prop_oneof![
+        Just(TriggerMode::Exactly),
+        Just(TriggerMode::AtLeast),
+        Just(TriggerMode::AtMost),
+        (0..4usize).prop_map(TriggerMode::Between),
+    ];
+
+    (
+        any::<bool>(),
+        "[- a]{0,3}",
+        0..4usize,
+        line_ending(),
+        trigger_mode,
+        any::<bool>(),
+    )
+        .prop_map(|(template, text, trigger, line_ending, mode, trailing)| {
+            let editor = if template {
+                factory::template_replacer_with(&format!("{}{{n}}", text), trigger, line_ending)
+            } else {
+                factory::replacer_with(&text, trigger, line_ending)
+            };
+            editor.trigger_mode(mode).ensure_trailing_newline(trailing)
+        })
+}
+
+fn count_newlines(editor: &Editor, text: &str) -> i64 {
+    // every kind of newline contains its last character exactly once
+    let end_char = editor.line_ending().as_str().chars().last().unwrap();
+    text.matches(end_char).count() as i64
+}
+
+proptest! {
+    #[test]
+    fn noop_editor_keeps_input(editor in noop_editor(), input in input()) {
+        prop_assert!(editor.is_noop());
+        prop_assert_eq!(&input, &editor.edit(&input));
+    }
+
+    #[test]
+    fn idempotent(editor in idempotent_editor(), input in input()) {
+        prop_assert!(editor.is_idempotent());
+        let once = editor.edit(&input);
+        prop_assert_eq!(&once, &editor.edit(&once));
+    }
+
+    #[test]
+    fn is_idempotent(editor in replacer_editor(), input in input()) {
+        prop_assume!(editor.is_idempotent());
+
+        let once = editor.edit(&input);
+        prop_assert_eq!(&once, &editor.edit(&once));
+    }
+
+    #[test]
+    fn newline_count(editor in editor(), input in input()) {
+        let (output, edits) = editor.edit_counted(&input);
+
+        let per_edit = count_newlines(&editor, editor.replacement()) - editor.trigger() as i64;
+        let expected = count_newlines(&editor, &input) + edits as i64 * per_edit;
+
+        prop_assert_eq!(expected, count_newlines(&editor, &output));
+    }
+
+    #[test]
+    fn buffered_matches_edit(
+        editor in editor(),
+        trailing in any::<bool>(),
+        input in input(),
+    ) {
+        let editor = editor.ensure_trailing_newline(trailing);
+        let mut output = Vec::new();
+        editor.edit_buffered(&mut input.as_bytes(), &mut output).unwrap();
+
+        prop_assert_eq!(editor.edit(&input), String::from_utf8(output).unwrap());
+    }
+}
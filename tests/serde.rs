@@ -0,0 +1,98 @@
+#![cfg(feature = "serde")]
+
+use linurgy::{factory, Editor, NewlineType, TriggerMode};
+
+#[test]
+fn newline_type_as_string() {
+    assert_eq!("\"lf\"", serde_json::to_string(&NewlineType::Lf).unwrap());
+    assert_eq!(
+        "\"crlf\"",
+        serde_json::to_string(&NewlineType::Crlf).unwrap()
+    );
+    assert_eq!(
+        "\"lineseparator\"",
+        serde_json::to_string(&NewlineType::LineSeparator).unwrap()
+    );
+    assert_eq!(
+        "\"paragraphseparator\"",
+        serde_json::to_string(&NewlineType::ParagraphSeparator).unwrap()
+    );
+    assert_eq!(
+        "\"auto\"",
+        serde_json::to_string(&NewlineType::Auto).unwrap()
+    );
+}
+
+#[test]
+fn newline_type_from_string() {
+    let lf: NewlineType = serde_json::from_str("\"lf\"").unwrap();
+    let crlf: NewlineType = serde_json::from_str("\"crlf\"").unwrap();
+
+    assert_eq!(NewlineType::Lf, lf);
+    assert_eq!(NewlineType::Crlf, crlf);
+}
+
+#[test]
+fn editor_round_trip() {
+    let editor = factory::appender("---", 2);
+
+    let json = serde_json::to_string(&editor).unwrap();
+
+    let actual: Editor = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(editor, actual);
+}
+
+#[test]
+fn editor_round_trip_crlf_same_output() {
+    let input = "foo\r\n\r\nbar\r\nbaz\r\n\r\n";
+
+    let editor = factory::inserter_crlf("--", 2);
+
+    let json = serde_json::to_string(&editor).unwrap();
+
+    let actual: Editor = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(editor.edit(input), actual.edit(input));
+}
+
+#[test]
+fn trigger_mode_between_as_map() {
+    let mode = TriggerMode::Between(3);
+    let json = serde_json::to_string(&mode).unwrap();
+
+    assert_eq!("{\"between\":3}", json);
+    assert_eq!(mode, serde_json::from_str(&json).unwrap());
+    assert_eq!(
+        "\"at_most\"",
+        serde_json::to_string(&TriggerMode::AtMost).unwrap()
+    );
+}
+
+#[test]
+fn line_options_as_editor_fields() {
+    let editor = factory::appender(";", 1)
+        .trim_trailing_whitespace(true)
+        .trim_leading_newlines(true)
+        .append_at_eof(true);
+
+    let mut json = serde_json::to_value(&editor).unwrap();
+    assert_eq!(true, json["trim_trailing"]);
+    assert_eq!(false, json["trim_leading"]);
+    assert_eq!(true, json["trim_leading_newlines"]);
+    assert_eq!(false, json["trim_trailing_newlines"]);
+    assert_eq!(true, json["append_eof"]);
+    assert_eq!(editor, serde_json::from_value(json.clone()).unwrap());
+
+    for field in &[
+        "trim_trailing",
+        "trim_leading",
+        "trim_leading_newlines",
+        "trim_trailing_newlines",
+        "append_eof",
+    ] {
+        json.as_object_mut().unwrap().remove(*field);
+    }
+    let actual: Editor = serde_json::from_value(json).unwrap();
+    assert_eq!(factory::appender(";", 1), actual);
+}
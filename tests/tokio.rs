@@ -0,0 +1,82 @@
+#![cfg(feature = "tokio")]
+
+use linurgy::{factory, Editor, NewlineType};
+use tokio::io::BufReader;
+
+#[tokio::test]
+async fn extra_line() {
+    let input = "foo\nbar\nbaz\n";
+
+    let expected = "foo\n\nbar\n\nbaz\n\n";
+
+    let mut input_buf = BufReader::new(input.as_bytes());
+    let mut output_buf = Vec::<u8>::with_capacity(input.len());
+
+    let res = factory::appender("\n", 1)
+        .edit_buffered_async(&mut input_buf, &mut output_buf)
+        .await;
+
+    assert!(res.is_ok());
+
+    let actual = String::from_utf8_lossy(&output_buf);
+
+    assert_eq!(expected, actual);
+}
+
+#[tokio::test]
+async fn extra_line_crlf() {
+    let input = "foo\r\nbar\r\nbaz\r\n";
+
+    let expected = "foo\r\n\r\nbar\r\n\r\nbaz\r\n\r\n";
+
+    let mut input_buf = BufReader::new(input.as_bytes());
+    let mut output_buf = Vec::<u8>::with_capacity(input.len());
+
+    let res = factory::appender_crlf("\r\n", 1)
+        .edit_buffered_async(&mut input_buf, &mut output_buf)
+        .await;
+
+    assert!(res.is_ok());
+
+    let actual = String::from_utf8_lossy(&output_buf);
+
+    assert_eq!(expected, actual);
+}
+
+#[tokio::test]
+async fn same_as_sync() {
+    let input = "foo\n\n\nbar\n\nbaz";
+
+    let editor = factory::inserter("--", 2);
+
+    let mut input_buf = BufReader::new(input.as_bytes());
+    let mut output_buf = Vec::<u8>::new();
+
+    editor
+        .edit_buffered_async(&mut input_buf, &mut output_buf)
+        .await
+        .unwrap();
+
+    assert_eq!(editor.edit(input), String::from_utf8_lossy(&output_buf));
+}
+
+#[tokio::test]
+async fn replace_line_separator() {
+    let input = "foo\u{2028}bar\u{2028}\u{2028}baz";
+
+    let expected = "foo bar  baz";
+
+    let mut input_buf = BufReader::with_capacity(4, input.as_bytes());
+    let mut output_buf = Vec::<u8>::with_capacity(input.len());
+
+    let editor = Editor::new(" ".to_string(), 1, NewlineType::LineSeparator);
+    let res = editor
+        .edit_buffered_async(&mut input_buf, &mut output_buf)
+        .await;
+
+    assert!(res.is_ok());
+
+    let actual = String::from_utf8_lossy(&output_buf);
+
+    assert_eq!(expected, actual);
+}